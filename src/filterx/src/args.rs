@@ -2,6 +2,24 @@ use clap::{ArgAction, Args, Parser, Subcommand, ValueHint};
 use filterx_core::reader::FileContentType;
 use filterx_source::{FastaRecordType, QualityType};
 
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum StatsFormat {
+    Table,
+    Tsv,
+    Json,
+}
+
+/// Where the barcodes of `demux` are read.
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum BarcodeFrom {
+    /// the start of reads
+    Seq,
+    /// the last field of the comment, e.g. `1:N:0:ACGTACGT+GGTTAACC`
+    Comm,
+    /// the index reads given by --index
+    Index,
+}
+
 static LONG_ABOUT: &'static str = include_str!("./long.txt");
 
 #[derive(Debug, Clone, Parser)]
@@ -46,8 +64,22 @@ pub enum Command {
     /// handle gtf file
     GTF(GFFCommand),
 
+    /// handle genbank/embl file
+    #[command(name = "genbank", visible_aliases = ["gb", "embl"])]
+    GenBank(GenBankCommand),
+
     /// builtin function help
     Info(InfoArgs),
+
+    /// summary of fasta/fastq file, e.g. N50, GC content, Q20/Q30
+    #[command(visible_alias = "st")]
+    Stats(StatsArgs),
+
+    /// demultiplex fastq files by the barcodes of samples
+    Demux(DemuxArgs),
+
+    /// merge overlapping paired-end reads into single reads
+    Merge(MergeArgs),
 }
 
 pub fn set_thread_size(thread_size: Option<usize>) -> () {
@@ -62,14 +94,26 @@ pub fn set_thread_size(thread_size: Option<usize>) -> () {
 
 #[derive(Debug, Clone, Args)]
 pub struct ShareArgs {
-    /// The input string
-    #[clap(value_hint=ValueHint::FilePath)]
-    pub input: String,
+    /// The input files, glob patterns e.g. 'data/*.fq.gz' are supported
+    #[clap(value_hint=ValueHint::FilePath, required = true)]
+    pub input: Vec<String>,
+
+    /// add a column with the name of the input file of every row, default name is `__file__`
+    #[clap(long, num_args = 0..=1, default_missing_value = "__file__")]
+    pub source_column: Option<String>,
 
     /// expression to filter
     #[clap(short = 'e', long, action = ArgAction::Append)]
     pub expr: Option<Vec<String>>,
 
+    /// script file with one statement per line, evaluated before `-e`
+    #[clap(long, value_hint=ValueHint::FilePath)]
+    pub script: Option<String>,
+
+    /// define a variable used in expression, e.g. -D min_len=100
+    #[clap(short = 'D', long, action = ArgAction::Append)]
+    pub define: Option<Vec<String>>,
+
     /// The output file, default is stdout.
     #[clap(short='o', long, value_hint=ValueHint::FilePath)]
     pub output: Option<String>,
@@ -85,6 +129,18 @@ pub struct ShareArgs {
     /// sql string
     #[clap(long)]
     pub sql: Option<String>,
+
+    /// split output into one file per value of the column, -o should contain `{}`, e.g. -o out_{}.fq.gz
+    #[clap(long, requires = "output", conflicts_with = "split_size")]
+    pub split_by: Option<String>,
+
+    /// split output into files of N records, -o should contain `{}`
+    #[clap(long, requires = "output")]
+    pub split_size: Option<usize>,
+
+    /// max number of output files opened at the same time when splitting output
+    #[clap(long, default_value = "64")]
+    pub max_open: Option<usize>,
 }
 
 #[derive(Debug, Clone, Parser)]
@@ -175,6 +231,14 @@ pub struct FastqCommand {
     /// detect quality type by first N sequences
     #[clap(long, default_value = "100")]
     pub detect_size: Option<usize>,
+
+    /// R2 files of paired reads, in the same order as the input files. Columns of R2 are `name2`, `comm2`, `seq2` and `qual2`
+    #[clap(long, num_args = 1.., value_hint=ValueHint::FilePath)]
+    pub r2: Option<Vec<String>>,
+
+    /// The output file of R2, pairs are interleaved in the output if not set
+    #[clap(long = "o2", requires = "r2", value_hint=ValueHint::FilePath)]
+    pub output2: Option<String>,
 }
 
 #[derive(Debug, Clone, Parser)]
@@ -204,6 +268,24 @@ pub struct GFFCommand {
     pub header: Option<bool>,
 }
 
+#[derive(Debug, Clone, Parser)]
+pub struct GenBankCommand {
+    #[clap(flatten)]
+    pub share_args: ShareArgs,
+
+    /// number of records per chunk
+    #[clap(short = 'c', long, default_value = "256")]
+    pub chunk: Option<usize>,
+
+    /// output the feature table, one row per feature
+    #[clap(long, default_value = "false", action = ArgAction::SetTrue)]
+    pub features: Option<bool>,
+
+    /// don't output the header line
+    #[clap(long = "no-header", action = ArgAction::SetTrue)]
+    pub no_header: Option<bool>,
+}
+
 #[derive(Debug, Clone, Parser)]
 pub struct InfoArgs {
     /// builtin function name
@@ -213,3 +295,132 @@ pub struct InfoArgs {
     #[clap(short='l', long, default_value = "false", action = ArgAction::SetTrue)]
     pub list: Option<bool>,
 }
+
+#[derive(Debug, Clone, Parser)]
+pub struct StatsArgs {
+    /// The input fasta/fastq files, glob patterns are supported, one row per file
+    #[clap(value_hint=ValueHint::FilePath, required = true)]
+    pub input: Vec<String>,
+
+    /// expression to filter, only the sequences left are counted
+    #[clap(short = 'e', long, action = ArgAction::Append)]
+    pub expr: Option<Vec<String>>,
+
+    /// script file with one statement per line, evaluated before `-e`
+    #[clap(long, value_hint=ValueHint::FilePath)]
+    pub script: Option<String>,
+
+    /// define a variable used in expression, e.g. -D min_len=100
+    #[clap(short = 'D', long, action = ArgAction::Append)]
+    pub define: Option<Vec<String>>,
+
+    /// The output file, default is stdout.
+    #[clap(short='o', long, value_hint=ValueHint::FilePath)]
+    pub output: Option<String>,
+
+    /// only works with -o.
+    #[clap(long, alias = "ot", default_value = "auto")]
+    pub output_type: Option<FileContentType>,
+
+    /// output format
+    #[clap(short = 'f', long, default_value = "table")]
+    pub format: Option<StatsFormat>,
+
+    /// number of sequence per chunk, only works with -e
+    #[clap(short = 'c', long, default_value = "4096")]
+    pub chunk: Option<usize>,
+
+    /// quality type of fastq, phred33, phred64, auto
+    #[clap(long, default_value = "auto")]
+    pub phred: Option<QualityType>,
+}
+
+#[derive(Debug, Clone, Parser)]
+pub struct DemuxArgs {
+    /// The input fastq files, glob patterns are supported
+    #[clap(value_hint=ValueHint::FilePath, required = true)]
+    pub input: Vec<String>,
+
+    /// barcode sheet, csv or tsv with columns: sample, barcode[, barcode2]
+    #[clap(short = 's', long, value_hint=ValueHint::FilePath)]
+    pub sheet: String,
+
+    /// where to read the barcodes
+    #[clap(long, default_value = "seq")]
+    pub from: Option<BarcodeFrom>,
+
+    /// index read files, I1 and optionally I2, only works with --from index
+    #[clap(long, num_args = 1..=2, value_hint=ValueHint::FilePath)]
+    pub index: Option<Vec<String>>,
+
+    /// start of the barcode in reads, only works with --from seq
+    #[clap(long, default_value = "0")]
+    pub offset: Option<usize>,
+
+    /// cut the barcode and the bases before it off reads, only works with --from seq
+    #[clap(long, default_value = "false", action = ArgAction::SetTrue)]
+    pub trim: Option<bool>,
+
+    /// max number of mismatches of every barcode
+    #[clap(short = 'm', long, default_value = "1")]
+    pub mismatch: Option<usize>,
+
+    /// The output files, `{}` is replaced by the sample name or `undetermined`, e.g. -o 'out/{}.fq.gz'
+    #[clap(short='o', long, value_hint=ValueHint::FilePath)]
+    pub output: String,
+
+    /// only works with -o.
+    #[clap(long, alias = "ot", default_value = "auto")]
+    pub output_type: Option<FileContentType>,
+
+    /// max number of output files opened at the same time
+    #[clap(long, default_value = "64")]
+    pub max_open: Option<usize>,
+
+    /// write the number of reads of every sample to this file, default is stdout
+    #[clap(long, value_hint=ValueHint::FilePath)]
+    pub report: Option<String>,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct MergeArgs {
+    /// The R1 files, glob patterns are supported
+    #[clap(value_hint=ValueHint::FilePath, required = true)]
+    pub input: Vec<String>,
+
+    /// The R2 files, in the same order as the R1 files
+    #[clap(long, num_args = 1.., required = true, value_hint=ValueHint::FilePath)]
+    pub r2: Vec<String>,
+
+    /// min number of overlapping bases of R1 and R2
+    #[clap(long, default_value = "10")]
+    pub min_overlap: Option<usize>,
+
+    /// max ratio of mismatches in the overlap
+    #[clap(short = 'm', long, default_value = "0.1")]
+    pub mismatch: Option<f64>,
+
+    /// The output file of merged reads
+    #[clap(short = 'o', long, value_hint=ValueHint::FilePath)]
+    pub output: String,
+
+    /// The output file of R1 of unmerged pairs, unmerged pairs are dropped if not set
+    #[clap(long = "o1", value_hint=ValueHint::FilePath)]
+    pub output1: Option<String>,
+
+    /// The output file of R2 of unmerged pairs
+    #[clap(long = "o2", requires = "output1", value_hint=ValueHint::FilePath)]
+    pub output2: Option<String>,
+
+    /// only works with -o, --o1 and --o2.
+    #[clap(long, alias = "ot", default_value = "auto")]
+    pub output_type: Option<FileContentType>,
+
+    /// quality type of fastq, phred33, phred64, auto
+    #[clap(long, default_value = "auto")]
+    pub phred: Option<QualityType>,
+
+    /// write the number of merged pairs to this file, default is stdout
+    #[clap(long, value_hint=ValueHint::FilePath)]
+    pub report: Option<String>,
+}
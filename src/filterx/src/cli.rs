@@ -1,11 +1,15 @@
 use crate::args::{Cli, Command};
+use crate::demux::filterx_demux;
 use crate::files::csv::filterx_csv;
 use crate::files::fasta::filterx_fasta;
 use crate::files::fastq::filterx_fastq;
+use crate::files::genbank::filterx_genbank;
 use crate::files::gxf::{filterx_gxf, GxfType};
 use crate::files::sam::filterx_sam;
 use crate::files::vcf::filterx_vcf;
 use crate::info::filterx_info;
+use crate::merge::filterx_merge;
+use crate::stats::filterx_stats;
 use filterx_core::thread_size::ThreadSize;
 
 use clap::Parser;
@@ -31,7 +35,16 @@ fn enable_debug() {
 }
 
 pub fn cli() -> FilterxResult<()> {
-    let parser = Cli::parse();
+    cli_from(std::env::args_os())
+}
+
+/// Run the command line with the given arguments, the first one is the program name.
+pub fn cli_from<I, T>(args: I) -> FilterxResult<()>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<std::ffi::OsString> + Clone,
+{
+    let parser = Cli::parse_from(args);
     set_thread(parser.threads);
     // enable_debug();
     match parser.command {
@@ -42,6 +55,10 @@ pub fn cli() -> FilterxResult<()> {
         Command::Vcf(cmd) => filterx_vcf(cmd),
         Command::GFF(cmd) => filterx_gxf(cmd, GxfType::Gff),
         Command::GTF(cmd) => filterx_gxf(cmd, GxfType::Gtf),
+        Command::GenBank(cmd) => filterx_genbank(cmd),
         Command::Info(cmd) => filterx_info(cmd),
+        Command::Stats(cmd) => filterx_stats(cmd),
+        Command::Demux(cmd) => filterx_demux(cmd),
+        Command::Merge(cmd) => filterx_merge(cmd),
     }
 }
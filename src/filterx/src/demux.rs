@@ -0,0 +1,454 @@
+use std::collections::HashMap;
+use std::io::{BufRead, Write};
+
+use crate::args::{BarcodeFrom, DemuxArgs};
+
+use filterx_core::{
+    reader::FilterxReader,
+    util,
+    writer::{FilterxWriter, SplitWriter},
+    FilterxResult, Hint,
+};
+use filterx_engine::eval::call::builtin::sequence::align::hamming_bytes;
+use filterx_source::block::fastq::Fastq;
+use filterx_source::{FastqSource, QualityType};
+
+/// Output of reads without a sample.
+const UNDETERMINED: &str = "undetermined";
+
+struct Barcode {
+    /// index of the sample
+    sample: usize,
+    seq: Vec<u8>,
+    seq2: Option<Vec<u8>>,
+}
+
+/// The barcode sheet, one line per barcode: sample, barcode[, barcode2].
+/// A sample may have more than one barcode.
+struct Sheet {
+    samples: Vec<String>,
+    barcodes: Vec<Barcode>,
+    exact: HashMap<(Vec<u8>, Vec<u8>), usize>,
+    mismatch: usize,
+}
+
+fn sheet_error<T>(path: &str, line: usize, msg: &str) -> FilterxResult<T> {
+    let mut h = Hint::new();
+    h.white("demux: ")
+        .cyan(&format!("{}:{}", path, line))
+        .white(": ")
+        .white(msg)
+        .bail()
+}
+
+fn is_barcode(s: &str) -> bool {
+    !s.is_empty() && s.bytes().all(|b| b"ACGTNacgtn".contains(&b))
+}
+
+impl Sheet {
+    fn from_path(path: &str, mismatch: usize) -> FilterxResult<Self> {
+        let reader = FilterxReader::new(path)?;
+        let mut sheet = Sheet {
+            samples: vec![],
+            barcodes: vec![],
+            exact: HashMap::new(),
+            mismatch,
+        };
+        let mut first = true;
+        for (i, line) in reader.lines().enumerate() {
+            let line = line?;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let separator = if line.contains('\t') { '\t' } else { ',' };
+            let fields: Vec<&str> = line.split(separator).map(|f| f.trim()).collect();
+            if fields.len() < 2 {
+                return sheet_error(path, i + 1, "expected columns: sample, barcode[, barcode2]");
+            }
+            // the first line may be a header
+            let skip_header = first && !is_barcode(fields[1]);
+            first = false;
+            if skip_header {
+                continue;
+            }
+            let (name, seq, seq2) = (fields[0], fields[1], fields.get(2).copied());
+            if name == UNDETERMINED {
+                return sheet_error(
+                    path,
+                    i + 1,
+                    "`undetermined` is reserved for reads without a sample",
+                );
+            }
+            if !is_barcode(seq) || !seq2.is_none_or(is_barcode) {
+                return sheet_error(path, i + 1, "barcodes should only contain A, C, G, T and N");
+            }
+            let sample = match sheet.samples.iter().position(|s| s == name) {
+                Some(sample) => sample,
+                None => {
+                    sheet.samples.push(name.to_string());
+                    sheet.samples.len() - 1
+                }
+            };
+            let barcode = Barcode {
+                sample,
+                seq: seq.to_ascii_uppercase().into_bytes(),
+                seq2: seq2.map(|s| s.to_ascii_uppercase().into_bytes()),
+            };
+            if let Some(other) = sheet.barcodes.first() {
+                if other.seq.len() != barcode.seq.len()
+                    || other.seq2.as_ref().map(|s| s.len())
+                        != barcode.seq2.as_ref().map(|s| s.len())
+                {
+                    return sheet_error(path, i + 1, "all barcodes should have the same length");
+                }
+            }
+            let key = (
+                barcode.seq.clone(),
+                barcode.seq2.clone().unwrap_or_default(),
+            );
+            if let Some(&other) = sheet.exact.get(&key) {
+                if sheet.barcodes[other].sample != sample {
+                    return sheet_error(path, i + 1, "the barcode is used by another sample");
+                }
+                // a repeated line
+                continue;
+            }
+            sheet.exact.insert(key, sheet.barcodes.len());
+            sheet.barcodes.push(barcode);
+        }
+        if sheet.barcodes.is_empty() {
+            return sheet_error(path, 1, "no barcode found");
+        }
+        Ok(sheet)
+    }
+
+    fn barcode_len(&self) -> usize {
+        self.barcodes[0].seq.len()
+    }
+
+    fn has_barcode2(&self) -> bool {
+        self.barcodes[0].seq2.is_some()
+    }
+
+    /// The sample of a read, none if no barcode is within `mismatch`, or the
+    /// best barcodes belong to different samples.
+    fn assign(&self, seq: &[u8], seq2: Option<&[u8]>) -> Option<usize> {
+        let seq2 = seq2.filter(|_| self.has_barcode2());
+        let key = (
+            seq.to_ascii_uppercase(),
+            seq2.unwrap_or_default().to_ascii_uppercase(),
+        );
+        if let Some(&i) = self.exact.get(&key) {
+            return Some(self.barcodes[i].sample);
+        }
+        let distance = |barcode: &[u8], read: Option<&[u8]>| {
+            hamming_bytes(barcode, read?).filter(|&d| d <= self.mismatch)
+        };
+        let mut best: Option<(usize, usize)> = None;
+        let mut ambiguous = false;
+        for barcode in &self.barcodes {
+            let d = match (distance(&barcode.seq, Some(seq)), &barcode.seq2) {
+                (Some(d), None) => d,
+                (Some(d), Some(barcode2)) => match distance(barcode2, seq2) {
+                    Some(d2) => d + d2,
+                    None => continue,
+                },
+                (None, _) => continue,
+            };
+            match best {
+                Some((best_d, sample)) if d == best_d => ambiguous |= sample != barcode.sample,
+                Some((best_d, _)) if d > best_d => {}
+                _ => {
+                    best = Some((d, barcode.sample));
+                    ambiguous = false;
+                }
+            }
+        }
+        match ambiguous {
+            true => None,
+            false => best.map(|(_, sample)| sample),
+        }
+    }
+
+    /// Barcodes of a sample, dual barcodes are written as `ACGT+GGTT`.
+    fn sample_barcodes(&self, sample: usize) -> String {
+        self.barcodes
+            .iter()
+            .filter(|b| b.sample == sample)
+            .map(|b| {
+                let mut s = String::from_utf8_lossy(&b.seq).to_string();
+                if let Some(seq2) = &b.seq2 {
+                    s.push('+');
+                    s.push_str(&String::from_utf8_lossy(seq2));
+                }
+                s
+            })
+            .collect::<Vec<_>>()
+            .join(";")
+    }
+}
+
+/// Barcodes in the last field of an Illumina comment, e.g. `1:N:0:ACGT+GGTT`.
+fn comment_barcodes(comment: &str) -> (&str, Option<&str>) {
+    let field = comment
+        .split_whitespace()
+        .last()
+        .and_then(|f| f.rsplit(':').next())
+        .unwrap_or("");
+    match field.split_once('+') {
+        Some((seq, seq2)) => (seq, Some(seq2)),
+        None => (field, None),
+    }
+}
+
+fn write_report(path: Option<String>, sheet: &Sheet, counts: &[u64]) -> FilterxResult<()> {
+    let mut writer = FilterxWriter::new(path, None, None)?;
+    let total: u64 = counts.iter().sum();
+    let percent = |n: u64| match total {
+        0 => 0.0,
+        _ => n as f64 * 100.0 / total as f64,
+    };
+    writeln!(writer, "sample\tbarcode\treads\tpercent")?;
+    for (i, sample) in sheet.samples.iter().enumerate() {
+        writeln!(
+            writer,
+            "{}\t{}\t{}\t{:.2}",
+            sample,
+            sheet.sample_barcodes(i),
+            counts[i],
+            percent(counts[i])
+        )?;
+    }
+    let undetermined = counts[sheet.samples.len()];
+    writeln!(
+        writer,
+        "{}\t-\t{}\t{:.2}",
+        UNDETERMINED,
+        undetermined,
+        percent(undetermined)
+    )?;
+    writer.flush()?;
+    Ok(())
+}
+
+fn demux_error<T>(msg: &str, flag: &str) -> FilterxResult<T> {
+    let mut h = Hint::new();
+    h.white("demux: ").white(msg).cyan(flag).white(".").bail()
+}
+
+pub fn filterx_demux(cmd: DemuxArgs) -> FilterxResult<()> {
+    let DemuxArgs {
+        input,
+        sheet,
+        from,
+        index,
+        offset,
+        trim,
+        mismatch,
+        output,
+        output_type,
+        max_open,
+        report,
+    } = cmd;
+
+    let sheet = Sheet::from_path(&sheet, mismatch.unwrap())?;
+    let from = from.unwrap();
+    let offset = offset.unwrap();
+    let trim = trim.unwrap();
+    let paths = util::expand_paths(&input)?;
+    match from {
+        BarcodeFrom::Seq if sheet.has_barcode2() => {
+            return demux_error(
+                "the second barcode can not be read from reads, use ",
+                "--from comm or --from index",
+            )
+        }
+        BarcodeFrom::Index if index.is_none() => {
+            return demux_error("index reads are needed by ", "--index")
+        }
+        BarcodeFrom::Index if paths.len() > 1 => {
+            return demux_error("only one input file is supported with ", "--index")
+        }
+        BarcodeFrom::Index if index.as_ref().unwrap().len() < 1 + sheet.has_barcode2() as usize => {
+            return demux_error(
+                "the second barcode needs the I2 file, e.g. ",
+                "--index I1.fq.gz I2.fq.gz",
+            )
+        }
+        BarcodeFrom::Seq | BarcodeFrom::Comm if index.is_some() => {
+            return demux_error("index reads only work with ", "--from index")
+        }
+        BarcodeFrom::Comm | BarcodeFrom::Index if trim => {
+            return demux_error("--trim only works with ", "--from seq")
+        }
+        _ => {}
+    }
+
+    let mut indexes = index
+        .unwrap_or_default()
+        .iter()
+        .map(|path| Fastq::from_path(path, QualityType::Phred33, 0))
+        .collect::<FilterxResult<Vec<_>>>()?;
+    let mut source = FastqSource::new(&paths[0], true, true, QualityType::Phred33, 0)?;
+    source.set_next_paths(paths[1..].to_vec());
+    let mut writer = SplitWriter::new(&output, output_type, max_open.unwrap())?;
+    // every sample has an output file, even if no read is assigned
+    for sample in sheet
+        .samples
+        .iter()
+        .map(|s| s.as_str())
+        .chain([UNDETERMINED])
+    {
+        writer.get(sample)?;
+    }
+    // the last one is undetermined
+    let mut counts = vec![0u64; sheet.samples.len() + 1];
+    let barcode_end = offset + sheet.barcode_len();
+    let mut index_seqs: Vec<String> = vec![];
+
+    loop {
+        while let Some(record) = source.fastq.parse_next()? {
+            index_seqs.clear();
+            for fastq in indexes.iter_mut() {
+                match fastq.parse_next()? {
+                    Some(index) => index_seqs.push(index.seq().to_string()),
+                    None => {
+                        writer.flush()?;
+                        return demux_error("index reads are fewer than reads in ", "--index");
+                    }
+                }
+            }
+            let sample = match from {
+                BarcodeFrom::Seq => record
+                    .seq()
+                    .as_bytes()
+                    .get(offset..barcode_end)
+                    .and_then(|seq| sheet.assign(seq, None)),
+                BarcodeFrom::Comm => record.comment().and_then(|comment| {
+                    let (seq, seq2) = comment_barcodes(comment);
+                    sheet.assign(seq.as_bytes(), seq2.map(|s| s.as_bytes()))
+                }),
+                BarcodeFrom::Index => sheet.assign(
+                    index_seqs[0].as_bytes(),
+                    index_seqs.get(1).map(|s| s.as_bytes()),
+                ),
+            };
+            counts[sample.unwrap_or(sheet.samples.len())] += 1;
+            let key = sample.map_or(UNDETERMINED, |i| sheet.samples[i].as_str());
+            let (writer, _) = writer.get(key)?;
+            if !trim {
+                writeln!(writer, "{}", record.format())?;
+                continue;
+            }
+            let seq = record.seq();
+            let qual = record.qual().unwrap_or("");
+            write!(writer, "@{}", record.name())?;
+            if let Some(comment) = record.comment() {
+                write!(writer, " {}", comment)?;
+            }
+            write!(
+                writer,
+                "\n{}\n+\n{}\n",
+                &seq[barcode_end.min(seq.len())..],
+                &qual[barcode_end.min(qual.len())..]
+            )?;
+        }
+        if !source.next_file()? {
+            break;
+        }
+    }
+    writer.flush()?;
+    for fastq in indexes.iter_mut() {
+        if fastq.parse_next()?.is_some() {
+            return demux_error("index reads are more than reads in ", "--index");
+        }
+    }
+    write_report(report, &sheet, &counts)
+}
+
+#[test]
+fn test_sheet() {
+    let dir = crate::test_util::TempDir::new("demux");
+    let sheet = |name: &str, content: &str| Sheet::from_path(&dir.write(name, content), 1);
+
+    // a header line, a sample with two barcodes
+    let s = sheet(
+        "header.csv",
+        "sample,barcode\nS1,ACGTACGT\nS2,ttggccaa\nS1,GGGGAAAA\n",
+    )
+    .unwrap();
+    assert_eq!(s.samples, vec!["S1", "S2"]);
+    assert_eq!(s.barcode_len(), 8);
+    assert!(!s.has_barcode2());
+    assert_eq!(s.sample_barcodes(0), "ACGTACGT;GGGGAAAA");
+    assert_eq!(s.sample_barcodes(1), "TTGGCCAA");
+
+    // tsv without a header, dual barcodes, a repeated line
+    let s = sheet(
+        "dual.tsv",
+        "# comment\nS1\tACGT\tGGTT\nS2\tTTAA\tCCGG\nS1\tACGT\tGGTT\n",
+    )
+    .unwrap();
+    assert_eq!(s.samples, vec!["S1", "S2"]);
+    assert!(s.has_barcode2());
+    assert_eq!(s.sample_barcodes(0), "ACGT+GGTT");
+
+    // the same barcode for two samples
+    assert!(sheet("conflict.csv", "S1,ACGT\nS2,ACGT\n").is_err());
+    // barcodes of different lengths
+    assert!(sheet("length.csv", "S1,ACGT\nS2,ACGTA\n").is_err());
+    assert!(sheet("length2.csv", "S1,ACGT,GG\nS2,TTAA\n").is_err());
+    assert!(sheet("base.csv", "S1,ACGT\nS2,ACXT\n").is_err());
+    assert!(sheet("columns.csv", "S1,ACGT\nS2\n").is_err());
+    assert!(sheet("reserved.csv", "undetermined,ACGT\n").is_err());
+    assert!(sheet("empty.csv", "sample,barcode\n").is_err());
+}
+
+#[test]
+fn test_assign() {
+    let dir = crate::test_util::TempDir::new("demux");
+    let path = dir.write("assign.csv", "S1,ACGTACGT\nS2,TTGGCCAA\nS3,ACGTACGA\n");
+    let s = Sheet::from_path(&path, 1).unwrap();
+    // exact matches win over barcodes within 1 mismatch
+    assert_eq!(s.assign(b"ACGTACGT", None), Some(0));
+    assert_eq!(s.assign(b"acgtacga", None), Some(2));
+    assert_eq!(s.assign(b"TTGGCCAT", None), Some(1));
+    assert_eq!(s.assign(b"TTGGCCTT", None), None);
+    // 1 mismatch to both S1 and S3
+    assert_eq!(s.assign(b"ACGTACGC", None), None);
+    let s = Sheet::from_path(&path, 2).unwrap();
+    assert_eq!(s.assign(b"TTGGCCTT", None), Some(1));
+
+    let path = dir.write("assign_dual.csv", "S1,ACGT,GGTT\nS2,TTAA,CCGG\n");
+    let s = Sheet::from_path(&path, 1).unwrap();
+    assert_eq!(s.assign(b"ACGT", Some(b"GGTT")), Some(0));
+    assert_eq!(s.assign(b"ACGA", Some(b"GGTT")), Some(0));
+    assert_eq!(s.assign(b"TTAA", Some(b"CCGA")), Some(1));
+    assert_eq!(s.assign(b"ACGT", Some(b"CCGG")), None);
+    assert_eq!(s.assign(b"ACGT", None), None);
+}
+
+#[test]
+fn test_index_reads() {
+    let fastq = |n: usize| {
+        (0..n)
+            .map(|i| format!("@r{}\nACGTACGT\n+\nIIIIIIII\n", i))
+            .collect::<String>()
+    };
+    let dir = crate::test_util::TempDir::new("demux");
+    let sheet = dir.write("index.csv", "S1,ACGT\n");
+    let reads = dir.write("index_reads.fq", &fastq(2));
+    let (output, report) = (dir.path("index_{}.fq"), dir.path("index_report.tsv"));
+    let demux = |n: usize| {
+        let index = dir.write("index_i1.fq", &fastq(n));
+        let args = [
+            "filterx", "demux", &reads, "-s", &sheet, "--from", "index", "--index", &index, "-o",
+            &output, "--report", &report,
+        ];
+        crate::cli::cli_from(args)
+    };
+    assert!(demux(2).is_ok());
+    assert!(demux(1).is_err());
+    assert!(demux(3).is_err());
+}
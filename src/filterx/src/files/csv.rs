@@ -1,3 +1,4 @@
+use super::Split;
 use crate::args::{CsvCommand, ShareArgs};
 use filterx_core::{util, writer::FilterxWriter, FilterxResult};
 use filterx_engine::vm::Vm;
@@ -7,12 +8,18 @@ pub fn filterx_csv(cmd: CsvCommand) -> FilterxResult<()> {
     let CsvCommand {
         share_args:
             ShareArgs {
-                input: path,
+                input,
+                source_column,
                 expr,
+                script,
+                define,
                 output,
                 table,
                 output_type,
                 sql,
+                split_by,
+                split_size,
+                max_open,
             },
         header,
         no_header,
@@ -22,6 +29,8 @@ pub fn filterx_csv(cmd: CsvCommand) -> FilterxResult<()> {
         skip,
         limit,
     } = cmd;
+    let paths = util::expand_paths(&input)?;
+    let path = &paths[0];
     let separator = match separator {
         Some(s) => Some(s),
         None => match util::detect_separator(path.as_str(), 20, skip, comment_prefix.clone())? {
@@ -53,26 +62,41 @@ pub fn filterx_csv(cmd: CsvCommand) -> FilterxResult<()> {
         None => None,
     };
     let comment_prefix = comment_prefix.unwrap();
-    let writer = FilterxWriter::new(output.clone(), None, output_type)?;
-    let lazy_df = util::init_df(
-        path.as_str(),
-        header.unwrap(),
-        &comment_prefix,
-        separator.as_deref(),
-        skip.unwrap(),
-        limit,
-        None,
-        None,
-        true,
-    )?;
+    let mut split = Split::new(&output, output_type, split_by, split_size, max_open)?;
+    // output goes to split files, the writer is only used by `print`
+    let writer_path = if split.is_some() {
+        None
+    } else {
+        output.clone()
+    };
+    let writer = FilterxWriter::new(writer_path, None, output_type)?;
+    let mut frames = Vec::with_capacity(paths.len());
+    for path in &paths {
+        let lazy_df = util::init_df(
+            path.as_str(),
+            header.unwrap(),
+            &comment_prefix,
+            separator.as_deref(),
+            skip.unwrap(),
+            limit,
+            None,
+            None,
+            true,
+        )?;
+        frames.push((path.clone(), lazy_df));
+    }
+    let lazy_df = util::concat_inputs(frames, source_column.as_deref())?;
     let columns = detect_columns(lazy_df.clone())?;
     let mut s = DataframeSource::new(lazy_df.clone());
     s.set_has_header(header.unwrap());
     s.set_init_column_names(&columns);
     let mut vm = Vm::from_source(Source::new(s.into(), SourceType::Csv), writer);
+    vm.set_variables(util::parse_defines(define, &vm.source().ret_column_names)?);
     vm.source_mut().set_has_header(header.unwrap());
-    let expr = util::merge_expr(expr);
-    vm.eval_once(&expr, sql)?;
+    let expr = util::merge_expr(expr, script)?;
+    if super::eval_once(&mut vm, &expr, sql)? {
+        return Ok(());
+    }
     if vm.status.printed {
         return Ok(());
     }
@@ -85,6 +109,18 @@ pub fn filterx_csv(cmd: CsvCommand) -> FilterxResult<()> {
     if vm.status.printed {
         return Ok(());
     }
+    if let Some(split) = split.as_mut() {
+        return split.write_df(&df, |part, writer, created| {
+            util::write_df(
+                part,
+                writer,
+                output_header.unwrap() && created,
+                output_separator.as_deref(),
+                None,
+                None,
+            )
+        });
+    }
     util::write_df(
         &mut df,
         &mut vm.writer,
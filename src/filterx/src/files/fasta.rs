@@ -1,5 +1,6 @@
 use crate::args::{FastaCommand, ShareArgs};
 
+use super::Split;
 use filterx_core::{util, writer::FilterxWriter, FilterxResult};
 use filterx_engine::vm::Vm;
 use filterx_source::{FastaSource, Source, SourceType};
@@ -10,12 +11,18 @@ pub fn filterx_fasta(cmd: FastaCommand) -> FilterxResult<()> {
     let FastaCommand {
         share_args:
             ShareArgs {
-                input: path,
+                input,
+                source_column,
                 expr,
+                script,
+                define,
                 output,
-                table: _,
+                table,
                 output_type,
                 sql,
+                split_by,
+                split_size,
+                max_open,
             },
         chunk: long,
         no_comment,
@@ -40,35 +47,58 @@ pub fn filterx_fasta(cmd: FastaCommand) -> FilterxResult<()> {
         _ => vec!["name", "comm", "seq"],
     };
 
-    let names = names.iter().map(|x| x.to_string()).collect();
+    let mut names: Vec<String> = names.iter().map(|x| x.to_string()).collect();
+    if let Some(name) = &source_column {
+        names.push(name.clone());
+    }
+    let paths = util::expand_paths(&input)?;
 
-    let expr = util::merge_expr(expr);
+    let expr = util::merge_expr(expr, script)?;
     let mut source = FastaSource::new(
-        path.as_str(),
+        paths[0].as_str(),
         !no_comment.unwrap(),
         r#type.unwrap(),
         detect_size.unwrap(),
     )?;
-    let mut writer = FilterxWriter::new(output.clone(), None, output_type)?;
-    if expr.is_empty() && sql.is_none() {
-        while let Some(record) = &mut source.fasta.parse_next()? {
-            writeln!(writer, "{}", record.format())?;
+    source.set_next_paths(paths[1..].to_vec());
+    source.set_source_column(source_column);
+    let mut split = Split::new(&output, output_type, split_by, split_size, max_open)?;
+    // output goes to split files, the writer is only used by `print`
+    let writer_path = if split.is_some() {
+        None
+    } else {
+        output.clone()
+    };
+    let mut writer = FilterxWriter::new(writer_path, None, output_type)?;
+    if expr.is_empty() && sql.is_none() && split.is_none() {
+        loop {
+            while let Some(record) = &mut source.fasta.parse_next()? {
+                writeln!(writer, "{}", record.format())?;
+            }
+            if !source.next_file()? {
+                return Ok(());
+            }
         }
-        return Ok(());
     }
     let chunk_size = long.unwrap();
     let mut vm = Vm::from_source(Source::new(source.into(), SourceType::Fasta), writer);
     vm.source.df_source_mut().set_init_column_names(&names);
+    vm.set_variables(util::parse_defines(define, &vm.source().ret_column_names)?);
     vm.status.set_chunk_size(chunk_size);
     'stop_parse: loop {
         let left = vm.next_batch()?;
         if left.is_none() {
             break 'stop_parse;
         }
-        vm.eval_once(&expr, sql.clone())?;
+        if super::eval_once(&mut vm, &expr, sql.clone())? {
+            return Ok(());
+        }
         if !vm.status.printed {
             let df = vm.into_df()?;
-            let writer = &mut vm.writer;
+            let keys = match split.as_ref() {
+                Some(split) => split.keys(&df, vm.status.consume_rows)?,
+                None => vec![],
+            };
             let cols = df.get_columns();
             let seq_col = cols.iter().position(|x| x.name() == "seq");
             let name_col = cols.iter().position(|x| x.name() == "name");
@@ -108,6 +138,10 @@ pub fn filterx_fasta(cmd: FastaCommand) -> FilterxResult<()> {
                     break 'stop_parse;
                 }
                 vm.status.consume_rows += 1;
+                let writer = match (split.as_mut(), keys.get(i)) {
+                    (Some(split), Some(key)) => split.writer.get(key)?.0,
+                    _ => &mut vm.writer,
+                };
                 for col_index in &valid_cols {
                     let col = &cols[*col_index];
                     match col.name().as_str() {
@@ -134,5 +168,42 @@ pub fn filterx_fasta(cmd: FastaCommand) -> FilterxResult<()> {
             }
         }
     }
+    if let Some(split) = split.as_mut() {
+        split.writer.flush()?;
+    }
+    if let Some(mut df) = vm.finish_group()? {
+        if output.is_none() && table.unwrap_or(false) {
+            println!("{}", df);
+            return Ok(());
+        }
+        util::write_df(&mut df, &mut vm.writer, true, Some("\t"), None, None)?;
+    }
     Ok(())
 }
+
+#[test]
+fn test_group_by() {
+    let dir = crate::test_util::TempDir::new("group_by");
+    let path = |name: &str| dir.path(name);
+    let seqs = ["ACGT", "ACGTA", "A", "ACGTAC", "ACGT"];
+    let fasta: String = seqs
+        .iter()
+        .enumerate()
+        .map(|(i, seq)| format!(">s{}\n{}\n", i, seq))
+        .collect();
+    std::fs::write(path("in.fa"), fasta).unwrap();
+
+    // chunks of 2 sequences, every group spans more than one chunk
+    let (input, output) = (path("in.fa"), path("out.tsv"));
+    let expr = "alias(k) = 'long' if len(seq) > 4 else 'short'; group_by(k); \
+        alias(n) = count(); alias(total) = sum(len(seq)); alias(avg) = mean(len(seq)); \
+        alias(longest) = max(len(seq)); alias(uniq) = n_unique(seq)";
+    let args = [
+        "filterx", "fasta", &input, "-c", "2", "-e", expr, "-o", &output,
+    ];
+    crate::cli::cli_from(args).unwrap();
+    assert_eq!(
+        std::fs::read_to_string(&output).unwrap(),
+        "k\tn\ttotal\tavg\tlongest\tuniq\nshort\t3\t9\t3.000\t4\t2\nlong\t2\t11\t5.500\t6\t2\n"
+    );
+}
@@ -1,20 +1,48 @@
 use crate::args::{FastqCommand, ShareArgs};
 
-use filterx_core::{util, writer::FilterxWriter, FilterxResult};
+use super::Split;
+use filterx_core::{util, writer::FilterxWriter, FilterxResult, Hint};
 use filterx_engine::vm::Vm;
+use filterx_source::block::fastq::MATE_SUFFIX;
 use filterx_source::{FastqSource, Source, SourceType};
+use polars::prelude::Column;
 
 use std::io::Write;
+
+/// Write a field of the `i`th record, `field` is the column name without the
+/// suffix of R2. Return false for other columns.
+fn write_field(
+    writer: &mut FilterxWriter,
+    field: &str,
+    col: &Column,
+    i: usize,
+) -> FilterxResult<bool> {
+    let value = col.get(i).unwrap();
+    match field {
+        "name" => write!(writer, "@{}", value.get_str().unwrap_or(""))?,
+        "comm" => write!(writer, " {}", value.get_str().unwrap_or(""))?,
+        "seq" => write!(writer, "\n{}\n", value.get_str().unwrap_or(""))?,
+        "qual" => write!(writer, "+\n{}\n", value.get_str().unwrap_or("space"))?,
+        _ => return Ok(false),
+    }
+    Ok(true)
+}
 pub fn filterx_fastq(cmd: FastqCommand) -> FilterxResult<()> {
     let FastqCommand {
         share_args:
             ShareArgs {
-                input: path,
+                input,
+                source_column,
                 expr,
+                script,
+                define,
                 output,
-                table: _,
+                table,
                 output_type,
                 sql,
+                split_by,
+                split_size,
+                max_open,
             },
         chunk: long,
         no_comment,
@@ -22,6 +50,8 @@ pub fn filterx_fastq(cmd: FastqCommand) -> FilterxResult<()> {
         phred,
         limit,
         detect_size,
+        r2,
+        output2,
     } = cmd;
 
     let _limit = match limit {
@@ -53,35 +83,98 @@ pub fn filterx_fastq(cmd: FastqCommand) -> FilterxResult<()> {
         _ => {}
     }
 
-    let names = names.iter().map(|x| x.to_string()).collect::<Vec<String>>();
-    let expr = util::merge_expr(expr);
+    let mut names = names.iter().map(|x| x.to_string()).collect::<Vec<String>>();
+    let paired = r2.is_some();
+    if paired {
+        let mate_names: Vec<String> = names
+            .iter()
+            .map(|name| format!("{}{}", name, MATE_SUFFIX))
+            .collect();
+        names.extend(mate_names);
+    }
+    if let Some(name) = &source_column {
+        names.push(name.clone());
+    }
+    let paths = util::expand_paths(&input)?;
+    let expr = util::merge_expr(expr, script)?;
     let mut source = FastqSource::new(
-        path.as_str(),
+        paths[0].as_str(),
         !no_comment.unwrap(),
         !no_quality.unwrap(),
         phred.unwrap(),
         detect_size.unwrap(),
     )?;
-    let mut writer = FilterxWriter::new(output.clone(), None, output_type)?;
-    if expr.is_empty() && sql.is_none() {
-        while let Some(record) = &mut source.fastq.parse_next()? {
-            writeln!(writer, "{}", record.format())?;
+    source.set_next_paths(paths[1..].to_vec());
+    source.set_source_column(source_column);
+    if let Some(r2) = r2 {
+        let paths2 = util::expand_paths(&r2)?;
+        if paths2.len() != paths.len() {
+            let mut h = Hint::new();
+            return h
+                .white("Paired reads: got ")
+                .cyan(&paths.len().to_string())
+                .white(" R1 files, but ")
+                .cyan(&paths2.len().to_string())
+                .white(" R2 files.")
+                .bail();
+        }
+        let mut mate = FastqSource::new(
+            paths2[0].as_str(),
+            !no_comment.unwrap(),
+            !no_quality.unwrap(),
+            phred.unwrap(),
+            detect_size.unwrap(),
+        )?;
+        mate.set_next_paths(paths2[1..].to_vec());
+        source.set_mate(mate);
+    }
+    if paired && (split_by.is_some() || split_size.is_some()) {
+        let mut h = Hint::new();
+        return h
+            .white("Splitting output is not supported for paired reads.")
+            .bail();
+    }
+    let mut split = Split::new(&output, output_type, split_by, split_size, max_open)?;
+    let mut writer2 = match output2 {
+        Some(path) => Some(FilterxWriter::new(Some(path), None, output_type)?),
+        None => None,
+    };
+    // output goes to split files, the writer is only used by `print`
+    let writer_path = if split.is_some() {
+        None
+    } else {
+        output.clone()
+    };
+    let mut writer = FilterxWriter::new(writer_path, None, output_type)?;
+    if expr.is_empty() && sql.is_none() && split.is_none() && !paired {
+        loop {
+            while let Some(record) = &mut source.fastq.parse_next()? {
+                writeln!(writer, "{}", record.format())?;
+            }
+            if !source.next_file()? {
+                return Ok(());
+            }
         }
-        return Ok(());
     }
     let chunk_size = long.unwrap();
     let mut vm = Vm::from_source(Source::new(source.into(), SourceType::Fastq), writer);
     vm.status.set_chunk_size(chunk_size);
     vm.source_mut().set_init_column_names(&names);
+    vm.set_variables(util::parse_defines(define, &vm.source().ret_column_names)?);
     'stop_parse: loop {
         let left = vm.next_batch()?;
         if left.is_none() {
             break 'stop_parse;
         }
-        vm.eval_once(&expr, sql.clone())?;
+        if super::eval_once(&mut vm, &expr, sql.clone())? {
+            return Ok(());
+        }
         if !vm.status.printed {
             let df = vm.into_df()?;
-            let writer = &mut vm.writer;
+            let keys = match split.as_ref() {
+                Some(split) => split.keys(&df, vm.status.consume_rows)?,
+                None => vec![],
+            };
             let cols = df.get_columns();
             let name_col = cols.iter().position(|x| x.name() == "name");
             let seq_col = cols.iter().position(|x| x.name() == "seq");
@@ -128,43 +221,121 @@ pub fn filterx_fastq(cmd: FastqCommand) -> FilterxResult<()> {
                 valid_cols = vec![name_col.unwrap(), seq_col.unwrap(), qual_col.unwrap()]
             }
 
+            // the same fields of R2 in paired mode
+            let mut mate_cols = vec![];
+            if paired {
+                for col_index in &valid_cols {
+                    let field = cols[*col_index].name().to_string();
+                    let name = format!("{}{}", field, MATE_SUFFIX);
+                    match cols.iter().position(|x| x.name() == name.as_str()) {
+                        Some(index) => mate_cols.push((field, index)),
+                        None => {
+                            let h = &mut vm.hint;
+                            return h
+                                .white("Lost ")
+                                .cyan(&format!("'{}'", name))
+                                .white(" column.")
+                                .bail();
+                        }
+                    }
+                }
+            }
+
             let rows = df.height();
             for i in 0..rows {
                 if vm.status.consume_rows >= vm.status.limit_rows {
                     break 'stop_parse;
                 }
                 vm.status.consume_rows += 1;
+                let writer = match (split.as_mut(), keys.get(i)) {
+                    (Some(split), Some(key)) => split.writer.get(key)?.0,
+                    _ => &mut vm.writer,
+                };
                 for col_index in &valid_cols {
                     let col = &cols[*col_index];
-                    match col.name().as_str() {
-                        "name" => {
-                            let name = col.get(i).unwrap();
-                            let name = name.get_str().unwrap_or("");
-                            write!(writer, "@{}", name)?;
-                        }
-                        "comm" => {
-                            let comm = col.get(i).unwrap();
-                            let comm = comm.get_str().unwrap_or("");
-                            write!(writer, " {}", comm)?;
-                        }
-                        "seq" => {
-                            let seq = col.get(i).unwrap();
-                            let seq = seq.get_str().unwrap_or("");
-                            write!(writer, "\n{}\n", seq)?;
-                        }
-                        "qual" => {
-                            let qual = col.get(i).unwrap();
-                            let qual = qual.get_str().unwrap_or("space");
-                            write!(writer, "+\n{}\n", qual)?;
-                        }
-                        _ => {
-                            break;
-                        }
+                    if !write_field(writer, col.name().as_str(), col, i)? {
+                        break;
+                    }
+                }
+                if paired {
+                    let writer = writer2.as_mut().unwrap_or(&mut vm.writer);
+                    for (field, col_index) in &mate_cols {
+                        write_field(writer, field, &cols[*col_index], i)?;
                     }
                 }
             }
-            writer.flush()?;
+            if let Some(writer2) = writer2.as_mut() {
+                writer2.flush()?;
+            }
+            vm.writer.flush()?;
+        }
+    }
+    if let Some(split) = split.as_mut() {
+        split.writer.flush()?;
+    }
+    if let Some(mut df) = vm.finish_group()? {
+        if output.is_none() && table.unwrap_or(false) {
+            println!("{}", df);
+            return Ok(());
         }
+        util::write_df(&mut df, &mut vm.writer, true, Some("\t"), None, None)?;
     }
     Ok(())
 }
+
+#[test]
+fn test_paired() {
+    let dir = crate::test_util::TempDir::new("paired");
+    let path = |name: &str| dir.path(name);
+    // read i has 4 + i bases
+    let reads = |mate: usize, n: usize| -> String {
+        (0..n)
+            .map(|i| {
+                let seq = "ACGT".to_string() + &"A".repeat(i);
+                let qual = "I".repeat(seq.len());
+                format!("@r{} {}:N:0\n{}\n+\n{}\n", i, mate, seq, qual)
+            })
+            .collect()
+    };
+    std::fs::write(path("r1.fq"), reads(1, 5)).unwrap();
+    std::fs::write(path("r2.fq"), reads(2, 5)).unwrap();
+    let names = |name: &str| -> Vec<String> {
+        std::fs::read_to_string(path(name))
+            .unwrap()
+            .lines()
+            .step_by(4)
+            .map(|line| line.to_string())
+            .collect()
+    };
+
+    // chunks of 2 pairs, R2 is read along with R1 across chunks
+    let (r1, r2, o1, o2) = (path("r1.fq"), path("r2.fq"), path("o1.fq"), path("o2.fq"));
+    let args = ["filterx", "fastq", &r1, "--r2", &r2, "-c", "2"];
+    let filter = ["-e", "len(seq2) != 5", "-o", &o1, "--o2", &o2];
+    crate::cli::cli_from(args.iter().chain(filter.iter())).unwrap();
+    assert_eq!(
+        names("o1.fq"),
+        vec!["@r0 1:N:0", "@r2 1:N:0", "@r3 1:N:0", "@r4 1:N:0"]
+    );
+    assert_eq!(
+        names("o2.fq"),
+        vec!["@r0 2:N:0", "@r2 2:N:0", "@r3 2:N:0", "@r4 2:N:0"]
+    );
+
+    // interleaved without --o2
+    let o3 = path("o3.fq");
+    let filter = ["-e", "name == 'r1'", "-o", &o3];
+    crate::cli::cli_from(args.iter().chain(filter.iter())).unwrap();
+    assert_eq!(names("o3.fq"), vec!["@r1 1:N:0", "@r1 2:N:0"]);
+
+    // errors are returned, not printed before exiting
+    let filter = ["-e", "rm(seq2)", "-o", &o3];
+    assert!(crate::cli::cli_from(args.iter().chain(filter.iter())).is_err());
+    let o4 = path("o_{}.fq");
+    let filter = ["--split-size", "2", "-o", &o4];
+    assert!(crate::cli::cli_from(args.iter().chain(filter.iter())).is_err());
+
+    // R1 and R2 files should match
+    let args = ["filterx", "fastq", &r1, &r1, "--r2", &r2, "-o", &o3];
+    assert!(crate::cli::cli_from(args).is_err());
+}
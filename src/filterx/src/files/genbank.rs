@@ -0,0 +1,100 @@
+use crate::args::{GenBankCommand, ShareArgs};
+
+use super::Split;
+use filterx_core::{util, writer::FilterxWriter, FilterxResult};
+use filterx_engine::vm::Vm;
+use filterx_source::{GenBankSource, Source, SourceType};
+
+pub fn filterx_genbank(cmd: GenBankCommand) -> FilterxResult<()> {
+    let GenBankCommand {
+        share_args:
+            ShareArgs {
+                input,
+                source_column,
+                expr,
+                script,
+                define,
+                output,
+                table,
+                output_type,
+                sql,
+                split_by,
+                split_size,
+                max_open,
+            },
+        chunk,
+        features,
+        no_header,
+    } = cmd;
+
+    let features = features.unwrap();
+    let mut names: Vec<String> = GenBankSource::column_names(features)
+        .iter()
+        .map(|x| x.to_string())
+        .collect();
+    if let Some(name) = &source_column {
+        names.push(name.clone());
+    }
+    let paths = util::expand_paths(&input)?;
+    let mut source = GenBankSource::new(paths[0].as_str(), features)?;
+    source.set_next_paths(paths[1..].to_vec());
+    source.set_source_column(source_column);
+
+    let mut split = Split::new(&output, output_type, split_by, split_size, max_open)?;
+    // output goes to split files, the writer is only used by `print`
+    let writer_path = if split.is_some() {
+        None
+    } else {
+        output.clone()
+    };
+    let writer = FilterxWriter::new(writer_path, None, output_type)?;
+    let expr = util::merge_expr(expr, script)?;
+    let mut vm = Vm::from_source(Source::new(source.into(), SourceType::GenBank), writer);
+    vm.source_mut().set_init_column_names(&names);
+    vm.set_variables(util::parse_defines(define, &vm.source().ret_column_names)?);
+    vm.status.set_chunk_size(chunk.unwrap());
+    let mut output_header = !no_header.unwrap();
+    while vm.next_batch()?.is_some() {
+        if super::eval_once(&mut vm, &expr, sql.clone())? {
+            return Ok(());
+        }
+        if vm.status.printed {
+            continue;
+        }
+        let mut df = vm.into_df()?;
+        if output.is_none() && table.unwrap_or(false) {
+            println!("{}", df);
+            continue;
+        }
+        if let Some(split) = split.as_mut() {
+            split.write_df(&df, |part, writer, created| {
+                util::write_df(
+                    part,
+                    writer,
+                    !no_header.unwrap() && created,
+                    Some("\t"),
+                    None,
+                    None,
+                )
+            })?;
+            continue;
+        }
+        util::write_df(
+            &mut df,
+            &mut vm.writer,
+            output_header,
+            Some("\t"),
+            None,
+            None,
+        )?;
+        output_header = false;
+    }
+    if let Some(mut df) = vm.finish_group()? {
+        if output.is_none() && table.unwrap_or(false) {
+            println!("{}", df);
+            return Ok(());
+        }
+        util::write_df(&mut df, &mut vm.writer, true, Some("\t"), None, None)?;
+    }
+    Ok(())
+}
@@ -1,6 +1,161 @@
 pub mod csv;
 pub mod fasta;
 pub mod fastq;
+pub mod genbank;
 pub mod gxf;
 pub mod sam;
 pub mod vcf;
+
+use filterx_core::{
+    reader::FileContentType,
+    value::Value,
+    writer::{FilterxWriter, SplitWriter},
+    FilterxResult, Hint,
+};
+use filterx_engine::vm::Vm;
+use polars::prelude::*;
+
+/// Evaluate the expression on the current chunk. `header()` prints the
+/// columns and returns true, the command should stop without output.
+pub fn eval_once(vm: &mut Vm, expr: &str, sql: Option<String>) -> FilterxResult<bool> {
+    vm.eval_once(expr, sql)?;
+    let rows = match vm.status.schema.take() {
+        Some(Value::List(rows)) => rows,
+        _ => return Ok(false),
+    };
+    println!("index\tname\ttype");
+    for row in rows {
+        if let Value::List(fields) = row {
+            let fields: Vec<String> = fields
+                .iter()
+                .map(|f| match f {
+                    Value::Int(i) => i.to_string(),
+                    Value::Str(s) => s.clone(),
+                    _ => String::new(),
+                })
+                .collect();
+            println!("{}", fields.join("\t"));
+        }
+    }
+    Ok(true)
+}
+
+pub enum SplitBy {
+    Column(String),
+    Size(usize),
+}
+
+/// `--split-by` / `--split-size`
+pub struct Split {
+    pub by: SplitBy,
+    pub writer: SplitWriter,
+}
+
+impl Split {
+    pub fn new(
+        output: &Option<String>,
+        output_type: Option<FileContentType>,
+        split_by: Option<String>,
+        split_size: Option<usize>,
+        max_open: Option<usize>,
+    ) -> FilterxResult<Option<Self>> {
+        let by = match (split_by, split_size) {
+            (Some(col), _) => SplitBy::Column(col),
+            (None, Some(size)) if size > 0 => SplitBy::Size(size),
+            _ => return Ok(None),
+        };
+        // clap makes sure -o is set
+        let template = output.as_deref().unwrap();
+        let writer = SplitWriter::new(template, output_type, max_open.unwrap())?;
+        Ok(Some(Self { by, writer }))
+    }
+
+    /// Key of every row in `df`, `offset` is the number of rows written before.
+    pub fn keys(&self, df: &DataFrame, offset: usize) -> FilterxResult<Vec<String>> {
+        match &self.by {
+            SplitBy::Size(size) => Ok((0..df.height())
+                .map(|i| ((offset + i) / size + 1).to_string())
+                .collect()),
+            SplitBy::Column(name) => {
+                let col = split_column(df, name)?;
+                Ok(col.as_materialized_series().iter().map(split_key).collect())
+            }
+        }
+    }
+
+    /// Write a whole dataframe, `write` receives the part, its writer, and whether
+    /// the file is just created (e.g. to write the header).
+    pub fn write_df<F>(&mut self, df: &DataFrame, mut write: F) -> FilterxResult<()>
+    where
+        F: FnMut(&mut DataFrame, &mut FilterxWriter, bool) -> FilterxResult<()>,
+    {
+        let parts = match &self.by {
+            SplitBy::Size(size) => (0..df.height())
+                .step_by(*size)
+                .enumerate()
+                .map(|(i, offset)| ((i + 1).to_string(), df.slice(offset as i64, *size)))
+                .collect::<Vec<_>>(),
+            SplitBy::Column(name) => {
+                split_column(df, name)?;
+                df.partition_by_stable([name.as_str()], true)?
+                    .into_iter()
+                    .map(|part| (split_key(part.column(name).unwrap().get(0).unwrap()), part))
+                    .collect()
+            }
+        };
+        for (key, mut part) in parts {
+            let (writer, created) = self.writer.get(&key)?;
+            write(&mut part, writer, created)?;
+        }
+        self.writer.flush()
+    }
+}
+
+fn split_column<'a>(df: &'a DataFrame, name: &str) -> FilterxResult<&'a Column> {
+    match df.column(name) {
+        Ok(col) => Ok(col),
+        Err(_) => {
+            let mut h = Hint::new();
+            h.white("--split-by: column ")
+                .cyan(name)
+                .white(" not found.")
+                .bail()
+        }
+    }
+}
+
+fn split_key(value: AnyValue) -> String {
+    match value {
+        AnyValue::Null => "null".to_string(),
+        AnyValue::String(s) => s.to_string(),
+        AnyValue::StringOwned(s) => s.to_string(),
+        v => v.to_string(),
+    }
+}
+
+#[test]
+fn test_header() {
+    // `header()` prints the columns and returns instead of exiting
+    let csv = "filterx csv -H ../../test_data/csv/example.csv -e header()";
+    let fasta = "filterx fasta ../../test_data/fasta/1.fa -c 1 -e header()";
+    for args in [csv, fasta] {
+        crate::cli::cli_from(args.split(' ')).unwrap();
+    }
+}
+
+#[test]
+fn test_split_by_missing_column() {
+    let dir = crate::test_util::TempDir::new("split_by");
+    let output = dir.path("out_{}.csv");
+    let args = [
+        "filterx",
+        "csv",
+        "-H",
+        "../../test_data/csv/example.csv",
+        "--split-by",
+        "country",
+        "-o",
+        &output,
+    ];
+    assert!(crate::cli::cli_from(args).is_err());
+}
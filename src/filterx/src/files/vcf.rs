@@ -1,3 +1,4 @@
+use super::Split;
 use crate::args::{ShareArgs, VcfCommand};
 use filterx_core::{util, writer::FilterxWriter, FilterxResult};
 use filterx_engine::vm::Vm;
@@ -55,36 +56,62 @@ pub fn filterx_vcf(cmd: VcfCommand) -> FilterxResult<()> {
     let VcfCommand {
         share_args:
             ShareArgs {
-                input: path,
+                input,
+                source_column,
                 expr,
+                script,
+                define,
                 output,
                 table,
                 output_type,
                 sql,
+                split_by,
+                split_size,
+                max_open,
             },
         header: include_header,
     } = cmd;
+    let paths = util::expand_paths(&input)?;
+    let path = &paths[0];
 
     let comment_prefix = "#";
     let separator = "\t";
-    let writer = FilterxWriter::new(output.clone(), None, output_type)?;
-    let (names, schema) = init_vcf_schema(&path)?;
-    let lazy_df = util::init_df(
-        path.as_str(),
-        false,
-        comment_prefix,
-        Some(separator),
-        0,
-        None,
-        schema,
-        Some(vec!["."]),
-        true,
-    )?;
+    let mut split = Split::new(&output, output_type, split_by, split_size, max_open)?;
+    // output goes to split files, the writer is only used by `print`
+    let writer_path = if split.is_some() {
+        None
+    } else {
+        output.clone()
+    };
+    let writer = FilterxWriter::new(writer_path, None, output_type)?;
+    let (mut names, schema) = init_vcf_schema(path)?;
+    let mut frames = Vec::with_capacity(paths.len());
+    for path in &paths {
+        let lazy_df = util::init_df(
+            path.as_str(),
+            false,
+            comment_prefix,
+            Some(separator),
+            0,
+            None,
+            schema.clone(),
+            Some(vec!["."]),
+            true,
+        )?;
+        frames.push((path.clone(), lazy_df));
+    }
+    let lazy_df = util::concat_inputs(frames, source_column.as_deref())?;
+    if let Some(name) = &source_column {
+        names.push(name.clone());
+    }
     let mut s = DataframeSource::new(lazy_df.clone());
     s.set_init_column_names(&names);
     let mut vm = Vm::from_source(Source::new(s.into(), SourceType::Vcf), writer);
-    let expr = util::merge_expr(expr);
-    vm.eval_once(&expr, sql)?;
+    vm.set_variables(util::parse_defines(define, &vm.source().ret_column_names)?);
+    let expr = util::merge_expr(expr, script)?;
+    if super::eval_once(&mut vm, &expr, sql)? {
+        return Ok(());
+    }
     if vm.status.printed {
         return Ok(());
     }
@@ -100,6 +127,12 @@ pub fn filterx_vcf(cmd: VcfCommand) -> FilterxResult<()> {
     if include_header.unwrap() {
         headers = Some(util::collect_comment_lines(path.as_str(), comment_prefix)?);
     }
+    if let Some(split) = split.as_mut() {
+        return split.write_df(&df, |part, writer, created| {
+            let headers = if created { headers.clone() } else { None };
+            util::write_df(part, writer, false, Some(separator), headers, Some("."))
+        });
+    }
     util::write_df(
         &mut df,
         &mut vm.writer,
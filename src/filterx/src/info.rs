@@ -6,7 +6,7 @@ use filterx_info::render;
 fn list_and_print() -> FilterxResult<()> {
     let functions = list_functions();
     for (index, func) in functions.iter().enumerate() {
-        println!("{:2} {}", (index + 1), func.name.cyan().bold());
+        println!("{:2} {}", (index + 1), func.name().cyan().bold());
     }
     println!();
     let mut h = Hint::new();
@@ -33,8 +33,9 @@ pub fn filterx_info(info: InfoArgs) -> FilterxResult<()> {
         return Ok(());
     }
 
-    let f = get_function(&name.unwrap());
-    render::render_markdown_help(f.doc);
-    render::render_alias_function(&f.alias);
+    let f = get_function(&name.unwrap())?;
+    render::render_markdown_help(f.doc());
+    render::render_signature(&f.signature());
+    render::render_alias_function(f.aliases());
     Ok(())
 }
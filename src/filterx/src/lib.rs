@@ -1,6 +1,11 @@
 pub mod args;
 pub mod cli;
+pub mod demux;
 pub mod files;
 pub mod info;
+pub mod merge;
+pub mod stats;
+#[cfg(test)]
+mod test_util;
 
 pub use filterx_core::FilterxResult;
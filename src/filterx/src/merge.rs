@@ -0,0 +1,337 @@
+use std::io::Write;
+
+use crate::args::MergeArgs;
+
+use filterx_core::{
+    seq::{reverse_complement, Complement},
+    util,
+    writer::FilterxWriter,
+    FilterxResult, Hint,
+};
+use filterx_source::{FastqSource, QualityType};
+
+/// Max quality of merged bases, `J` in phred33.
+const MAX_QUAL: u8 = 41;
+/// Min quality of bases with conflicting calls.
+const MIN_QUAL: u8 = 2;
+
+fn phred_offset(quality_type: QualityType) -> u8 {
+    match quality_type {
+        QualityType::Phred64 => 64,
+        _ => 33,
+    }
+}
+
+fn is_n(b: u8) -> bool {
+    b == b'N' || b == b'n'
+}
+
+/// Start of revcomp(R2) on R1 with the best overlap, a matched base scores 1
+/// and a mismatched one -1. The start is negative if R2 reads through the
+/// start of R1. `N` is neither a match nor a mismatch.
+fn best_overlap(seq1: &[u8], seq2: &[u8], min_overlap: usize, mismatch: f64) -> Option<isize> {
+    let (len1, len2) = (seq1.len() as isize, seq2.len() as isize);
+    let min_overlap = min_overlap.max(1) as isize;
+    let mut best: Option<(isize, isize)> = None;
+    for offset in (min_overlap - len2)..=(len1 - min_overlap) {
+        let start = offset.max(0);
+        let end = len1.min(offset + len2);
+        let overlap = (end - start) as usize;
+        let max_mismatch = (overlap as f64 * mismatch) as usize;
+        let mut matched = 0;
+        let mut mismatched = 0;
+        for (a, b) in seq1[start as usize..end as usize]
+            .iter()
+            .zip(&seq2[(start - offset) as usize..])
+        {
+            if is_n(*a) || is_n(*b) {
+                continue;
+            }
+            if a.eq_ignore_ascii_case(b) {
+                matched += 1;
+            } else {
+                mismatched += 1;
+                if mismatched > max_mismatch {
+                    break;
+                }
+            }
+        }
+        if mismatched > max_mismatch {
+            continue;
+        }
+        let score = matched as isize - mismatched as isize;
+        if best.is_none_or(|(best_score, _)| score > best_score) {
+            best = Some((score, offset));
+        }
+    }
+    best.map(|(_, offset)| offset)
+}
+
+/// Merge R1 and revcomp(R2) starting at `offset` of R1, from the start of R1
+/// to the end of revcomp(R2). Qualities are without the phred offset. Bases
+/// agreeing in the overlap get the sum of the qualities, conflicting bases
+/// take the base of the higher quality and the difference of the qualities.
+fn merge_pair(
+    (seq1, qual1): (&[u8], &[u8]),
+    (seq2, qual2): (&[u8], &[u8]),
+    offset: isize,
+    seq: &mut Vec<u8>,
+    qual: &mut Vec<u8>,
+) {
+    seq.clear();
+    qual.clear();
+    let end = offset + seq2.len() as isize;
+    for i in 0..end {
+        let a = (i < seq1.len() as isize).then(|| (seq1[i as usize], qual1[i as usize]));
+        let b = (i >= offset).then(|| {
+            let j = (i - offset) as usize;
+            (seq2[j], qual2[j])
+        });
+        let (base, q) = match (a, b) {
+            (Some(a), None) | (None, Some(a)) => a,
+            (Some(a), Some(b)) if is_n(b.0) => a,
+            (Some(a), Some(b)) if is_n(a.0) => b,
+            (Some(a), Some(b)) if a.0.eq_ignore_ascii_case(&b.0) => {
+                (a.0, (a.1 + b.1).min(MAX_QUAL))
+            }
+            (Some(a), Some(b)) if a.1 >= b.1 => (a.0, (a.1 - b.1).max(MIN_QUAL)),
+            (Some(a), Some(b)) => (b.0, (b.1 - a.1).max(MIN_QUAL)),
+            (None, None) => unreachable!(),
+        };
+        seq.push(base);
+        qual.push(q);
+    }
+}
+
+/// Qualities of a read without the phred offset, all 0 if there are none.
+fn scores(qual: Option<&str>, len: usize, offset: u8, buf: &mut Vec<u8>) {
+    buf.clear();
+    match qual {
+        Some(qual) => buf.extend(qual.bytes().map(|q| q.saturating_sub(offset))),
+        None => buf.resize(len, 0),
+    }
+}
+
+fn merge_error<T>(msg: &str, path: &str) -> FilterxResult<T> {
+    let mut h = Hint::new();
+    h.white("merge: ").white(msg).cyan(path).white(".").bail()
+}
+
+fn write_report(path: Option<String>, pairs: u64, merged: u64) -> FilterxResult<()> {
+    let mut writer = FilterxWriter::new(path, None, None)?;
+    let percent = match pairs {
+        0 => 0.0,
+        _ => merged as f64 * 100.0 / pairs as f64,
+    };
+    writeln!(writer, "pairs\tmerged\tunmerged\tpercent")?;
+    writeln!(
+        writer,
+        "{}\t{}\t{}\t{:.2}",
+        pairs,
+        merged,
+        pairs - merged,
+        percent
+    )?;
+    writer.flush()?;
+    Ok(())
+}
+
+pub fn filterx_merge(cmd: MergeArgs) -> FilterxResult<()> {
+    let MergeArgs {
+        input,
+        r2,
+        min_overlap,
+        mismatch,
+        output,
+        output1,
+        output2,
+        output_type,
+        phred,
+        report,
+    } = cmd;
+
+    let min_overlap = min_overlap.unwrap();
+    let mismatch = mismatch.unwrap();
+    if !(0.0..1.0).contains(&mismatch) {
+        let mut h = Hint::new();
+        return h
+            .white("merge: expected ")
+            .cyan("--mismatch")
+            .white(" between 0 and 1, but got ")
+            .red(&mismatch.to_string())
+            .bail();
+    }
+    let paths = util::expand_paths(&input)?;
+    let paths2 = util::expand_paths(&r2)?;
+    if paths2.len() != paths.len() {
+        let mut h = Hint::new();
+        return h
+            .white("merge: got ")
+            .cyan(&paths.len().to_string())
+            .white(" R1 files, but ")
+            .cyan(&paths2.len().to_string())
+            .white(" R2 files.")
+            .bail();
+    }
+    let phred = phred.unwrap();
+    let mut source = FastqSource::new(&paths[0], true, true, phred, 100)?;
+    source.set_next_paths(paths[1..].to_vec());
+    let mut mate = FastqSource::new(&paths2[0], true, true, phred, 100)?;
+    mate.set_next_paths(paths2[1..].to_vec());
+
+    let mut writer = FilterxWriter::new(Some(output), None, output_type)?;
+    let mut writer1 = match output1 {
+        Some(path) => Some(FilterxWriter::new(Some(path), None, output_type)?),
+        None => None,
+    };
+    // R2 of unmerged pairs is interleaved with R1 if --o2 is not set
+    let mut writer2 = match output2 {
+        Some(path) => Some(FilterxWriter::new(Some(path), None, output_type)?),
+        None => None,
+    };
+
+    let (mut pairs, mut merged) = (0u64, 0u64);
+    let (mut qual1, mut qual2) = (vec![], vec![]);
+    let (mut seq, mut qual) = (vec![], vec![]);
+    loop {
+        let offset1 = phred_offset(source.fastq.quality_type);
+        let offset2 = phred_offset(mate.fastq.quality_type);
+        while let Some(record1) = source.fastq.parse_next()? {
+            let record2 = match mate.fastq.parse_next()? {
+                Some(record2) => record2,
+                None => return merge_error("R2 has fewer reads than R1 in ", &mate.fastq.path),
+            };
+            pairs += 1;
+            let seq1 = record1.seq().as_bytes();
+            let seq2 = reverse_complement(record2.seq().as_bytes(), Complement::Iupac);
+            scores(record1.qual(), seq1.len(), offset1, &mut qual1);
+            scores(record2.qual(), seq2.len(), offset2, &mut qual2);
+            qual2.reverse();
+            if let Some(offset) = best_overlap(seq1, &seq2, min_overlap, mismatch) {
+                merged += 1;
+                merge_pair((seq1, &qual1), (&seq2, &qual2), offset, &mut seq, &mut qual);
+                qual.iter_mut().for_each(|q| *q += offset1);
+                write!(writer, "@{}", record1.name())?;
+                if let Some(comment) = record1.comment() {
+                    write!(writer, " {}", comment)?;
+                }
+                writer.write_all(b"\n")?;
+                writer.write_all(&seq)?;
+                writer.write_all(b"\n+\n")?;
+                writer.write_all(&qual)?;
+                writer.write_all(b"\n")?;
+                continue;
+            }
+            if let Some(writer1) = writer1.as_mut() {
+                writeln!(writer1, "{}", record1.format())?;
+                let writer2 = writer2.as_mut().unwrap_or(writer1);
+                writeln!(writer2, "{}", record2.format())?;
+            }
+        }
+        if mate.fastq.parse_next()?.is_some() {
+            return merge_error("R2 has more reads than R1 in ", &mate.fastq.path);
+        }
+        if !source.next_file()? {
+            break;
+        }
+        mate.next_file()?;
+    }
+    writer.flush()?;
+    if let Some(writer1) = writer1.as_mut() {
+        writer1.flush()?;
+    }
+    if let Some(writer2) = writer2.as_mut() {
+        writer2.flush()?;
+    }
+    write_report(report, pairs, merged)
+}
+
+#[test]
+fn test_merge_pair() {
+    let merge = |seq1: &[u8], qual1: &[u8], seq2: &[u8], qual2: &[u8], offset: isize| {
+        let (mut seq, mut qual) = (vec![], vec![]);
+        merge_pair((seq1, qual1), (seq2, qual2), offset, &mut seq, &mut qual);
+        (String::from_utf8(seq).unwrap(), qual)
+    };
+    let insert = b"GATTACACCGTAGTCA";
+    // R2 is already reverse complemented, it starts at base 6 of R1
+    let (seq1, seq2) = (&insert[..12], &insert[6..]);
+    assert_eq!(best_overlap(seq1, seq2, 4, 0.1), Some(6));
+    let (seq, qual) = merge(seq1, &[20; 12], seq2, &[30; 10], 6);
+    assert_eq!(seq.as_bytes(), insert);
+    // the overlap has the sum of the qualities, at most 41
+    assert_eq!(qual, [[20; 6], [41; 6], [30; 6]].concat()[..16]);
+
+    // a short insert, R2 reads through the start of R1 and both have adapters
+    let seq1 = b"GATTACACCGAAAA";
+    let seq2 = b"TTTTGATTACACCG";
+    assert_eq!(best_overlap(seq1, seq2, 4, 0.1), Some(-4));
+    let (seq, _) = merge(seq1, &[20; 14], seq2, &[20; 14], -4);
+    assert_eq!(seq, "GATTACACCG");
+
+    // R2 is in R1, the bases of R1 after the end of R2 are cut
+    let seq1 = b"GATTACACCGTAGT";
+    assert_eq!(best_overlap(seq1, b"TACACCG", 4, 0.1), Some(3));
+    let (seq, _) = merge(seq1, &[20; 14], b"TACACCG", &[20; 7], 3);
+    assert_eq!(seq, "GATTACACCG");
+
+    // N is neither a match nor a mismatch, the other base is used
+    let (seq1, seq2) = (b"GATTACNCCG", b"GATTACACNG");
+    assert_eq!(best_overlap(seq1, seq2, 10, 0.0), Some(0));
+    let (seq, qual) = merge(
+        seq1,
+        &[20, 20, 20, 20, 20, 20, 2, 20, 20, 20],
+        seq2,
+        &[10; 10],
+        0,
+    );
+    assert_eq!(seq, "GATTACACCG");
+    assert_eq!(qual, [30, 30, 30, 30, 30, 30, 10, 30, 20, 30]);
+
+    // conflicting bases take the base of the higher quality and the difference
+    let (seq, qual) = merge(b"ACGT", &[30, 10, 12, 12], b"AGCT", &[10, 30, 10, 12], 0);
+    assert_eq!(seq, "AGGT");
+    assert_eq!(qual, [40, 20, 2, 24]);
+
+    // one mismatch in 10 bases
+    let (seq1, seq2) = (b"GATTACACCG", b"GATTACTCCG");
+    assert_eq!(best_overlap(seq1, seq2, 10, 0.1), Some(0));
+    assert_eq!(best_overlap(seq1, seq2, 10, 0.05), None);
+    assert_eq!(best_overlap(b"GATTACACCG", b"CCGT", 4, 0.1), None);
+}
+
+#[test]
+fn test_mismatch_bounds() {
+    let dir = crate::test_util::TempDir::new("merge");
+    let path = |name: &str| dir.path(name);
+    // R1 and R2 overlap in 12 bases
+    let insert = b"GATTACACCGTAGTCAGGCTTAAC";
+    let seq2 = String::from_utf8(reverse_complement(&insert[6..], Complement::Iupac)).unwrap();
+    let seq1 = std::str::from_utf8(&insert[..18]).unwrap();
+    let (r1, r2) = (path("r1.fq"), path("r2.fq"));
+    std::fs::write(&r1, format!("@r\n{}\n+\n{}\n", seq1, "I".repeat(18))).unwrap();
+    let read2 = format!("@r\n{}\n+\n{}\n", seq2, "I".repeat(18));
+    std::fs::write(&r2, &read2).unwrap();
+    let (output, report) = (path("merged.fq"), path("report.tsv"));
+    let merge = |r2: &str, mismatch: &str| {
+        let mismatch = format!("--mismatch={}", mismatch);
+        let args = [
+            "filterx", "merge", &r1, "--r2", r2, "-o", &output, "--report", &report, &mismatch,
+        ];
+        crate::cli::cli_from(args)
+    };
+    for mismatch in ["0", "0.1", "0.99"] {
+        merge(&r2, mismatch).unwrap();
+        let merged = std::fs::read_to_string(&output).unwrap();
+        assert_eq!(merged.lines().nth(1).unwrap().as_bytes(), insert);
+        let report = std::fs::read_to_string(&report).unwrap();
+        assert_eq!(report.lines().nth(1), Some("1\t1\t0\t100.00"));
+    }
+    assert!(merge(&r2, "1.5").is_err());
+    assert!(merge(&r2, "1").is_err());
+    assert!(merge(&r2, "-0.1").is_err());
+    // R2 has one more read than R1
+    let more = path("more.fq");
+    std::fs::write(&more, read2.repeat(2)).unwrap();
+    assert!(merge(&more, "0.1").is_err());
+}
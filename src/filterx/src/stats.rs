@@ -0,0 +1,391 @@
+use std::collections::BTreeMap;
+use std::io::{BufRead, Write};
+
+use crate::args::{StatsArgs, StatsFormat};
+
+use filterx_core::{
+    reader::FilterxReader, util, value::Value, writer::FilterxWriter, FilterxResult, Hint,
+};
+use filterx_engine::vm::Vm;
+use filterx_source::{FastaRecordType, FastaSource, FastqSource, QualityType, Source, SourceType};
+
+/// Summary of all sequences in one file.
+#[derive(Debug, Default)]
+struct SeqStats {
+    num_seqs: u64,
+    sum_len: u64,
+    gc: u64,
+    q20: u64,
+    q30: u64,
+    /// length -> number of sequences
+    lengths: BTreeMap<usize, u64>,
+}
+
+impl SeqStats {
+    fn update(&mut self, seq: &[u8], qual: Option<&[u8]>, phred_offset: u8) {
+        self.num_seqs += 1;
+        self.sum_len += seq.len() as u64;
+        *self.lengths.entry(seq.len()).or_insert(0) += 1;
+        self.gc += seq
+            .iter()
+            .filter(|b| matches!(b, b'G' | b'C' | b'g' | b'c'))
+            .count() as u64;
+        if let Some(qual) = qual {
+            for q in qual {
+                let q = q.saturating_sub(phred_offset);
+                if q >= 20 {
+                    self.q20 += 1;
+                    if q >= 30 {
+                        self.q30 += 1;
+                    }
+                }
+            }
+        }
+    }
+
+    fn min_len(&self) -> usize {
+        self.lengths.keys().next().copied().unwrap_or(0)
+    }
+
+    fn max_len(&self) -> usize {
+        self.lengths.keys().next_back().copied().unwrap_or(0)
+    }
+
+    fn avg_len(&self) -> f64 {
+        if self.num_seqs == 0 {
+            return 0.0;
+        }
+        self.sum_len as f64 / self.num_seqs as f64
+    }
+
+    /// nearest-rank quantile of sequence length
+    fn quantile_len(&self, q: f64) -> usize {
+        let rank = ((q * self.num_seqs as f64).ceil() as u64).max(1);
+        let mut seen = 0;
+        for (len, count) in &self.lengths {
+            seen += count;
+            if seen >= rank {
+                return *len;
+            }
+        }
+        0
+    }
+
+    fn n50(&self) -> usize {
+        let mut acc = 0;
+        for (len, count) in self.lengths.iter().rev() {
+            acc += *len as u64 * count;
+            if acc * 2 >= self.sum_len {
+                return *len;
+            }
+        }
+        0
+    }
+
+    fn percent(&self, n: u64) -> f64 {
+        if self.sum_len == 0 {
+            return 0.0;
+        }
+        n as f64 * 100.0 / self.sum_len as f64
+    }
+}
+
+enum StatsValue {
+    Str(String),
+    Int(u64),
+    Float(f64),
+    Null,
+}
+
+impl StatsValue {
+    fn text(&self) -> String {
+        match self {
+            StatsValue::Str(s) => s.clone(),
+            StatsValue::Int(i) => i.to_string(),
+            StatsValue::Float(f) => format!("{:.2}", f),
+            StatsValue::Null => "-".to_string(),
+        }
+    }
+
+    fn json(&self) -> String {
+        match self {
+            StatsValue::Str(s) => format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\"")),
+            StatsValue::Null => "null".to_string(),
+            _ => self.text(),
+        }
+    }
+}
+
+const STATS_COLUMNS: [&str; 14] = [
+    "file", "format", "num_seqs", "sum_len", "min_len", "avg_len", "max_len", "Q1", "Q2", "Q3",
+    "N50", "GC(%)", "Q20(%)", "Q30(%)",
+];
+
+fn stats_row(file: &str, source_type: SourceType, stats: &SeqStats) -> Vec<StatsValue> {
+    let is_fastq = source_type == SourceType::Fastq;
+    let qual_percent = |n: u64| {
+        if is_fastq {
+            StatsValue::Float(stats.percent(n))
+        } else {
+            StatsValue::Null
+        }
+    };
+    vec![
+        StatsValue::Str(file.to_string()),
+        StatsValue::Str(if is_fastq { "FASTQ" } else { "FASTA" }.to_string()),
+        StatsValue::Int(stats.num_seqs),
+        StatsValue::Int(stats.sum_len),
+        StatsValue::Int(stats.min_len() as u64),
+        StatsValue::Float(stats.avg_len()),
+        StatsValue::Int(stats.max_len() as u64),
+        StatsValue::Int(stats.quantile_len(0.25) as u64),
+        StatsValue::Int(stats.quantile_len(0.5) as u64),
+        StatsValue::Int(stats.quantile_len(0.75) as u64),
+        StatsValue::Int(stats.n50() as u64),
+        StatsValue::Float(stats.percent(stats.gc)),
+        qual_percent(stats.q20),
+        qual_percent(stats.q30),
+    ]
+}
+
+fn write_stats(
+    writer: &mut FilterxWriter,
+    rows: &[Vec<StatsValue>],
+    format: StatsFormat,
+) -> FilterxResult<()> {
+    match format {
+        StatsFormat::Tsv => {
+            writeln!(writer, "{}", STATS_COLUMNS.join("\t"))?;
+            for row in rows {
+                let row: Vec<String> = row.iter().map(|v| v.text()).collect();
+                writeln!(writer, "{}", row.join("\t"))?;
+            }
+        }
+        StatsFormat::Json => {
+            let objects: Vec<String> = rows
+                .iter()
+                .map(|row| {
+                    let fields: Vec<String> = STATS_COLUMNS
+                        .iter()
+                        .zip(row)
+                        .map(|(name, v)| format!("\"{}\": {}", name, v.json()))
+                        .collect();
+                    format!("  {{{}}}", fields.join(", "))
+                })
+                .collect();
+            writeln!(writer, "[\n{}\n]", objects.join(",\n"))?;
+        }
+        StatsFormat::Table => {
+            let rows: Vec<Vec<String>> = rows
+                .iter()
+                .map(|row| row.iter().map(|v| v.text()).collect())
+                .collect();
+            let widths: Vec<usize> = STATS_COLUMNS
+                .iter()
+                .enumerate()
+                .map(|(i, name)| rows.iter().map(|r| r[i].len()).fold(name.len(), usize::max))
+                .collect();
+            let format_line = |cells: Vec<&str>| {
+                cells
+                    .iter()
+                    .enumerate()
+                    .map(|(i, cell)| {
+                        // text columns are left aligned, numbers are right aligned
+                        if i < 2 {
+                            format!("{:<width$}", cell, width = widths[i])
+                        } else {
+                            format!("{:>width$}", cell, width = widths[i])
+                        }
+                    })
+                    .collect::<Vec<String>>()
+                    .join("  ")
+            };
+            writeln!(writer, "{}", format_line(STATS_COLUMNS.to_vec()))?;
+            for row in &rows {
+                writeln!(
+                    writer,
+                    "{}",
+                    format_line(row.iter().map(|s| s.as_str()).collect())
+                )?;
+            }
+        }
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Guess fasta or fastq by the first non-blank byte of the file.
+fn detect_source_type(path: &str) -> FilterxResult<SourceType> {
+    let mut reader = FilterxReader::new(path)?;
+    let buf = reader.fill_buf()?;
+    let first = buf.iter().find(|b| !b.is_ascii_whitespace());
+    match first {
+        Some(b'>') => Ok(SourceType::Fasta),
+        Some(b'@') => Ok(SourceType::Fastq),
+        _ => {
+            let mut h = Hint::new();
+            h.white("stats: can not detect the format of ")
+                .cyan(path)
+                .white(", only ")
+                .green("fasta")
+                .white(" and ")
+                .green("fastq")
+                .white(" are supported.")
+                .bail()
+        }
+    }
+}
+
+fn phred_offset(quality_type: QualityType) -> u8 {
+    match quality_type {
+        QualityType::Phred64 => 64,
+        _ => 33,
+    }
+}
+
+/// Stats row of a file, `None` if `header()` printed the columns.
+fn file_stats(
+    path: &str,
+    expr: &str,
+    chunk: usize,
+    phred: QualityType,
+    variables: &[(String, Value)],
+) -> FilterxResult<Option<Vec<StatsValue>>> {
+    let source_type = detect_source_type(path)?;
+    let mut stats = SeqStats::default();
+
+    let (source, offset) = match source_type {
+        SourceType::Fasta => {
+            let mut source = FastaSource::new(path, false, FastaRecordType::Auto, 3)?;
+            if expr.is_empty() {
+                while let Some(record) = source.fasta.parse_next()? {
+                    stats.update(record.seq().as_bytes(), None, 0);
+                }
+            }
+            (Source::new(source.into(), SourceType::Fasta), 0)
+        }
+        _ => {
+            let mut source = FastqSource::new(path, false, true, phred, 100)?;
+            let offset = phred_offset(source.fastq.quality_type);
+            if expr.is_empty() {
+                while let Some(record) = source.fastq.parse_next()? {
+                    let qual = record.qual().map(|q| q.as_bytes());
+                    stats.update(record.seq().as_bytes(), qual, offset);
+                }
+            }
+            (Source::new(source.into(), SourceType::Fastq), offset)
+        }
+    };
+
+    if !expr.is_empty() {
+        let names = match source_type {
+            SourceType::Fasta => vec!["name", "seq"],
+            _ => vec!["name", "seq", "qual"],
+        };
+        let names = names.iter().map(|x| x.to_string()).collect();
+        // only used by `print`
+        let writer = FilterxWriter::new(None, None, None)?;
+        let mut vm = Vm::from_source(source, writer);
+        vm.set_variables(variables.to_vec());
+        vm.source_mut().set_init_column_names(&names);
+        vm.status.set_chunk_size(chunk);
+        while vm.next_batch()?.is_some() {
+            if crate::files::eval_once(&mut vm, expr, None)? {
+                return Ok(None);
+            }
+            if vm.status.printed {
+                continue;
+            }
+            let df = vm.into_df()?;
+            let seq = match df.column("seq") {
+                Ok(seq) => seq.str()?.clone(),
+                Err(_) => {
+                    let h = &mut vm.hint;
+                    return h.white("Lost ").cyan("'seq'").white(" column.").bail();
+                }
+            };
+            let qual = match df.column("qual") {
+                Ok(qual) if source_type == SourceType::Fastq => Some(qual.str()?.clone()),
+                _ => None,
+            };
+            for i in 0..df.height() {
+                let s = seq.get(i).unwrap_or("");
+                let q = qual.as_ref().and_then(|q| q.get(i)).map(|q| q.as_bytes());
+                stats.update(s.as_bytes(), q, offset);
+            }
+        }
+        vm.writer.flush()?;
+    }
+    Ok(Some(stats_row(path, source_type, &stats)))
+}
+
+pub fn filterx_stats(cmd: StatsArgs) -> FilterxResult<()> {
+    let StatsArgs {
+        input,
+        expr,
+        script,
+        define,
+        output,
+        output_type,
+        format,
+        chunk,
+        phred,
+    } = cmd;
+
+    let expr = util::merge_expr(expr, script)?;
+    // columns of fasta and fastq
+    let columns = ["name", "seq", "qual"].map(String::from);
+    let variables = util::parse_defines(define, &columns)?;
+    let mut writer = FilterxWriter::new(output, None, output_type)?;
+    let mut rows = vec![];
+    for path in util::expand_paths(&input)? {
+        match file_stats(&path, &expr, chunk.unwrap(), phred.unwrap(), &variables)? {
+            Some(row) => rows.push(row),
+            None => return Ok(()),
+        }
+    }
+    write_stats(&mut writer, &rows, format.unwrap())
+}
+
+#[test]
+fn test_stats() {
+    let mut stats = SeqStats::default();
+    for len in 2..=10 {
+        stats.update("A".repeat(len).as_bytes(), None, 33);
+    }
+    // 10 + 9 + 8 is half of 54 bases
+    assert_eq!(stats.n50(), 8);
+    assert_eq!(stats.quantile_len(0.5), 6);
+    assert_eq!(SeqStats::default().n50(), 0);
+
+    let dir = crate::test_util::TempDir::new("stats");
+    let path = |name: &str| dir.path(name);
+    // phred33: 'I' = 40, '+' = 10, '5' = 20, '?' = 30
+    let fastq = "@r0\nACGTACGTAC\n+\nIIIII+++++\n@r1\nGGCC\n+\n5555\n@r2\nAT\n+\n??\n";
+    std::fs::write(path("in.fq"), fastq).unwrap();
+    let (input, output) = (path("in.fq"), path("out.tsv"));
+    let stats = |args: &[&str]| -> String {
+        let base = [
+            "filterx", "stats", &input, "--phred", "phred33", "-f", "tsv",
+        ];
+        let out = ["-o", output.as_str()];
+        crate::cli::cli_from(base.iter().chain(args).chain(out.iter())).unwrap();
+        let text = std::fs::read_to_string(&output).unwrap();
+        text.lines().nth(1).unwrap().replacen(&input, "in.fq", 1)
+    };
+    assert_eq!(
+        stats(&[]),
+        "in.fq\tFASTQ\t3\t16\t2\t5.33\t10\t2\t4\t10\t10\t56.25\t68.75\t43.75"
+    );
+    // sequences left after filtering across chunks
+    assert_eq!(
+        stats(&["-e", "len(seq) > 2", "-c", "1"]),
+        "in.fq\tFASTQ\t2\t14\t4\t7.00\t10\t4\t4\t10\t10\t64.29\t64.29\t35.71"
+    );
+    // errors are returned, not printed before exiting
+    let args = ["filterx", "stats", &input, "-e", "rm(seq)", "-o", &output];
+    assert!(crate::cli::cli_from(args).is_err());
+    std::fs::write(path("in.txt"), "name\tseq\n").unwrap();
+    let args = ["filterx", "stats", &path("in.txt"), "-o", &output];
+    assert!(crate::cli::cli_from(args).is_err());
+}
@@ -1,6 +1,8 @@
 use anyhow::Result;
 use thiserror::Error as ThisError;
 
+use crate::Hint;
+
 pub type FilterxResult<T> = Result<T, FilterxError>;
 
 #[derive(ThisError, Debug)]
@@ -25,4 +27,7 @@ pub enum FilterxError {
 
     #[error("Error initializing gzip: {0}")]
     GzipError(#[from] gzp::GzpError),
+
+    #[error("{0}")]
+    HintError(Hint),
 }
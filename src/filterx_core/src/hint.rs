@@ -1,17 +1,30 @@
 pub use colored::ColoredString;
 pub use colored::Colorize;
 
-#[derive(Debug)]
+use crate::{FilterxError, FilterxResult};
+
+#[derive(Debug, Clone, Default)]
 pub struct Hint {
     pub msg: Vec<ColoredString>,
+    /// byte range of the statement where the error occurs
+    pub span: Option<(usize, usize)>,
+    /// how to fix the error, e.g. a similar column name
+    pub suggestion: Option<String>,
 }
 
 impl Hint {
     pub fn new() -> Self {
         Hint {
             msg: Vec::with_capacity(4),
+            span: None,
+            suggestion: None,
         }
     }
+
+    /// message without colors
+    pub fn message(&self) -> String {
+        self.msg.iter().map(|m| m.input.as_str()).collect()
+    }
 }
 
 impl std::fmt::Display for Hint {
@@ -19,6 +32,9 @@ impl std::fmt::Display for Hint {
         for m in &self.msg {
             write!(f, "{}", m)?;
         }
+        if let Some(suggestion) = &self.suggestion {
+            write!(f, "\nDid you mean `{}`?", suggestion.green())?;
+        }
         Ok(())
     }
 }
@@ -69,6 +85,18 @@ impl Hint {
 
     pub fn clear(&mut self) -> &mut Self {
         self.msg.clear();
+        self.span = None;
+        self.suggestion = None;
+        self
+    }
+
+    pub fn span(&mut self, start: usize, end: usize) -> &mut Self {
+        self.span = Some((start, end));
+        self
+    }
+
+    pub fn suggestion(&mut self, s: &str) -> &mut Self {
+        self.suggestion = Some(s.to_string());
         self
     }
 
@@ -92,6 +120,11 @@ impl Hint {
         self
     }
 
+    /// Take the hint as an error, the hint is left empty to be reused.
+    pub fn bail<T>(&mut self) -> FilterxResult<T> {
+        Err(FilterxError::HintError(std::mem::take(self)))
+    }
+
     pub fn print_and_exit(&self) -> ! {
         eprintln!("{}", self);
         std::process::exit(1);
@@ -2,6 +2,7 @@ pub mod error;
 pub mod hint;
 pub mod reader;
 pub mod sep;
+pub mod seq;
 pub mod thread_size;
 pub mod util;
 pub mod value;
@@ -0,0 +1,53 @@
+/// Bases complemented by [`complement`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Complement {
+    /// A, C, G and T
+    Dna,
+    /// A, C, G and U
+    Rna,
+    /// A, C, G, T and the IUPAC ambiguity codes
+    Iupac,
+}
+
+/// Complement of a base, the case is kept. Other bytes, and S, W and N which
+/// are their own complement, are returned as they are.
+pub fn complement(b: u8, mode: Complement) -> u8 {
+    use Complement::*;
+    let c = match (b.to_ascii_uppercase(), mode) {
+        (b'A', Rna) => b'U',
+        (b'A', _) => b'T',
+        (b'T', Dna | Iupac) => b'A',
+        (b'U', Rna) => b'A',
+        (b'C', _) => b'G',
+        (b'G', _) => b'C',
+        (b'R', Iupac) => b'Y',
+        (b'Y', Iupac) => b'R',
+        (b'K', Iupac) => b'M',
+        (b'M', Iupac) => b'K',
+        (b'B', Iupac) => b'V',
+        (b'V', Iupac) => b'B',
+        (b'D', Iupac) => b'H',
+        (b'H', Iupac) => b'D',
+        _ => return b,
+    };
+    match b.is_ascii_lowercase() {
+        true => c.to_ascii_lowercase(),
+        false => c,
+    }
+}
+
+/// Reverse complement of a sequence, see [`complement`].
+pub fn reverse_complement(seq: &[u8], mode: Complement) -> Vec<u8> {
+    seq.iter().rev().map(|&b| complement(b, mode)).collect()
+}
+
+#[test]
+fn test_complement() {
+    use Complement::*;
+    // U is not a DNA base and T is not an RNA base
+    assert_eq!(reverse_complement(b"ACGTUacgtuRYN", Dna), b"NYRuacgtUACGT");
+    assert_eq!(reverse_complement(b"ACGTUacgtuRYN", Rna), b"NYRatcguATCGU");
+    let iupac = reverse_complement(b"ACGTRYKMBVDHSWNacgtrykmbvdhswn", Iupac);
+    assert_eq!(iupac, b"nwsdhbvkmryacgtNWSDHBVKMRYACGT");
+    assert_eq!(complement(b'-', Iupac), b'-');
+}
@@ -6,8 +6,8 @@ use polars::{
 };
 
 use crate::{
-    reader::FilterxReader, sep::Separator, thread_size::ThreadSize, writer::FilterxWriter,
-    FilterxError, FilterxResult,
+    reader::FilterxReader, sep::Separator, thread_size::ThreadSize, value::Value,
+    writer::FilterxWriter, FilterxError, FilterxResult, Hint,
 };
 use std::io::Write;
 use std::num::NonZero;
@@ -53,12 +53,151 @@ pub fn open_csv_file_in_lazy(
     Ok(lazy)
 }
 
+/// Expand glob patterns of input paths, the order of inputs is kept and
+/// paths matched by one pattern are sorted.
+pub fn expand_paths(inputs: &[String]) -> FilterxResult<Vec<String>> {
+    let mut paths = vec![];
+    for input in inputs {
+        let is_pattern = input.contains(['*', '?', '[']);
+        if !is_pattern || std::path::Path::new(input).exists() {
+            paths.push(input.clone());
+            continue;
+        }
+        let matched = match glob::glob(input) {
+            Ok(matched) => matched,
+            Err(e) => {
+                return Err(FilterxError::RuntimeError(format!(
+                    "Invalid glob pattern {}: {}",
+                    input, e
+                )))
+            }
+        };
+        let mut matched: Vec<String> = matched
+            .filter_map(|p| p.ok())
+            .map(|p| p.display().to_string())
+            .collect();
+        if matched.is_empty() {
+            return Err(FilterxError::RuntimeError(format!(
+                "No file matches {}",
+                input
+            )));
+        }
+        matched.sort();
+        paths.extend(matched);
+    }
+    Ok(paths)
+}
+
+/// Concat lazy frames of all inputs in order. If `source_column` is set, a
+/// column with the file name of every row is added.
+pub fn concat_inputs(
+    frames: Vec<(String, LazyFrame)>,
+    source_column: Option<&str>,
+) -> FilterxResult<LazyFrame> {
+    let frames: Vec<LazyFrame> = frames
+        .into_iter()
+        .map(|(path, lazy)| match source_column {
+            Some(name) => lazy.with_column(lit(path).alias(name)),
+            None => lazy,
+        })
+        .collect();
+    if frames.len() == 1 {
+        return Ok(frames.into_iter().next().unwrap());
+    }
+    let args = UnionArgs {
+        to_supertypes: true,
+        ..Default::default()
+    };
+    Ok(concat(frames, args)?)
+}
+
+/// Merge the `--script` file and `-e` expressions into one program, the
+/// script goes first and every `-e` is a new line.
 #[inline]
-pub fn merge_expr(expr: Option<Vec<String>>) -> String {
-    match expr {
-        Some(expr) => expr.join(";"),
-        None => "".to_string(),
+pub fn merge_expr(expr: Option<Vec<String>>, script: Option<String>) -> FilterxResult<String> {
+    let mut lines = vec![];
+    if let Some(path) = script {
+        let source = match std::fs::read_to_string(&path) {
+            Ok(source) => source,
+            Err(e) => {
+                let mut h = Hint::new();
+                return h
+                    .white("Can't read script ")
+                    .cyan(&path)
+                    .white(": ")
+                    .red(&e.to_string())
+                    .bail();
+            }
+        };
+        lines.push(source.trim_end().to_string());
     }
+    if let Some(expr) = expr {
+        lines.extend(expr.iter().map(|e| e.trim().to_string()));
+    }
+    Ok(lines.join("\n"))
+}
+
+/// parse `--define key=value`, value is int, float, True/False/None or str.
+/// A name should not be one of `columns`, which would be shadowed.
+pub fn parse_defines(
+    defines: Option<Vec<String>>,
+    columns: &[String],
+) -> FilterxResult<Vec<(String, Value)>> {
+    let mut variables = vec![];
+    for define in defines.unwrap_or_default() {
+        let (name, value) = match define.split_once('=') {
+            Some((name, value)) => (name.trim(), value.trim()),
+            None => ("", ""),
+        };
+        let valid_name = name
+            .chars()
+            .enumerate()
+            .all(|(i, c)| c == '_' || c.is_ascii_alphabetic() || (i > 0 && c.is_ascii_digit()));
+        if name.is_empty() || !valid_name {
+            let mut h = Hint::new();
+            return h
+                .white("Invalid define ")
+                .cyan(&define)
+                .white(", expected ")
+                .green("name=value")
+                .white(", e.g. ")
+                .green("-D min_len=100")
+                .bail();
+        }
+        if columns.iter().any(|c| c == name) {
+            let mut h = Hint::new();
+            return h
+                .white("Invalid define ")
+                .cyan(&define)
+                .white(", ")
+                .cyan(name)
+                .white(" is a column, use another name.")
+                .bail();
+        }
+        let value = if let Ok(i) = value.parse::<i64>() {
+            Value::Int(i)
+        } else if let Ok(f) = value.parse::<f64>() {
+            Value::Float(f)
+        } else {
+            match value {
+                "True" => Value::Bool(true),
+                "False" => Value::Bool(false),
+                "None" => Value::Null,
+                _ => {
+                    let quoted = value.len() >= 2
+                        && (value.starts_with('\'') && value.ends_with('\'')
+                            || value.starts_with('"') && value.ends_with('"'));
+                    if quoted {
+                        Value::Str(value[1..value.len() - 1].to_string())
+                    } else {
+                        Value::Str(value.to_string())
+                    }
+                }
+            }
+        };
+        variables.push((name.to_string(), value));
+    }
+    Ok(variables)
 }
 
 pub fn create_schemas(fileds: Vec<(String, DataType)>) -> Option<SchemaRef> {
@@ -113,6 +252,28 @@ pub fn init_df(
     lazy
 }
 
+/// Text of a list column for output, items are joined by `;` like `1;9`,
+/// csv can't hold nested data.
+pub fn list_to_string(c: &Column) -> PolarsResult<Column> {
+    let ca: StringChunked = c
+        .list()?
+        .into_iter()
+        .map(|items| {
+            items.map(|items| {
+                items
+                    .iter()
+                    .map(|v| match v {
+                        AnyValue::String(s) => s.to_string(),
+                        v => v.to_string(),
+                    })
+                    .collect::<Vec<String>>()
+                    .join(";")
+            })
+        })
+        .collect();
+    Ok(ca.with_name(c.name().clone()).into_column())
+}
+
 pub fn write_df(
     df: &mut DataFrame,
     writer: &mut FilterxWriter,
@@ -142,6 +303,17 @@ pub fn write_df(
     if let Some(null_value) = null_value {
         writer = writer.with_null_value(null_value.into());
     }
+    let lists: Vec<usize> = df
+        .get_columns()
+        .iter()
+        .enumerate()
+        .filter(|(_, c)| c.dtype().is_list())
+        .map(|(i, _)| i)
+        .collect();
+    for i in lists {
+        let c = list_to_string(&df.get_columns()[i])?;
+        df.replace_column(i, c)?;
+    }
     writer.finish(df)?;
     Ok(())
 }
@@ -222,3 +394,40 @@ pub fn detect_separator(
     }
     Ok(None)
 }
+
+#[test]
+fn test_parse_defines() {
+    let columns = vec!["a".to_string()];
+    let defines = |d: &[&str]| Some(d.iter().map(|s| s.to_string()).collect());
+    let variables = parse_defines(defines(&["b=3", "c='x'", "d=True"]), &columns).unwrap();
+    assert_eq!(
+        variables,
+        vec![
+            ("b".to_string(), Value::Int(3)),
+            ("c".to_string(), Value::Str("x".to_string())),
+            ("d".to_string(), Value::Bool(true)),
+        ]
+    );
+    // a define would shadow the column
+    assert!(parse_defines(defines(&["a=3"]), &columns).is_err());
+    assert!(parse_defines(defines(&["1a=3"]), &columns).is_err());
+}
+
+#[test]
+fn test_merge_expr() {
+    let path = std::env::temp_dir().join("filterx_test_script.fx");
+    std::fs::write(&path, "# comment\nlen(seq) > 10\n\n").unwrap();
+    let script = Some(path.to_str().unwrap().to_string());
+    let expr = Some(vec![
+        " gc(seq) > 0.5 ".to_string(),
+        "print('{name}')".to_string(),
+    ]);
+    // the script goes first, every -e is a new line
+    assert_eq!(
+        merge_expr(expr, script.clone()).unwrap(),
+        "# comment\nlen(seq) > 10\ngc(seq) > 0.5\nprint('{name}')"
+    );
+    assert_eq!(merge_expr(None, None).unwrap(), "");
+    std::fs::remove_file(&path).unwrap();
+    assert!(merge_expr(None, script).is_err());
+}
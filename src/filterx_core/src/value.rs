@@ -185,7 +185,7 @@ impl Value {
                 h.white("Use ")
                     .green(&format!("col({})", i))
                     .white(" to access column by index.")
-                    .print_and_exit()
+                    .bail()
             }
             _ => {
                 let mut h = Hint::new();
@@ -193,7 +193,7 @@ impl Value {
                     .cyan("name, string")
                     .white(" or ")
                     .cyan("function which returns a column name")
-                    .print_and_exit();
+                    .bail()
             }
         }
     }
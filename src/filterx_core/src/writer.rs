@@ -1,3 +1,5 @@
+use std::collections::{HashMap, HashSet};
+use std::fs::OpenOptions;
 use std::io::{BufWriter, Write};
 
 use gzp::{
@@ -6,7 +8,7 @@ use gzp::{
     Compression,
 };
 
-use crate::{reader::FileContentType, thread_size::ThreadSize, FilterxResult};
+use crate::{reader::FileContentType, thread_size::ThreadSize, FilterxResult, Hint};
 
 pub enum FilterxWriter {
     Stdout(StdoutWriter),
@@ -40,6 +42,15 @@ impl PlainWriter {
             path: path.to_string(),
         })
     }
+
+    pub fn append(path: &str) -> FilterxResult<Self> {
+        let file = OpenOptions::new().append(true).create(true).open(path)?;
+        let file = BufWriter::new(file);
+        Ok(Self {
+            file,
+            path: path.to_string(),
+        })
+    }
 }
 
 pub struct GzipWriter {
@@ -52,6 +63,21 @@ pub struct GzipWriter {
 impl GzipWriter {
     pub fn new(path: &str, compression_level: u32, threads: usize) -> FilterxResult<Self> {
         let fp = std::fs::File::create(path)?;
+        Self::from_file(fp, path, compression_level, threads)
+    }
+
+    /// Append a new gzip member to the end of the file.
+    pub fn append(path: &str, compression_level: u32, threads: usize) -> FilterxResult<Self> {
+        let fp = OpenOptions::new().append(true).create(true).open(path)?;
+        Self::from_file(fp, path, compression_level, threads)
+    }
+
+    fn from_file(
+        fp: std::fs::File,
+        path: &str,
+        compression_level: u32,
+        threads: usize,
+    ) -> FilterxResult<Self> {
         let gzip_writer: ParCompress<Gzip> = ParCompressBuilder::new()
             .compression_level(Compression::new(compression_level))
             .num_threads(threads)?
@@ -108,6 +134,115 @@ impl FilterxWriter {
     }
 }
 
+/// Fan records out into files named by a template, e.g. `out_{}.fq.gz`.
+///
+/// Writers are opened lazily. When more than `max_open` files are opened, the
+/// least recently used one is closed, it will be reopened in append mode.
+/// Keys with the same file name, like `a/c` and `a_c`, share one file.
+pub struct SplitWriter {
+    template: String,
+    file_type: FileContentType,
+    max_open: usize,
+    tick: u64,
+    /// by the file path
+    writers: HashMap<String, (FilterxWriter, u64)>,
+    created: HashSet<String>,
+    keys: Vec<String>,
+}
+
+impl SplitWriter {
+    pub fn new(
+        template: &str,
+        file_type: Option<FileContentType>,
+        max_open: usize,
+    ) -> FilterxResult<Self> {
+        if !template.contains("{}") {
+            let mut h = Hint::new();
+            return h
+                .white("Output file name should contain ")
+                .cyan("{}")
+                .white(" when splitting output, e.g. ")
+                .green("-o out_{}.fq.gz")
+                .bail();
+        }
+        let file_type = match file_type {
+            Some(FileContentType::Auto) | None => FileContentType::from_path(template)?,
+            Some(file_type) => file_type,
+        };
+        Ok(Self {
+            template: template.to_string(),
+            file_type,
+            max_open: max_open.max(1),
+            tick: 0,
+            writers: HashMap::new(),
+            created: HashSet::new(),
+            keys: vec![],
+        })
+    }
+
+    pub fn path(&self, key: &str) -> String {
+        let key = key.replace(['/', '\\'], "_");
+        self.template.replace("{}", &key)
+    }
+
+    /// keys in the order their files are created
+    pub fn keys(&self) -> &[String] {
+        &self.keys
+    }
+
+    /// Get the writer of `key`, the bool is true if the file is just created.
+    pub fn get(&mut self, key: &str) -> FilterxResult<(&mut FilterxWriter, bool)> {
+        self.tick += 1;
+        let mut created = false;
+        let path = self.path(key);
+        if !self.writers.contains_key(&path) {
+            if self.writers.len() >= self.max_open {
+                self.close_least_used()?;
+            }
+            let append = self.created.contains(&path);
+            let writer = match (self.file_type, append) {
+                (FileContentType::Gzip, false) => {
+                    FilterxWriter::Gzip(GzipWriter::new(&path, 6, 1)?)
+                }
+                (FileContentType::Gzip, true) => {
+                    FilterxWriter::Gzip(GzipWriter::append(&path, 6, 1)?)
+                }
+                (_, false) => FilterxWriter::Plain(PlainWriter::new(&path)?),
+                (_, true) => FilterxWriter::Plain(PlainWriter::append(&path)?),
+            };
+            if !append {
+                created = true;
+                self.created.insert(path.clone());
+                self.keys.push(key.to_string());
+            }
+            self.writers.insert(path.clone(), (writer, 0));
+        }
+        let (writer, last_used) = self.writers.get_mut(&path).unwrap();
+        *last_used = self.tick;
+        Ok((writer, created))
+    }
+
+    fn close_least_used(&mut self) -> FilterxResult<()> {
+        let path = self
+            .writers
+            .iter()
+            .min_by_key(|(_, (_, last_used))| *last_used)
+            .map(|(path, _)| path.clone());
+        if let Some(path) = path {
+            let (mut writer, _) = self.writers.remove(&path).unwrap();
+            writer.flush()?;
+        }
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> FilterxResult<()> {
+        for (writer, _) in self.writers.values_mut() {
+            writer.flush()?;
+        }
+        Ok(())
+    }
+}
+
 impl Write for FilterxWriter {
     fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
         match self {
@@ -126,3 +261,53 @@ impl Write for FilterxWriter {
         Ok(())
     }
 }
+
+#[test]
+fn test_split_writer() {
+    use std::io::Read;
+
+    let dir = std::env::temp_dir().join("filterx_test_split");
+    std::fs::create_dir_all(&dir).unwrap();
+    for ext in ["txt", "txt.gz"] {
+        let template = dir.join(format!("out_{{}}.{}", ext));
+        let template = template.to_str().unwrap();
+        // a file left by an earlier run is truncated when it is created
+        let stale = template.replace("{}", "a");
+        std::fs::write(&stale, "stale\n").unwrap();
+
+        let mut split = SplitWriter::new(template, None, 2).unwrap();
+        let mut created = vec![];
+        for (i, key) in ["a", "b", "c", "a", "b", "a/c", "a_c"].iter().enumerate() {
+            let (writer, is_new) = split.get(key).unwrap();
+            writeln!(writer, "{}", i).unwrap();
+            created.push(is_new);
+            assert!(split.writers.len() <= 2);
+        }
+        split.flush().unwrap();
+        let split_keys = split.keys().to_vec();
+        drop(split);
+        assert_eq!(created, vec![true, true, true, false, false, true, false]);
+        assert_eq!(split_keys, vec!["a", "b", "c", "a/c"]);
+
+        let read = |key: &str| {
+            let mut text = String::new();
+            let mut fp = std::fs::File::open(template.replace("{}", key)).unwrap();
+            if ext.ends_with(".gz") {
+                flate2::read::MultiGzDecoder::new(fp)
+                    .read_to_string(&mut text)
+                    .unwrap();
+            } else {
+                fp.read_to_string(&mut text).unwrap();
+            }
+            text
+        };
+        // `a` and `b` are closed and reopened in append mode
+        assert_eq!(read("a"), "0\n3\n");
+        assert_eq!(read("b"), "1\n4\n");
+        assert_eq!(read("c"), "2\n");
+        // `a/c` and `a_c` share one file
+        assert_eq!(read("a_c"), "5\n6\n");
+    }
+    assert!(SplitWriter::new("out.txt", None, 2).is_err());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
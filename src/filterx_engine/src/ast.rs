@@ -14,6 +14,7 @@ pub use python_ast::ModInteractive;
 pub use python_ast::Stmt;
 pub use python_ast::StmtAssign;
 pub use python_ast::StmtAugAssign;
+pub use python_ast::StmtExpr;
 
 pub use python_ast::ExprAttribute;
 pub use python_ast::ExprBinOp;
@@ -22,6 +23,7 @@ pub use python_ast::ExprCall;
 pub use python_ast::ExprCompare;
 pub use python_ast::ExprConstant;
 pub use python_ast::ExprFormattedValue;
+pub use python_ast::ExprIfExp;
 pub use python_ast::ExprName;
 pub use python_ast::ExprSlice;
 pub use python_ast::ExprTuple;
@@ -29,6 +31,7 @@ pub use python_ast::ExprUnaryOp; // a.b(...)
 
 pub use python_ast::Constant;
 pub use python_ast::Expr;
+pub use python_ast::Keyword;
 pub use python_ast::ModExpression;
 
 pub use python_ast::ExprContext;
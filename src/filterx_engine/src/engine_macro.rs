@@ -6,7 +6,7 @@ macro_rules! eval {
                 ast::Expr::$expr(x) => x.eval($vm)?,
             )*
             _ => {
-                $vm.hint.white($msg).print_and_exit();
+                return $vm.hint.white($msg).bail();
             }
         }
     };
@@ -16,7 +16,7 @@ macro_rules! eval {
                 ast::Expr::$expr(x) => x.eval($vm)?,
             )*
             _ => {
-                $msg.print_and_exit()
+                return $msg.bail()
             }
         }
     };
@@ -25,10 +25,10 @@ macro_rules! eval {
 #[macro_export]
 macro_rules! eval_col {
     ($vm:expr, $target:expr, $msg:literal) => {
-        eval!($vm, $target, $msg, Name, Call, Constant, UnaryOp)
+        eval!($vm, $target, $msg, Name, Call, Attribute, Constant, UnaryOp)
     };
     ($vm:expr, $target:expr, $msg:literal,) => {
-        eval!($vm, $target, $msg, Name, Call, Constant, UnaryOp)
+        eval!($vm, $target, $msg, Name, Call, Attribute, Constant, UnaryOp)
     };
 }
 
@@ -52,15 +52,21 @@ macro_rules! eval_str {
     };
 }
 
+/// Define a group of builtin functions, one entry per function:
+/// `(name, can_expression, can_inplace, (aliases)), arity, [arg types] => eval`.
+/// The doc is read from `doc/<name>.md` and the module `<name>` is declared.
 #[macro_export]
 macro_rules! builtin_function {
     (   $group: ident,
         $(
-            ($name:ident, $expression:expr, $inplace:expr $(, ($($alias:ident),*))?),
+            ($name:ident, $expression:expr, $inplace:expr $(, ($($alias:tt),*))?),
+            $arity:ident $(($($n:literal),*))?,
+            [$($ty:ident),*]
+            => $eval:expr,
         )*
     ) => {
         pub use crate::eval::call::BuiltinFunction;
-        pub static $group: &'static [BuiltinFunction] = &[
+        pub static $group: &[BuiltinFunction] = &[
             $(
 
                 BuiltinFunction {
@@ -68,9 +74,12 @@ macro_rules! builtin_function {
                     alias: &[stringify!($name), $(
                         $(stringify!($alias)),*
                     )?],
+                    arity: $crate::eval::call::Arity::$arity $(($($n),*))?,
+                    arg_types: &[$($crate::eval::call::ArgType::$ty),*],
                     can_expression: $expression,
                     can_inplace: $inplace,
                     doc: include_str!(concat!("doc/", stringify!($name), ".md")),
+                    eval: $eval,
                 },
             )*
         ];
@@ -87,11 +96,12 @@ macro_rules! execuable {
         use crate::vm::VmMode;
         if $vm.mode == VmMode::Print {
             let h = &mut $vm.hint;
-            h.white("Con't use ")
+            return h
+                .white("Con't use ")
                 .red($target)
                 .white(" in builtin function")
                 .green(" `print`.")
-                .print_and_exit()
+                .bail();
         }
     };
 }
@@ -1,11 +1,12 @@
 use std::ops::Deref;
 
 use super::super::ast;
-use filterx_core::value;
 use filterx_core::value::Value;
 use filterx_core::FilterxResult;
 
+use crate::eval::ops::binop;
 use crate::eval::Eval;
+use crate::group::{Agg, AggKind};
 use crate::vm::Vm;
 
 use crate::{eval, eval_col, execuable};
@@ -18,47 +19,65 @@ impl<'a> Eval<'a> for ast::StmtAssign {
         execuable!(vm, "=");
         if self.targets.len() != 1 {
             let h = &mut vm.hint;
-            h.white("Dosn't support unpacking multiple assignment expression")
+            return h
+                .white("Dosn't support unpacking multiple assignment expression")
                 .next_line()
                 .next_line()
                 .green(" Right: alias(new_col) = col1 + col2")
                 .next_line()
                 .red(" Wrong: alias(new_col) = col1, col2")
                 .next_line()
-                .print_and_exit();
+                .bail();
         }
 
         let target = self.targets.first().unwrap();
 
         let pass = match target {
-            ast::Expr::Call(c) => {
-                let function_name = match c.func.as_ref() {
-                    ast::Expr::Name(n) => n.eval(vm)?,
-                    _ => unreachable!(),
-                };
-                match function_name {
-                    value::Value::Name(n) => match n.name.as_str() {
-                        "alias" => true,
-                        _ => false,
-                    },
-                    _ => false,
-                }
-            }
+            ast::Expr::Call(c) => match c.func.as_ref() {
+                ast::Expr::Name(n) => n.id.as_str() == "alias",
+                _ => unreachable!(),
+            },
             _ => false,
         };
 
+        // a variable is looked up before columns, and would shadow the column
+        if let ast::Expr::Call(c) = target {
+            if let Some(ast::Expr::Name(n)) = c.args.first().filter(|_| pass) {
+                if vm.variables.contains_key(n.id.as_str()) {
+                    let h = &mut vm.hint;
+                    return h
+                        .white("Column ")
+                        .cyan(n.id.as_str())
+                        .white(" has the same name as a variable, use another name.")
+                        .bail();
+                }
+            }
+        }
+
         let new_col = eval_col!(vm, target, "A column needed in left `=` expression");
         let new_col_name = new_col.column()?;
+        if vm.group.as_ref().is_some_and(|g| g.active) {
+            return assign_aggregation(vm, new_col_name, self.value.deref());
+        }
+
         let exist = vm.source().check_column(new_col_name);
 
+        // min_len = 100
+        if !exist && !pass {
+            if let ast::Expr::Name(n) = target {
+                return assign_variable(vm, n.id.as_str(), self.value.deref());
+            }
+        }
+
         if !exist && !pass {
             let h = &mut vm.hint;
-            h.white("Use")
+            return h
+                .white("Use")
                 .cyan(" `alias` ")
                 .bold()
                 .white("to create a new column.")
                 .green(" alias(new_col) = col1 + col2")
-                .print_and_exit();
+                .bail();
         }
 
         let right = self.value.deref();
@@ -79,12 +98,20 @@ impl<'a> Eval<'a> for ast::StmtAssign {
                 .next_line()
                 .cyan("5. BinOp, like col1 + col2")
                 .next_line()
+                .cyan("6. IfExp, like 'long' if len(seq) > 100 else 'short'")
+                .next_line()
+                .cyan("7. Compare, like qual(qual) > 30 or not (a > 1 and b < 2)")
+                .next_line()
                 .next_line(),
             Constant,
             Name,
             Call,
+            Attribute,
             UnaryOp,
-            BinOp
+            BinOp,
+            IfExp,
+            Compare,
+            BoolOp
         );
         let if_append = match exist {
             true => None,
@@ -96,14 +123,125 @@ impl<'a> Eval<'a> for ast::StmtAssign {
     }
 }
 
+/// a name which is not a column holds a constant, e.g. `min_len = 100`
+fn assign_variable(vm: &mut Vm, name: &str, right: &ast::Expr) -> FilterxResult<Value> {
+    let value = eval!(
+        vm,
+        right,
+        "A constant needed in variable assignment, e.g. min_len = 100",
+        Constant,
+        Name,
+        UnaryOp,
+        BinOp
+    );
+    if !value.is_const() && !value.is_keyword() {
+        let h = &mut vm.hint;
+        return h
+            .white("Variable ")
+            .cyan(name)
+            .white(" can only hold a constant.")
+            .next_line()
+            .white("Use")
+            .cyan(" `alias` ")
+            .bold()
+            .white("to create a new column.")
+            .green(" alias(new_col) = col1 + col2")
+            .bail();
+    }
+    vm.variables.insert(name.to_string(), value);
+    Ok(Value::None)
+}
+
+/// group_by(a); alias(n) = count()
+fn assign_aggregation(vm: &mut Vm, name: &str, right: &ast::Expr) -> FilterxResult<Value> {
+    let kind = match right {
+        ast::Expr::Call(c) => match c.func.as_ref() {
+            ast::Expr::Name(n) => AggKind::from_name(n.id.as_str()),
+            _ => None,
+        },
+        _ => None,
+    };
+    let kind = match kind {
+        Some(kind) => kind,
+        None => {
+            let h = &mut vm.hint;
+            return h
+                .white("After ")
+                .cyan("group_by")
+                .white(", only aggregations can be assigned: ")
+                .green("count, sum, mean, min, max, n_unique")
+                .next_line()
+                .white("Example: ")
+                .green("group_by(a); alias(n) = count()")
+                .bail();
+        }
+    };
+    vm.group.as_mut().unwrap().in_agg = true;
+    let value = eval!(vm, right, "An aggregation is required.", Call);
+    let group = vm.group.as_mut().unwrap();
+    group.in_agg = false;
+    let input = match value {
+        Value::None => None,
+        v => Some(v.expr()?),
+    };
+    group.aggs.push(Agg {
+        name: name.to_string(),
+        kind,
+        input,
+    });
+    Ok(Value::None)
+}
+
 impl<'a> Eval<'a> for ast::StmtAugAssign {
     type Output = Value;
     fn eval(&self, vm: &'a mut Vm) -> FilterxResult<Self::Output> {
         execuable!(vm, &format!("{:?}", self.op));
+        if let ast::Expr::Name(n) = self.target.deref() {
+            if let Some(left) = vm.variables.get(n.id.as_str()).cloned() {
+                let value = eval!(
+                    vm,
+                    self.value.deref(),
+                    "A constant needed.",
+                    Constant,
+                    Name,
+                    UnaryOp,
+                    BinOp
+                );
+                if !value.is_const() {
+                    let h = &mut vm.hint;
+                    return h
+                        .white("Variable ")
+                        .cyan(n.id.as_str())
+                        .white(" can only hold a constant.")
+                        .bail();
+                }
+                match self.op {
+                    ast::Operator::Add
+                    | ast::Operator::Sub
+                    | ast::Operator::Mult
+                    | ast::Operator::Div
+                    | ast::Operator::Mod
+                    | ast::Operator::Pow
+                    | ast::Operator::FloorDiv => {}
+                    _ => {
+                        let h = &mut vm.hint;
+                        return h
+                            .white("Only support ")
+                            .cyan("+=, -=, *=, /=, %=, **=, //=")
+                            .white(" on variable ")
+                            .cyan(n.id.as_str())
+                            .bail();
+                    }
+                }
+                let value = binop(left, value, self.op)?;
+                vm.variables.insert(n.id.to_string(), value);
+                return Ok(Value::None);
+            }
+        }
         let target = eval!(vm, self.target.deref(), "A column needed.", Call, Name);
         let target_name = target.column()?;
 
-        vm.source().has_column(target_name);
+        vm.source().has_column(target_name)?;
         let value = eval!(
             vm,
             self.value.deref(),
@@ -122,13 +260,16 @@ impl<'a> Eval<'a> for ast::StmtAugAssign {
             ast::Operator::Mult => target * value,
             ast::Operator::Div => target / value,
             ast::Operator::Mod => target % value,
+            ast::Operator::Pow => target.pow(value),
+            ast::Operator::FloorDiv => target.floor_div(value),
             ast::Operator::BitAnd => target.and(value),
             ast::Operator::BitOr => target.or(value),
             _ => {
                 let h = &mut vm.hint;
-                h.white("Only support binary op: ")
-                    .cyan("+, -, *, /, %, &, |")
-                    .print_and_exit();
+                return h
+                    .white("Only support binary op: ")
+                    .cyan("+, -, *, /, //, %, **, &, |")
+                    .bail();
             }
         };
 
@@ -137,3 +278,14 @@ impl<'a> Eval<'a> for ast::StmtAugAssign {
         Ok(Value::None)
     }
 }
+
+#[test]
+fn test_assign_variable() {
+    use polars::prelude::*;
+
+    let run = |script: &str| crate::vm::run_df(df!("a" => [1, 2, 3, 4, 5]).unwrap(), script);
+    let df = run("min_a = 3; a > min_a").unwrap();
+    assert_eq!(df.height(), 2);
+    // a column named like a variable would be shadowed by the variable
+    assert!(run("n = 3; alias(n) = a * 2").is_err());
+}
@@ -1,6 +1,6 @@
 use super::super::*;
 
-pub fn alias<'a>(vm: &'a mut Vm, args: &Vec<ast::Expr>) -> FilterxResult<value::Value> {
+pub fn alias(vm: &mut Vm, args: &[ast::Expr]) -> FilterxResult<value::Value> {
     expect_args_len(args, 1)?;
     let col = eval_col!(
         vm,
@@ -1,9 +1,9 @@
 use super::super::*;
 use polars::prelude::DataType;
 
-pub fn cast<'a>(
-    vm: &'a mut Vm,
-    args: &Vec<ast::Expr>,
+pub fn cast(
+    vm: &mut Vm,
+    args: &[ast::Expr],
     new_type: &str,
     inplace: bool,
 ) -> FilterxResult<value::Value> {
@@ -15,7 +15,7 @@ pub fn cast<'a>(
     );
     let name = col_name.column()?;
     let e = col_name.expr()?;
-    vm.source_mut().has_column(name);
+    vm.source_mut().has_column(name)?;
     let new_type = match new_type.to_lowercase().as_str() {
         "int" => DataType::Int32,
         "float" => DataType::Float32,
@@ -34,7 +34,8 @@ pub fn cast<'a>(
         "u16" => DataType::UInt16,
         "" => {
             let h = &mut vm.hint;
-            h.white("cast: avaliable cast funtion: ")
+            return h
+                .white("cast: avaliable cast funtion: ")
                 .green("cast_int")
                 .white(", ")
                 .green("cast_float")
@@ -62,7 +63,7 @@ pub fn cast<'a>(
                 .green("cast_u8")
                 .white(", ")
                 .green("cast_u16")
-                .print_and_exit();
+                .bail();
         }
         _ => panic!("unsupported type"),
     };
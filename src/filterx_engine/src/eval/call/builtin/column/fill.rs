@@ -2,9 +2,9 @@ use super::super::*;
 use filterx_core::value::Value;
 use polars::prelude::Literal;
 
-pub fn fill<'a>(
-    vm: &'a mut Vm,
-    args: &Vec<ast::Expr>,
+pub fn fill(
+    vm: &mut Vm,
+    args: &[ast::Expr],
     inplace: bool,
     is_na: bool,
 ) -> FilterxResult<value::Value> {
@@ -23,10 +23,10 @@ pub fn fill<'a>(
     );
     let name = col_name.column()?;
     let mut e = col_name.expr()?;
-    vm.source_mut().has_column(name);
-    if is_na{
+    vm.source_mut().has_column(name)?;
+    if is_na {
         e = e.fill_nan(const_value.lit());
-    }else{
+    } else {
         e = e.fill_null(const_value.lit());
     }
     if inplace {
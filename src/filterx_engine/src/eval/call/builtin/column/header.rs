@@ -1,11 +1,51 @@
 use super::super::*;
 
+/// Columns of the source as `[index, name, type]` rows, the caller prints them.
 pub fn header(vm: &mut Vm) -> FilterxResult<value::Value> {
     let source = &vm.source_mut();
     let schema = source.columns()?;
-    println!("index\tname\ttype");
-    for (index, (name, t)) in schema.iter().enumerate() {
-        println!("{}\t{}\t{}", index, name, t);
-    }
-    std::process::exit(0);
+    let rows = schema
+        .iter()
+        .enumerate()
+        .map(|(index, (name, t))| {
+            value::Value::List(vec![
+                value::Value::Int(index as i64),
+                value::Value::Str(name.to_string()),
+                value::Value::Str(t.to_string()),
+            ])
+        })
+        .collect();
+    let schema = value::Value::List(rows);
+    vm.status.schema = Some(schema.clone());
+    Ok(schema)
+}
+
+#[test]
+fn test_header() {
+    use filterx_core::writer::FilterxWriter;
+    use filterx_source::{DataframeSource, Source, SourceType};
+    use polars::prelude::*;
+
+    let df = df!("a" => [1, 2, 3], "b" => ["x", "y", "z"]).unwrap();
+    let mut source = DataframeSource::new(df.lazy());
+    source.set_init_column_names(&vec!["a".to_string(), "b".to_string()]);
+    let writer = FilterxWriter::new(None, None, None).unwrap();
+    let mut vm = Vm::from_source(Source::new(source.into(), SourceType::Csv), writer);
+    // the filter after `header()` is skipped
+    vm.eval_once("header(); a > 1", None).unwrap();
+    let row = |i: i64, name: &str, t: &str| {
+        value::Value::List(vec![
+            value::Value::Int(i),
+            value::Value::Str(name.to_string()),
+            value::Value::Str(t.to_string()),
+        ])
+    };
+    assert_eq!(
+        vm.status.schema,
+        Some(value::Value::List(vec![
+            row(0, "a", "i32"),
+            row(1, "b", "str")
+        ]))
+    );
+    assert_eq!(vm.into_df().unwrap().height(), 3);
 }
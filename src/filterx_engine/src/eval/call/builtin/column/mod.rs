@@ -1,9 +1,10 @@
 use crate::builtin_function;
+use polars::frame::UniqueKeepStrategy;
 
 builtin_function! {
     FUNCTION_COLUMN,
-    (rm, false, false),
-    (alias,false, false),
+    (rm, false, false), Any, [Column] => |vm, c| rm(vm, c.args),
+    (alias, false, false), Exact(1), [Column] => |vm, c| alias(vm, c.args),
     (cast, true, true, (
         cast_int,
         cast_float,
@@ -18,16 +19,30 @@ builtin_function! {
         cast_i8,
         cast_u16,
         cast_u8
-    )),
-    (dup,false, false, (dup_any, dup_none, dup_last)),
-    (fill, true, true, (fill_null, fill_nan)),
-    (is_null, false, false, (is_not_null)),
-    (rename, false, false),
-    (select, false, false),
-    (print, false, false, (format, fmt, f)),
-    (sort, false, false, (Sort, sorT)),
-    (col, true, false, (c)),
-    (drop_null, false, false),
-    (header, false, false),
-    (occ, false, false, (occ_lte, occ_gte)),
+    )), Exact(1), [Column] => |vm, c| {
+        let target = c.name.strip_prefix("cast_").unwrap_or("");
+        cast(vm, c.args, target, c.inplace)
+    },
+    (dup, false, false, (dup_any, dup_none, dup_last)), Any, [Column] => |vm, c| {
+        let keep = match c.name {
+            "dup_none" => UniqueKeepStrategy::None,
+            "dup_last" => UniqueKeepStrategy::Last,
+            "dup_any" => UniqueKeepStrategy::Any,
+            _ => UniqueKeepStrategy::First,
+        };
+        dup(vm, c.args, keep)
+    },
+    (fill, true, true, (fill_null, fill_nan)), Exact(2), [Column, Any]
+        => |vm, c| fill(vm, c.args, c.inplace, c.name == "fill_nan"),
+    (is_null, true, false, (is_not_null)), Exact(1), [Column]
+        => |vm, c| is_null(vm, c.args, c.name == "is_not_null"),
+    (rename, false, false), Exact(2), [Column, Column] => |vm, c| rename(vm, c.args),
+    (select, false, false), Any, [Column] => |vm, c| select(vm, c.args),
+    (print, false, false, (format, fmt, f)), Exact(1), [Str] => |vm, c| print(vm, c.args),
+    (sort, false, false, (Sort, sorT)), Any, [Column] => |vm, c| sort(vm, c.args, c.name != "Sort"),
+    (col, true, false, (c)), Exact(1), [Any] => |vm, c| col(vm, c.args),
+    (drop_null, false, false), Any, [Column] => |vm, c| drop_null(vm, c.args),
+    (header, false, false), Exact(0), [] => |vm, _| header(vm),
+    (occ, false, false, (occ_lte, occ_gte)), AtLeast(2), [Column, Int]
+        => |vm, c| occ(vm, c.args, c.name == "occ_lte"),
 }
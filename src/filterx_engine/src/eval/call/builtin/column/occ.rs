@@ -2,7 +2,7 @@ use super::super::*;
 use polars::chunked_array::ops::SortMultipleOptions;
 use polars::prelude::{col as polars_col, JoinArgs};
 
-pub fn occ(vm: &mut Vm, args: &Vec<ast::Expr>, lte: bool) -> FilterxResult<value::Value> {
+pub fn occ(vm: &mut Vm, args: &[ast::Expr], lte: bool) -> FilterxResult<value::Value> {
     if args.len() < 2 {
         return Err(FilterxError::RuntimeError(
             "occ: expected at least 2 arguments".to_string(),
@@ -18,7 +18,7 @@ pub fn occ(vm: &mut Vm, args: &Vec<ast::Expr>, lte: bool) -> FilterxResult<value
         let arg = &args[i];
         let v = eval_col!(vm, arg, "occ: expected column(s) name as argument(s)");
         let col = v.column()?;
-        vm.source_mut().has_column(col);
+        vm.source_mut().has_column(col)?;
         cols.push(col.to_string());
     }
 
@@ -3,7 +3,7 @@ use polars::prelude::format_str;
 use crate::vm::VmMode;
 
 use super::super::*;
-use polars::prelude::{col, Expr};
+use polars::prelude::{col, DataType, Expr, GetOutput};
 use regex::Regex;
 
 use lazy_static::lazy_static;
@@ -11,7 +11,8 @@ use lazy_static::lazy_static;
 use filterx_core::util;
 
 lazy_static! {
-    static ref REGEX_PATTERN: Regex = Regex::new(r"\{([\(\)a-zA-Z0-9_\-+/*,=\\ ]*)\}").unwrap();
+    static ref REGEX_PATTERN: Regex =
+        Regex::new(r#"\{([\(\)a-zA-Z0-9_\-+/*,=<>!.%'"\\ ]*)\}"#).unwrap();
     static ref REGEX_VARNAME: Regex = Regex::new(r"^[_a-zA-Z]+[a-zA-Z_0-9]*$").unwrap();
 }
 
@@ -40,20 +41,25 @@ fn parse_format_string(s: &str, vm: &mut Vm) -> FilterxResult<(String, Option<Ve
                 "Error format string, empty format value".to_string(),
             ));
         }
+        if let Some(v) = vm.variables.get(item) {
+            cols.push(v.expr()?);
+            continue;
+        }
         if REGEX_VARNAME.is_match(item) {
             // recheck columns name
-            vm.source_mut().has_column(item);
+            vm.source_mut().has_column(item)?;
             cols.push(col(item));
             continue;
         }
         let ast = vm.ast(item)?;
         if !ast.is_expression() {
             let h = &mut vm.hint;
-            h.white("Only support expression in ")
+            return h
+                .white("Only support expression in ")
                 .cyan("print")
                 .white(", but got ")
                 .red(item)
-                .print_and_exit();
+                .bail();
         }
         let ast = ast.expression().unwrap();
         vm.set_print_expr(item);
@@ -99,9 +105,31 @@ fn test_parse_format_string() {
     assert_eq!(cols[0], col("seq").str().len_chars());
 }
 
+/// `format_str` can't cast a list to string, format it by `util::list_to_string`.
+fn display_lists(vm: &mut Vm, cols: Vec<Expr>) -> FilterxResult<Vec<Expr>> {
+    let named: Vec<Expr> = cols
+        .iter()
+        .enumerate()
+        .map(|(i, c)| c.clone().alias(format!("{}{}", FORMAT_COLUMN_NAME, i)))
+        .collect();
+    let schema = vm.source_mut().lazy().select(named).collect_schema()?;
+    let cols = cols
+        .into_iter()
+        .zip(schema.iter_values())
+        .map(|(c, dtype)| match dtype.is_list() {
+            true => c.map(
+                |c| util::list_to_string(&c).map(Some),
+                GetOutput::from_type(DataType::String),
+            ),
+            false => c,
+        })
+        .collect();
+    Ok(cols)
+}
+
 const FORMAT_COLUMN_NAME: &str = "__@#$fmt__";
 
-pub fn print<'a>(vm: &'a mut Vm, args: &Vec<ast::Expr>) -> FilterxResult<value::Value> {
+pub fn print(vm: &mut Vm, args: &[ast::Expr]) -> FilterxResult<value::Value> {
     expect_args_len(args, 1)?;
     if vm.status.consume_rows >= vm.status.limit_rows {
         return Ok(value::Value::None);
@@ -120,7 +148,7 @@ pub fn print<'a>(vm: &'a mut Vm, args: &Vec<ast::Expr>) -> FilterxResult<value::
         vm.set_mode(VmMode::Print);
         let (fmt_, cols_) = parse_format_string(&value, vm)?;
         vm.set_mode(VmMode::Expression);
-        let cols_ = cols_.unwrap_or(vec![]);
+        let cols_ = display_lists(vm, cols_.unwrap_or(vec![]))?;
         vm.expr_cache
             .insert(value.clone(), (fmt_.clone(), cols_.clone()));
         let value = vm.expr_cache.get(&value).unwrap();
@@ -1,13 +1,13 @@
 use super::super::*;
 use polars::chunked_array::ops::SortMultipleOptions;
 
-pub fn sort(vm: &mut Vm, args: &Vec<ast::Expr>, incr: bool) -> FilterxResult<value::Value> {
+pub fn sort(vm: &mut Vm, args: &[ast::Expr], incr: bool) -> FilterxResult<value::Value> {
     let mut cols = Vec::new();
 
     for arg in args {
         let v = eval_col!(vm, arg, "sort: expected column(s) name as argument(s)");
         let col = v.column()?;
-        vm.source_mut().has_column(col);
+        vm.source_mut().has_column(col)?;
         cols.push(col.to_string());
     }
 
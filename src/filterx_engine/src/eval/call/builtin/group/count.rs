@@ -0,0 +1,15 @@
+use super::super::*;
+use super::group_by::aggregate;
+use crate::group::AggKind;
+
+pub fn count(
+    vm: &mut Vm,
+    args: &[ast::Expr],
+    keywords: &[ast::Keyword],
+) -> FilterxResult<value::Value> {
+    // count(seq, 'N') outside of `group_by` counts the matches of a pattern
+    if args.len() == 2 && !vm.group.as_ref().is_some_and(|g| g.in_agg) {
+        return count_matches(vm, args, keywords);
+    }
+    aggregate(vm, args, AggKind::Count)
+}
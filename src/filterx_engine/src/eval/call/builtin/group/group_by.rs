@@ -0,0 +1,80 @@
+use super::super::*;
+use crate::group::{AggKind, GroupBy};
+use crate::util::check_repeat;
+
+pub fn group_by(vm: &mut Vm, args: &[ast::Expr]) -> FilterxResult<value::Value> {
+    if args.is_empty() {
+        return Err(FilterxError::RuntimeError(
+            "group_by: expected at least 1 argument".to_string(),
+        ));
+    }
+    let mut keys = vec![];
+    for arg in args {
+        let v = eval_col!(vm, arg, "group_by: expected column(s) name as argument(s)");
+        let col = v.column()?;
+        vm.source_mut().has_column(col)?;
+        keys.push(col.to_string());
+    }
+
+    if check_repeat(&keys) {
+        let h = &mut vm.hint;
+        return h
+            .white("group_by: column name should not repeat, but got: ")
+            .cyan(&keys.join(", "))
+            .bail();
+    }
+
+    match vm.group.as_mut() {
+        // fasta/fastq evaluate the expression once per chunk
+        Some(group) if group.keys == keys => {
+            group.aggs.clear();
+            group.active = true;
+        }
+        Some(_) => {
+            let h = &mut vm.hint;
+            return h
+                .white("group_by: only one ")
+                .cyan("group_by")
+                .white(" is allowed.")
+                .bail();
+        }
+        None => vm.group = Some(GroupBy::new(keys)),
+    }
+    Ok(value::Value::None)
+}
+
+/// Evaluate the input of an aggregation, `None` means counting rows.
+pub fn aggregate(vm: &mut Vm, args: &[ast::Expr], kind: AggKind) -> FilterxResult<value::Value> {
+    let name = match kind {
+        AggKind::Count => "count",
+        AggKind::Sum => "sum",
+        AggKind::Mean => "mean",
+        AggKind::Min => "min",
+        AggKind::Max => "max",
+        AggKind::NUnique => "n_unique",
+    };
+    if !vm.group.as_ref().is_some_and(|g| g.in_agg) {
+        let h = &mut vm.hint;
+        return h
+            .white("Function `")
+            .cyan(name)
+            .bold()
+            .white("` is an aggregation, it only works after `group_by`. Example: ")
+            .green(&format!("group_by(a); alias(x) = {}(b)", name))
+            .bail();
+    }
+    if kind == AggKind::Count && args.is_empty() {
+        return Ok(value::Value::None);
+    }
+    expect_args_len(args, 1)?;
+    let v = eval_col!(
+        vm,
+        &args[0],
+        "aggregation: expected a column name or an expression as argument"
+    );
+    if !v.is_expr() {
+        let col = v.column()?;
+        vm.source_mut().has_column(col)?;
+    }
+    Ok(value::Value::named_expr(None, v.expr()?))
+}
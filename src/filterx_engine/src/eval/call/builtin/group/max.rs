@@ -0,0 +1,15 @@
+use super::super::*;
+use super::group_by::aggregate;
+use crate::group::AggKind;
+
+pub fn max(vm: &mut Vm, args: &[ast::Expr]) -> FilterxResult<value::Value> {
+    // max(a, b, ...) outside of `group_by` works row-wise across columns
+    if args.len() > 1 && !vm.group.as_ref().is_some_and(|g| g.in_agg) {
+        let mut exprs = Vec::with_capacity(args.len());
+        for arg in args {
+            exprs.push(number_arg(vm, arg, "max")?.expr()?);
+        }
+        return Ok(value::Value::named_expr(None, row_extreme(exprs, true)));
+    }
+    aggregate(vm, args, AggKind::Max)
+}
@@ -0,0 +1,15 @@
+use super::super::*;
+use super::group_by::aggregate;
+use crate::group::AggKind;
+
+pub fn min(vm: &mut Vm, args: &[ast::Expr]) -> FilterxResult<value::Value> {
+    // min(a, b, ...) outside of `group_by` works row-wise across columns
+    if args.len() > 1 && !vm.group.as_ref().is_some_and(|g| g.in_agg) {
+        let mut exprs = Vec::with_capacity(args.len());
+        for arg in args {
+            exprs.push(number_arg(vm, arg, "min")?.expr()?);
+        }
+        return Ok(value::Value::named_expr(None, row_extreme(exprs, false)));
+    }
+    aggregate(vm, args, AggKind::Min)
+}
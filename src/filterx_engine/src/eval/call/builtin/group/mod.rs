@@ -0,0 +1,32 @@
+use crate::builtin_function;
+
+builtin_function! {
+    FUNCTION_GROUP,
+    (group_by, false, false), AtLeast(1), [Column] => |vm, c| group_by(vm, c.args),
+    (count, true, false), Range(0, 2), [Column, Str] => |vm, c| count(vm, c.args, c.keywords),
+    (sum, false, false), Exact(1), [Column] => |vm, c| sum(vm, c.args),
+    (mean, false, false), Exact(1), [Column] => |vm, c| mean(vm, c.args),
+    (min, true, false), AtLeast(1), [Column] => |vm, c| min(vm, c.args),
+    (max, true, false), AtLeast(1), [Column] => |vm, c| max(vm, c.args),
+    (n_unique, false, false), Exact(1), [Column] => |vm, c| n_unique(vm, c.args),
+}
+
+#[test]
+fn test_count() {
+    use crate::eval::call::functions::{check_arity, get_function};
+    use crate::vm::run_df;
+    use crate::vm::Vm;
+    use filterx_source::SourceType;
+    use polars::prelude::*;
+
+    let df = df!("a" => [1, 2, 3], "b" => ["x", "y", "x"]).unwrap();
+
+    let count = get_function("count").unwrap();
+    let mut vm = Vm::mock(SourceType::Csv);
+    assert!(check_arity(&mut vm, count, "count", 0).is_ok());
+
+    let df = run_df(df, "group_by(b); alias(n) = count()").unwrap();
+    let df = df.sort(["b"], Default::default()).unwrap();
+    let n: Vec<Option<u32>> = df.column("n").unwrap().u32().unwrap().into_iter().collect();
+    assert_eq!(n, vec![Some(2), Some(1)]);
+}
@@ -0,0 +1,7 @@
+use super::super::*;
+use super::group_by::aggregate;
+use crate::group::AggKind;
+
+pub fn n_unique(vm: &mut Vm, args: &[ast::Expr]) -> FilterxResult<value::Value> {
+    aggregate(vm, args, AggKind::NUnique)
+}
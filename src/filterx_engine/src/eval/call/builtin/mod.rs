@@ -6,7 +6,7 @@ pub use crate::{eval, eval_col, eval_int, eval_str, execuable};
 pub use filterx_core::{value, FilterxError, FilterxResult};
 pub use filterx_source::{source::SourceType, DataframeSource};
 
-pub fn expect_args_len(args: &Vec<crate::ast::Expr>, len: usize) -> FilterxResult<()> {
+pub fn expect_args_len(args: &[crate::ast::Expr], len: usize) -> FilterxResult<()> {
     if args.len() != len {
         return Err(FilterxError::RuntimeError(format!(
             "Expect {} args, but got {} args.",
@@ -17,6 +17,133 @@ pub fn expect_args_len(args: &Vec<crate::ast::Expr>, len: usize) -> FilterxResul
     Ok(())
 }
 
+/// Check that only the keyword arguments in `allowed` are passed.
+pub fn expect_keywords(keywords: &[ast::Keyword], allowed: &[&str]) -> FilterxResult<()> {
+    for keyword in keywords {
+        match &keyword.arg {
+            Some(arg) if allowed.contains(&arg.as_str()) => {}
+            Some(arg) => {
+                return Err(FilterxError::RuntimeError(format!(
+                    "Unexpected keyword argument `{}`, expected one of: {}.",
+                    arg,
+                    allowed.join(", ")
+                )));
+            }
+            None => {
+                return Err(FilterxError::RuntimeError(
+                    "`**kwargs` is not supported.".to_string(),
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Get the value of keyword argument `name`, like `regex=False`.
+pub fn keyword_arg<'k>(keywords: &'k [ast::Keyword], name: &str) -> Option<&'k ast::Expr> {
+    keywords
+        .iter()
+        .find(|k| k.arg.as_ref().is_some_and(|arg| arg.as_str() == name))
+        .map(|k| &k.value)
+}
+
+/// The argument at `index`, or the keyword argument `name`, like python.
+pub fn arg_or_keyword<'k>(
+    args: &'k [ast::Expr],
+    keywords: &'k [ast::Keyword],
+    index: usize,
+    name: &str,
+) -> Option<&'k ast::Expr> {
+    args.get(index).or_else(|| keyword_arg(keywords, name))
+}
+
+pub fn keyword_bool(
+    vm: &mut Vm,
+    keywords: &[ast::Keyword],
+    name: &str,
+    default: bool,
+) -> FilterxResult<bool> {
+    match keyword_arg(keywords, name) {
+        Some(value) => bool_arg(vm, value, name),
+        None => Ok(default),
+    }
+}
+
+pub fn bool_arg(vm: &mut Vm, value: &ast::Expr, name: &str) -> FilterxResult<bool> {
+    match eval!(
+        vm,
+        value,
+        "keyword argument: expected True or False",
+        Constant,
+        Name
+    ) {
+        value::Value::Bool(b) => Ok(b),
+        _ => {
+            let h = &mut vm.hint;
+            h.white("Keyword argument ")
+                .cyan(name)
+                .white(" expected ")
+                .green("True")
+                .white(" or ")
+                .green("False")
+                .bail()
+        }
+    }
+}
+
+pub fn int_arg(vm: &mut Vm, value: &ast::Expr, name: &str) -> FilterxResult<i64> {
+    match eval!(
+        vm,
+        value,
+        "argument: expected an integer",
+        Constant,
+        UnaryOp,
+        Name
+    ) {
+        value::Value::Int(i) => Ok(i),
+        _ => {
+            let h = &mut vm.hint;
+            h.white("Argument ")
+                .cyan(name)
+                .white(" expected an integer")
+                .bail()
+        }
+    }
+}
+
+pub fn keyword_number(
+    vm: &mut Vm,
+    keywords: &[ast::Keyword],
+    name: &str,
+    default: f64,
+) -> FilterxResult<f64> {
+    match keyword_arg(keywords, name) {
+        Some(value) => float_arg(vm, value, name),
+        None => Ok(default),
+    }
+}
+
+pub fn float_arg(vm: &mut Vm, value: &ast::Expr, name: &str) -> FilterxResult<f64> {
+    match eval!(
+        vm,
+        value,
+        "argument: expected a number",
+        Constant,
+        UnaryOp,
+        Name
+    ) {
+        value::Value::Int(i) if i >= 0 => Ok(i as f64),
+        value::Value::Float(f) if f >= 0.0 => Ok(f),
+        _ => {
+            let h = &mut vm.hint;
+            h.white("Argument ")
+                .cyan(name)
+                .white(" expected a non-negative number")
+                .bail()
+        }
+    }
+}
+
 pub mod string;
 pub use string::*;
 
@@ -31,3 +158,6 @@ pub use number::*;
 
 pub mod row;
 pub use row::*;
+
+pub mod group;
+pub use group::*;
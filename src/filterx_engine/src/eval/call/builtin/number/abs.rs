@@ -1,22 +1,8 @@
 use super::super::*;
 
-pub fn abs<'a>(
-    vm: &'a mut Vm,
-    args: &Vec<ast::Expr>,
-    inplace: bool,
-) -> FilterxResult<value::Value> {
+pub fn abs(vm: &mut Vm, args: &[ast::Expr], inplace: bool) -> FilterxResult<value::Value> {
     expect_args_len(args, 1)?;
-    let col_name = eval_col!(
-        vm,
-        &args[0],
-        "abs: expected a column name as first argument"
-    );
-    let name = col_name.column()?;
-    let e = col_name.expr()?;
-    vm.source_mut().has_column(name);
-    if inplace {
-        vm.source_mut().with_column(e.alias(name), None);
-        return Ok(value::Value::None);
-    }
-    Ok(value::Value::named_expr(None, e))
+    let v = number_arg(vm, &args[0], "abs")?;
+    let e = v.expr()?.abs();
+    number_result(vm, &v, e, inplace)
 }
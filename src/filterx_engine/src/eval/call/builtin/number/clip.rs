@@ -0,0 +1,10 @@
+use super::super::*;
+
+pub fn clip(vm: &mut Vm, args: &[ast::Expr], inplace: bool) -> FilterxResult<value::Value> {
+    expect_args_len(args, 3)?;
+    let v = number_arg(vm, &args[0], "clip")?;
+    let lower = number_arg(vm, &args[1], "clip")?;
+    let upper = number_arg(vm, &args[2], "clip")?;
+    let e = v.expr()?.clip(lower.expr()?, upper.expr()?);
+    number_result(vm, &v, e, inplace)
+}
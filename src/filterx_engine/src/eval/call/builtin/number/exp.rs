@@ -0,0 +1,8 @@
+use super::super::*;
+
+pub fn exp(vm: &mut Vm, args: &[ast::Expr], inplace: bool) -> FilterxResult<value::Value> {
+    expect_args_len(args, 1)?;
+    let v = number_arg(vm, &args[0], "exp")?;
+    let e = v.expr()?.exp();
+    number_result(vm, &v, e, inplace)
+}
@@ -0,0 +1,18 @@
+use super::super::*;
+
+pub fn floor(
+    vm: &mut Vm,
+    args: &[ast::Expr],
+    inplace: bool,
+    ceil: bool,
+) -> FilterxResult<value::Value> {
+    expect_args_len(args, 1)?;
+    let name = if ceil { "ceil" } else { "floor" };
+    let v = number_arg(vm, &args[0], name)?;
+    let e = if ceil {
+        v.expr()?.ceil()
+    } else {
+        v.expr()?.floor()
+    };
+    number_result(vm, &v, e, inplace)
+}
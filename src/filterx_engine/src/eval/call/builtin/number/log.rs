@@ -0,0 +1,41 @@
+use super::super::*;
+
+/// `base` is None for `log`, which takes an optional base as second argument.
+pub fn log(
+    vm: &mut Vm,
+    args: &[ast::Expr],
+    inplace: bool,
+    base: Option<f64>,
+) -> FilterxResult<value::Value> {
+    let base = match base {
+        Some(base) => {
+            expect_args_len(args, 1)?;
+            base
+        }
+        None if args.len() == 2 => {
+            let base = eval!(
+                vm,
+                &args[1],
+                "log: expected a number as base",
+                Constant,
+                UnaryOp,
+                BinOp
+            );
+            base.float()?
+        }
+        None => {
+            expect_args_len(args, 1)?;
+            std::f64::consts::E
+        }
+    };
+    if base <= 0.0 || base == 1.0 {
+        let h = &mut vm.hint;
+        return h
+            .white("log: base must be positive and not equal to 1, but got ")
+            .red(&base.to_string())
+            .bail();
+    }
+    let v = number_arg(vm, &args[0], "log")?;
+    let e = v.expr()?.log(base);
+    number_result(vm, &v, e, inplace)
+}
@@ -2,5 +2,125 @@ use crate::builtin_function;
 
 builtin_function! {
     FUNCTION_NUMBER,
-    (abs, true, true),
+    (abs, true, true), Exact(1), [Number] => |vm, c| abs(vm, c.args, c.inplace),
+    (log, true, true, (ln, log2, log10)), Range(1, 2), [Number, Number] => |vm, c| {
+        let base = match c.name {
+            "log2" => Some(2.0),
+            "log10" => Some(10.0),
+            _ => None,
+        };
+        log(vm, c.args, c.inplace, base)
+    },
+    (exp, true, true), Exact(1), [Number] => |vm, c| exp(vm, c.args, c.inplace),
+    (sqrt, true, true), Exact(1), [Number] => |vm, c| sqrt(vm, c.args, c.inplace),
+    (pow, true, true), Exact(2), [Number, Number] => |vm, c| pow(vm, c.args, c.inplace),
+    (round, true, true), Range(1, 2), [Number, Int] => |vm, c| round(vm, c.args, c.inplace),
+    (floor, true, true, (ceil)), Exact(1), [Number]
+        => |vm, c| floor(vm, c.args, c.inplace, c.name == "ceil"),
+    (clip, true, true), Exact(3), [Number, Number, Number] => |vm, c| clip(vm, c.args, c.inplace),
+}
+
+use super::*;
+
+/// Evaluate the number a math function works on, a column, an expression or a constant.
+pub fn number_arg(vm: &mut Vm, arg: &ast::Expr, function: &str) -> FilterxResult<value::Value> {
+    let v = eval!(
+        vm,
+        arg,
+        vm.hint
+            .cyan(function)
+            .white(": expected a column, an expression or a number as argument, ")
+            .white("example: ")
+            .cyan(&format!("{}(a)", function))
+            .white(", ")
+            .cyan(&format!("{}(a * 2)", function)),
+        Name,
+        Call,
+        Attribute,
+        Constant,
+        UnaryOp,
+        BinOp,
+        IfExp
+    );
+    if let Some(name) = v.name() {
+        vm.source_mut().has_column(name)?;
+    }
+    Ok(v)
+}
+
+/// Return `e` as an expression, or replace the column for the inplace version.
+pub fn number_result(
+    vm: &mut Vm,
+    v: &value::Value,
+    e: polars::prelude::Expr,
+    inplace: bool,
+) -> FilterxResult<value::Value> {
+    if inplace {
+        let name = v.column()?;
+        vm.source_mut().with_column(e.alias(name), None);
+        return Ok(value::Value::None);
+    }
+    Ok(value::Value::named_expr(None, e))
+}
+
+/// Row-wise minimum or maximum of several expressions, nulls are skipped.
+pub fn row_extreme(exprs: Vec<polars::prelude::Expr>, max: bool) -> polars::prelude::Expr {
+    use polars::prelude::when;
+    exprs
+        .into_iter()
+        .reduce(|a, b| {
+            let keep_a = if max {
+                a.clone().gt_eq(b.clone())
+            } else {
+                a.clone().lt_eq(b.clone())
+            };
+            when(b.clone().is_null().or(keep_a)).then(a).otherwise(b)
+        })
+        .unwrap()
+}
+
+#[test]
+fn test_math() {
+    use polars::prelude::*;
+
+    let run = |script: &str| {
+        let df = df!("a" => [-4.0, 1.0, 9.0], "b" => [2, 3, 4]).unwrap();
+        crate::vm::run_df(df, script)
+    };
+    let c = |script: &str| -> Vec<f64> {
+        let df = run(&format!("alias(c) = {}", script)).unwrap();
+        let c = df.column("c").unwrap().cast(&DataType::Float64).unwrap();
+        c.f64().unwrap().into_no_null_iter().collect()
+    };
+
+    assert_eq!(c("abs(a)"), vec![4.0, 1.0, 9.0]);
+    assert_eq!(c("sqrt(abs(a))"), vec![2.0, 1.0, 3.0]);
+    assert_eq!(c("a ** 2"), vec![16.0, 1.0, 81.0]);
+    assert_eq!(c("pow(b, 2)"), vec![4.0, 9.0, 16.0]);
+    assert_eq!(c("a // 3"), vec![-2.0, 0.0, 3.0]);
+    assert_eq!(c("round(log2(b), 2)"), vec![1.0, 1.58, 2.0]);
+    assert_eq!(c("round(log(b, 2), 2)"), vec![1.0, 1.58, 2.0]);
+    assert_eq!(c("round(ln(exp(b)), 6)"), vec![2.0, 3.0, 4.0]);
+    assert_eq!(c("round(log10(b * 25), 4)"), vec![1.699, 1.8751, 2.0]);
+    assert_eq!(c("round(a / 3, 1)"), vec![-1.3, 0.3, 3.0]);
+    assert_eq!(c("floor(a / 3)"), vec![-2.0, 0.0, 3.0]);
+    assert_eq!(c("ceil(a / 3)"), vec![-1.0, 1.0, 3.0]);
+    assert_eq!(c("clip(a, 0, 5)"), vec![0.0, 1.0, 5.0]);
+    // row-wise min/max outside of `group_by`
+    assert_eq!(c("min(a, b)"), vec![-4.0, 1.0, 4.0]);
+    assert_eq!(c("max(a, b, 5)"), vec![5.0, 5.0, 9.0]);
+
+    let df = run("abs_(a)").unwrap();
+    let a: Vec<f64> = df
+        .column("a")
+        .unwrap()
+        .f64()
+        .unwrap()
+        .into_no_null_iter()
+        .collect();
+    assert_eq!(a, vec![4.0, 1.0, 9.0]);
+
+    assert!(run("alias(c) = log(b, 1)").is_err());
+    assert!(run("alias(c) = round(a, -1)").is_err());
+    assert!(run("alias(c) = sqrt(d)").is_err());
 }
@@ -0,0 +1,9 @@
+use super::super::*;
+
+pub fn pow(vm: &mut Vm, args: &[ast::Expr], inplace: bool) -> FilterxResult<value::Value> {
+    expect_args_len(args, 2)?;
+    let v = number_arg(vm, &args[0], "pow")?;
+    let exponent = number_arg(vm, &args[1], "pow")?;
+    let e = v.expr()?.pow(exponent.expr()?);
+    number_result(vm, &v, e, inplace)
+}
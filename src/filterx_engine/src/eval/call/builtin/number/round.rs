@@ -0,0 +1,31 @@
+use super::super::*;
+
+pub fn round(vm: &mut Vm, args: &[ast::Expr], inplace: bool) -> FilterxResult<value::Value> {
+    let decimals = match args.len() {
+        1 => 0,
+        2 => {
+            let n = eval_int!(
+                vm,
+                &args[1],
+                "round: expected a non-negative number as decimals"
+            );
+            let n = n.int()?;
+            if n < 0 {
+                let h = &mut vm.hint;
+                return h
+                    .white("round: expected a non-negative number as decimals")
+                    .bail();
+            }
+            n as u32
+        }
+        n => {
+            return Err(FilterxError::RuntimeError(format!(
+                "Expect 1 or 2 args, but got {} args.",
+                n
+            )));
+        }
+    };
+    let v = number_arg(vm, &args[0], "round")?;
+    let e = v.expr()?.round(decimals);
+    number_result(vm, &v, e, inplace)
+}
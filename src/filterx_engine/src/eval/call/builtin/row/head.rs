@@ -1,6 +1,6 @@
 use super::super::*;
 
-pub fn head(vm: &mut Vm, args: &Vec<ast::Expr>) -> FilterxResult<value::Value> {
+pub fn head(vm: &mut Vm, args: &[ast::Expr]) -> FilterxResult<value::Value> {
     expect_args_len(args, 1)?;
     let n = eval_col!(
         vm,
@@ -13,15 +13,17 @@ pub fn head(vm: &mut Vm, args: &Vec<ast::Expr>) -> FilterxResult<value::Value> {
                 i as usize
             } else {
                 let h = &mut vm.hint;
-                h.white("head: expected a non-negative number as first argument, but got ")
+                return h
+                    .white("head: expected a non-negative number as first argument, but got ")
                     .cyan(&format!("{}", i))
-                    .print_and_exit();
+                    .bail();
             }
         }
         _ => {
             let h = &mut vm.hint;
-            h.white("head: expected a non-negative number as first argument")
-                .print_and_exit();
+            return h
+                .white("head: expected a non-negative number as first argument")
+                .bail();
         }
     };
 
@@ -2,6 +2,6 @@ use crate::builtin_function;
 
 builtin_function! {
     FUNCTION_ROW,
-    (head, false, false, (limit)),
-    (tail, false, false),
+    (head, false, false, (limit)), Exact(1), [Int] => |vm, c| head(vm, c.args),
+    (tail, false, false), Exact(1), [Int] => |vm, c| tail(vm, c.args),
 }
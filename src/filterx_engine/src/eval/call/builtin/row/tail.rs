@@ -1,6 +1,6 @@
 use super::super::*;
 
-pub fn tail(vm: &mut Vm, args: &Vec<ast::Expr>) -> FilterxResult<value::Value> {
+pub fn tail(vm: &mut Vm, args: &[ast::Expr]) -> FilterxResult<value::Value> {
     expect_args_len(args, 1)?;
 
     let n = eval!(
@@ -16,15 +16,17 @@ pub fn tail(vm: &mut Vm, args: &Vec<ast::Expr>) -> FilterxResult<value::Value> {
                 i as usize
             } else {
                 let h = &mut vm.hint;
-                h.white("tail: expected a non-negative number as first argument, but got ")
+                return h
+                    .white("tail: expected a non-negative number as first argument, but got ")
                     .cyan(&format!("{}", i))
-                    .print_and_exit();
+                    .bail();
             }
         }
         _ => {
             let h = &mut vm.hint;
-            h.white("tail: expected a non-negative number as first argument")
-                .print_and_exit();
+            return h
+                .white("tail: expected a non-negative number as first argument")
+                .bail();
         }
     };
 
@@ -0,0 +1,353 @@
+use super::super::*;
+use filterx_source::block::fasta::Fasta;
+use filterx_source::FastaRecordType;
+use polars::prelude::{Int64Chunked, IntoLazy, StringChunked};
+
+pub struct Adapter {
+    pub name: String,
+    pub seq: Vec<u8>,
+}
+
+/// Read all records of a fasta file as `(name, seq)`.
+pub fn read_fasta(path: &str) -> FilterxResult<Vec<(String, String)>> {
+    let mut fasta = Fasta::from_path(path, FastaRecordType::Dna, 0)?;
+    let mut records = vec![];
+    while let Some(record) = fasta.parse_next()? {
+        records.push((record.name().to_string(), record.seq().to_string()));
+    }
+    Ok(records)
+}
+
+/// The argument is an adapter sequence, or a fasta file of adapters.
+pub fn load_adapters(vm: &mut Vm, arg: &str, function: &str) -> FilterxResult<Vec<Adapter>> {
+    let adapters: Vec<Adapter> = if std::path::Path::new(arg).is_file() {
+        read_fasta(arg)?
+            .into_iter()
+            .map(|(name, seq)| Adapter {
+                name,
+                seq: seq.to_ascii_uppercase().into_bytes(),
+            })
+            .collect()
+    } else {
+        vec![Adapter {
+            name: arg.to_string(),
+            seq: arg.to_ascii_uppercase().into_bytes(),
+        }]
+    };
+    let invalid = adapters.is_empty()
+        || adapters
+            .iter()
+            .any(|a| a.seq.is_empty() || !a.seq.iter().all(u8::is_ascii_alphabetic));
+    if invalid {
+        let h = &mut vm.hint;
+        return h
+            .cyan(function)
+            .white(": expected a sequence or a fasta file, but got ")
+            .red(arg)
+            .bail();
+    }
+    Ok(adapters)
+}
+
+/// Number of errors allowed for `len` aligned bases, `max_error` below 1 is
+/// a rate, otherwise a count.
+pub fn allowed_errors(max_error: f64, len: usize) -> usize {
+    if max_error >= 1.0 {
+        max_error as usize
+    } else {
+        (max_error * len as f64) as usize
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AdapterMatch {
+    /// matched range of the read
+    pub start: usize,
+    pub end: usize,
+    pub errors: usize,
+    /// aligned bases of the adapter minus errors
+    pub matches: usize,
+}
+
+/// Bases of an IUPAC code as bits of A, C, G, T, 0 for other characters.
+#[inline(always)]
+pub fn iupac_bits(b: u8) -> u8 {
+    match b.to_ascii_uppercase() {
+        b'A' => 0b0001,
+        b'C' => 0b0010,
+        b'G' => 0b0100,
+        b'T' | b'U' => 0b1000,
+        b'R' => 0b0101,
+        b'Y' => 0b1010,
+        b'S' => 0b0110,
+        b'W' => 0b1001,
+        b'K' => 0b1100,
+        b'M' => 0b0011,
+        b'B' => 0b1110,
+        b'D' => 0b1101,
+        b'H' => 0b1011,
+        b'V' => 0b0111,
+        b'N' => 0b1111,
+        _ => 0,
+    }
+}
+
+/// Bases match if they share a base, IUPAC codes in both sides are supported.
+#[inline(always)]
+pub fn base_eq(a: u8, b: u8) -> bool {
+    iupac_bits(a) & iupac_bits(b) != 0
+}
+
+/// Find the adapter in the read, the adapter may also run past the 3' end of
+/// the read by at least `min_overlap` bases. Align with mismatches, and indels
+/// if `indel`, the match with the most matched bases wins, the leftmost on ties.
+pub fn find_3prime(
+    read: &[u8],
+    adapter: &[u8],
+    max_error: f64,
+    min_overlap: usize,
+    indel: bool,
+) -> Option<AdapterMatch> {
+    const INF: usize = usize::MAX / 2;
+    let m = adapter.len();
+    let n = read.len();
+    // one column of the table, (errors, start in read) for adapter[..i]
+    let mut prev: Vec<(usize, usize)> = (0..=m)
+        .map(|i| if indel || i == 0 { (i, 0) } else { (INF, 0) })
+        .collect();
+    let mut cur = prev.clone();
+    let mut best: Option<AdapterMatch> = None;
+    let mut consider = |errors: usize, aligned: usize, start: usize, end: usize| {
+        if errors > allowed_errors(max_error, aligned) {
+            return;
+        }
+        let matches = aligned - errors;
+        let better = match best {
+            None => true,
+            Some(b) => matches > b.matches || (matches == b.matches && start < b.start),
+        };
+        if better {
+            best = Some(AdapterMatch {
+                start,
+                end,
+                errors,
+                matches,
+            });
+        }
+    };
+    for j in 1..=n {
+        // the adapter may start at any base of the read
+        cur[0] = (0, j);
+        for i in 1..=m {
+            let (e, s) = prev[i - 1];
+            let mut cell = (e + !base_eq(read[j - 1], adapter[i - 1]) as usize, s);
+            if indel {
+                // a base of the adapter is missing in the read
+                if cur[i - 1].0 + 1 < cell.0 {
+                    cell = (cur[i - 1].0 + 1, cur[i - 1].1);
+                }
+                // an extra base in the read
+                if prev[i].0 + 1 < cell.0 {
+                    cell = (prev[i].0 + 1, prev[i].1);
+                }
+            }
+            cur[i] = cell;
+        }
+        if cur[m].0 < INF {
+            consider(cur[m].0, m, cur[m].1, j);
+        }
+        std::mem::swap(&mut prev, &mut cur);
+    }
+    // partial adapters at the 3' end, `prev` is the last column now
+    for (i, &(errors, start)) in prev.iter().enumerate().take(m).skip(min_overlap.max(1)) {
+        if errors < INF {
+            consider(errors, i, start, n);
+        }
+    }
+    best
+}
+
+/// Find the primer in the read, the primer may also start before the 5' end
+/// of the read by at least `min_overlap` bases.
+pub fn find_5prime(
+    read: &[u8],
+    primer: &[u8],
+    max_error: f64,
+    min_overlap: usize,
+    indel: bool,
+) -> Option<AdapterMatch> {
+    let read: Vec<u8> = read.iter().rev().copied().collect();
+    let primer: Vec<u8> = primer.iter().rev().copied().collect();
+    let m = find_3prime(&read, &primer, max_error, min_overlap, indel)?;
+    Some(AdapterMatch {
+        start: read.len() - m.end,
+        end: read.len() - m.start,
+        ..m
+    })
+}
+
+pub struct TrimOptions {
+    pub max_error: f64,
+    pub min_overlap: usize,
+    pub indel: bool,
+}
+
+impl TrimOptions {
+    pub fn from_keywords(vm: &mut Vm, keywords: &[ast::Keyword]) -> FilterxResult<Self> {
+        expect_keywords(keywords, &["mismatch", "min_overlap", "indel"])?;
+        Ok(TrimOptions {
+            max_error: keyword_number(vm, keywords, "mismatch", 0.1)?,
+            min_overlap: keyword_number(vm, keywords, "min_overlap", 3.0)? as usize,
+            indel: keyword_bool(vm, keywords, "indel", false)?,
+        })
+    }
+}
+
+/// The kept range of a read, the adapter hit and its position.
+struct Hit {
+    start: usize,
+    end: usize,
+    index: usize,
+    pos: i64,
+}
+
+/// Find the adapters in every read, cut `seq` and `qual` to the range `keep`
+/// returns, and add the columns `<kind>` and `<kind>_pos` for the adapter hit.
+pub fn trim_matches<F, K>(
+    vm: &mut Vm,
+    kind: &str,
+    adapters: &[Adapter],
+    find: F,
+    keep: K,
+) -> FilterxResult<value::Value>
+where
+    F: Fn(&[u8], &[u8]) -> Option<AdapterMatch>,
+    K: Fn(&AdapterMatch, usize) -> (usize, usize, i64),
+{
+    vm.source().has_column("seq")?;
+    let has_qual = vm.source_type() == SourceType::Fastq && vm.source().check_column("qual");
+    let mut df = vm.source_mut().lazy().collect()?;
+    let seq = df.column("seq")?.str()?.clone();
+    let qual = match has_qual {
+        true => Some(df.column("qual")?.str()?.clone()),
+        false => None,
+    };
+
+    let hits: Vec<Option<Hit>> = seq
+        .into_iter()
+        .map(|s| {
+            let s = s?.as_bytes();
+            let (index, m) = adapters
+                .iter()
+                .enumerate()
+                .filter_map(|(i, a)| find(s, &a.seq).map(|m| (i, m)))
+                .min_by_key(|(_, m)| (std::cmp::Reverse(m.matches), m.start))?;
+            let (start, end, pos) = keep(&m, s.len());
+            Some(Hit {
+                start,
+                end,
+                index,
+                pos,
+            })
+        })
+        .collect();
+
+    let cut = |ca: &StringChunked| -> StringChunked {
+        ca.into_iter()
+            .zip(&hits)
+            .map(|(s, hit)| match (s, hit) {
+                (Some(s), Some(hit)) => Some(s.get(hit.start..hit.end.min(s.len())).unwrap_or("")),
+                (s, _) => s,
+            })
+            .collect::<StringChunked>()
+            .with_name(ca.name().clone())
+    };
+    df.with_column(cut(&seq))?;
+    if let Some(qual) = &qual {
+        df.with_column(cut(qual))?;
+    }
+    // reads without a hit get an empty name and -1, a null would blank `print`
+    let names: StringChunked = hits
+        .iter()
+        .map(|hit| Some(hit.as_ref().map_or("", |h| adapters[h.index].name.as_str())))
+        .collect();
+    let pos: Int64Chunked = hits
+        .iter()
+        .map(|hit| Some(hit.as_ref().map_or(-1, |h| h.pos)))
+        .collect();
+    let pos_name = format!("{}_pos", kind);
+    df.with_column(names.with_name(kind.into()))?;
+    df.with_column(pos.with_name(pos_name.as_str().into()))?;
+
+    let source = vm.source_mut();
+    source.update(df.lazy());
+    for name in [kind.to_string(), pos_name] {
+        if !source.ret_column_names.contains(&name) {
+            source.ret_column_names.push(name);
+        }
+    }
+    Ok(value::Value::None)
+}
+
+#[test]
+fn test_find_adapter() {
+    let read = b"ACGTTTGCAAGATCGGAAGAGC";
+    let m = find_3prime(read, b"AGATCGGAAGAGC", 0.1, 3, false).unwrap();
+    assert_eq!((m.start, m.end, m.errors), (9, 22, 0));
+    // partial adapter at the 3' end, with one mismatch
+    let m = find_3prime(b"ACGTTTGCAAGATCGCA", b"AGATCGGAAGAGC", 1.0, 3, false).unwrap();
+    assert_eq!((m.start, m.end, m.errors), (9, 17, 1));
+    assert_eq!(
+        find_3prime(b"ACGTTTGCAAG", b"AGATCGGAAGAGC", 0.1, 3, false),
+        None
+    );
+    // one base of the adapter is deleted in the read
+    let m = find_3prime(b"ACGTTTGCAAGACGGAAGAGC", b"AGATCGGAAGAGC", 1.0, 3, true).unwrap();
+    assert_eq!((m.start, m.end, m.errors), (9, 21, 1));
+
+    let m = find_5prime(
+        b"CAGCCGCGGTAATACGTAG",
+        b"GTGCCAGCMGCCGCGGTAA",
+        0.1,
+        3,
+        false,
+    )
+    .unwrap();
+    assert_eq!((m.start, m.end), (0, 12));
+}
+
+#[test]
+fn test_trim_matches() {
+    use polars::prelude::*;
+
+    let df = df!(
+        "name" => ["r1", "r2"],
+        "seq" => ["ACGTTTGCAAGATCGGAAGAGC", "ACGTACGTACGT"]
+    )
+    .unwrap();
+    let df = crate::vm::run_df(df, "trim_adapter_('AGATCGGAAGAGC')").unwrap();
+
+    let seq: Vec<&str> = df
+        .column("seq")
+        .unwrap()
+        .str()
+        .unwrap()
+        .into_no_null_iter()
+        .collect();
+    assert_eq!(seq, vec!["ACGTTTGCA", "ACGTACGTACGT"]);
+    // no nulls for reads without a hit
+    let adapter = df.column("adapter").unwrap().str().unwrap();
+    assert_eq!(adapter.null_count(), 0);
+    assert_eq!(
+        adapter.into_no_null_iter().collect::<Vec<_>>(),
+        vec!["AGATCGGAAGAGC", ""]
+    );
+    let pos: Vec<Option<i64>> = df
+        .column("adapter_pos")
+        .unwrap()
+        .i64()
+        .unwrap()
+        .into_iter()
+        .collect();
+    assert_eq!(pos, vec![Some(10), Some(-1)]);
+}
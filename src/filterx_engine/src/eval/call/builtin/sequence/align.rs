@@ -0,0 +1,171 @@
+use super::super::*;
+use polars::prelude::{Column, DataType, GetOutput, Int64Chunked, IntoColumn};
+
+/// Levenshtein distance of two sequences, case insensitive.
+pub fn edit_distance_bytes(a: &[u8], b: &[u8]) -> usize {
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut cur = vec![0; b.len() + 1];
+    for (i, x) in a.iter().enumerate() {
+        cur[0] = i + 1;
+        for (j, y) in b.iter().enumerate() {
+            let sub = prev[j] + !x.eq_ignore_ascii_case(y) as usize;
+            cur[j + 1] = sub.min(prev[j + 1] + 1).min(cur[j] + 1);
+        }
+        std::mem::swap(&mut prev, &mut cur);
+    }
+    prev[b.len()]
+}
+
+/// Number of mismatches of two sequences of the same length, case
+/// insensitive, `None` if the lengths differ.
+pub fn hamming_bytes(a: &[u8], b: &[u8]) -> Option<usize> {
+    if a.len() != b.len() {
+        return None;
+    }
+    Some(
+        a.iter()
+            .zip(b)
+            .filter(|(x, y)| !x.eq_ignore_ascii_case(y))
+            .count(),
+    )
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AlignMode {
+    /// Smith-Waterman
+    Local,
+    /// Needleman-Wunsch
+    Global,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Scoring {
+    pub matched: i64,
+    pub mismatch: i64,
+    pub gap: i64,
+}
+
+/// Best alignment score of two sequences with a linear gap penalty.
+pub fn align_score_bytes(a: &[u8], b: &[u8], mode: AlignMode, scoring: Scoring) -> i64 {
+    let local = mode == AlignMode::Local;
+    let mut prev: Vec<i64> = (0..=b.len() as i64)
+        .map(|j| if local { 0 } else { j * scoring.gap })
+        .collect();
+    let mut cur = vec![0; b.len() + 1];
+    let mut best = 0;
+    for (i, x) in a.iter().enumerate() {
+        cur[0] = if local {
+            0
+        } else {
+            (i as i64 + 1) * scoring.gap
+        };
+        for (j, y) in b.iter().enumerate() {
+            let score = match x.eq_ignore_ascii_case(y) {
+                true => scoring.matched,
+                false => scoring.mismatch,
+            };
+            let mut cell = (prev[j] + score)
+                .max(prev[j + 1] + scoring.gap)
+                .max(cur[j] + scoring.gap);
+            if local {
+                cell = cell.max(0);
+                best = best.max(cell);
+            }
+            cur[j + 1] = cell;
+        }
+        std::mem::swap(&mut prev, &mut cur);
+    }
+    match mode {
+        AlignMode::Local => best,
+        AlignMode::Global => prev[b.len()],
+    }
+}
+
+/// Evaluate `f` on the sequence pairs of the first two arguments, each of them
+/// a column or a literal sequence.
+pub fn map_pairs<F>(
+    vm: &mut Vm,
+    args: &[ast::Expr],
+    function: &str,
+    f: F,
+) -> FilterxResult<value::Value>
+where
+    F: Fn(&[u8], &[u8]) -> Option<i64> + Send + Sync + 'static,
+{
+    let mut exprs = vec![];
+    for arg in &args[..2] {
+        let v = eval_col!(vm, arg, "expected a column or a sequence as argument");
+        if let Some(name) = v.name() {
+            vm.source_mut().has_column(name)?;
+        }
+        match v.expr() {
+            Ok(e) => exprs.push(e),
+            Err(_) => {
+                let h = &mut vm.hint;
+                return h
+                    .cyan(function)
+                    .white(": expected a column or a sequence as argument")
+                    .bail();
+            }
+        }
+    }
+    let b = exprs.pop().unwrap();
+    let a = exprs.pop().unwrap();
+    let e = a.map_many(
+        move |columns: &mut [Column]| {
+            let a = columns[0].str()?;
+            let b = columns[1].str()?;
+            // a literal is a single value, compared with every row
+            let pair = |x: Option<&str>, y: Option<&str>| f(x?.as_bytes(), y?.as_bytes());
+            let ca: Int64Chunked = match (a.len(), b.len()) {
+                (n, 1) if n != 1 => {
+                    let y = b.get(0);
+                    a.into_iter().map(|x| pair(x, y)).collect()
+                }
+                (1, n) if n != 1 => {
+                    let x = a.get(0);
+                    b.into_iter().map(|y| pair(x, y)).collect()
+                }
+                _ => a.into_iter().zip(b).map(|(x, y)| pair(x, y)).collect(),
+            };
+            Ok(Some(ca.with_name(a.name().clone()).into_column()))
+        },
+        &[b],
+        GetOutput::from_type(DataType::Int64),
+    );
+    Ok(value::Value::named_expr(None, e))
+}
+
+#[test]
+fn test_align() {
+    assert_eq!(edit_distance_bytes(b"kitten", b"sitting"), 3);
+    assert_eq!(edit_distance_bytes(b"ACGT", b"acgt"), 0);
+    assert_eq!(edit_distance_bytes(b"", b"ACG"), 3);
+
+    assert_eq!(hamming_bytes(b"ACGT", b"ACCA"), Some(2));
+    assert_eq!(hamming_bytes(b"ACGT", b"ACG"), None);
+
+    let scoring = Scoring {
+        matched: 2,
+        mismatch: -3,
+        gap: -5,
+    };
+    // the local alignment is GCAT
+    assert_eq!(
+        align_score_bytes(b"TTTGCATTT", b"AAGCATAA", AlignMode::Local, scoring),
+        8
+    );
+    assert_eq!(
+        align_score_bytes(b"ACGT", b"ACGT", AlignMode::Global, scoring),
+        8
+    );
+    // one gap
+    assert_eq!(
+        align_score_bytes(b"ACGT", b"AGT", AlignMode::Global, scoring),
+        1
+    );
+    assert_eq!(
+        align_score_bytes(b"AAAA", b"TTTT", AlignMode::Local, scoring),
+        0
+    );
+}
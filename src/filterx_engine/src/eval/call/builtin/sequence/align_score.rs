@@ -0,0 +1,44 @@
+use super::super::*;
+use super::align::{align_score_bytes, map_pairs, AlignMode, Scoring};
+
+pub fn align_score(
+    vm: &mut Vm,
+    args: &[ast::Expr],
+    keywords: &[ast::Keyword],
+) -> FilterxResult<value::Value> {
+    expect_keywords(keywords, &["mode", "match_score", "mismatch", "gap"])?;
+    let mode = match arg_or_keyword(args, keywords, 2, "mode") {
+        Some(value) => eval_str!(vm, value, "align_score: expected a string as mode")
+            .string()
+            .ok(),
+        None => Some("local".to_string()),
+    };
+    let mode = match mode.as_deref() {
+        Some("local") => AlignMode::Local,
+        Some("global") => AlignMode::Global,
+        _ => {
+            let h = &mut vm.hint;
+            return h
+                .cyan("align_score")
+                .white(": expected mode ")
+                .green("'local'")
+                .white(" or ")
+                .green("'global'")
+                .bail();
+        }
+    };
+    let mut score = |name: &str, default: i64| -> FilterxResult<i64> {
+        match keyword_arg(keywords, name) {
+            Some(value) => int_arg(vm, value, name),
+            None => Ok(default),
+        }
+    };
+    let scoring = Scoring {
+        matched: score("match_score", 2)?,
+        mismatch: score("mismatch", -3)?,
+        gap: score("gap", -5)?,
+    };
+    map_pairs(vm, args, "align_score", move |a, b| {
+        Some(align_score_bytes(a, b, mode, scoring))
+    })
+}
@@ -0,0 +1,212 @@
+use super::adapter::iupac_bits;
+
+/// NCBI genetic codes, amino acids of the 64 codons in the order of
+/// `TTT, TTC, TTA, TTG, TCT, ...`, the same as the `ncbieaa` of NCBI gc.prt.
+const GENETIC_CODES: [(u8, &[u8; 64]); 25] = [
+    (
+        1,
+        b"FFLLSSSSYY**CC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+    ),
+    (
+        2,
+        b"FFLLSSSSYY**CCWWLLLLPPPPHHQQRRRRIIMMTTTTNNKKSS**VVVVAAAADDEEGGGG",
+    ),
+    (
+        3,
+        b"FFLLSSSSYY**CCWWTTTTPPPPHHQQRRRRIIMMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+    ),
+    (
+        4,
+        b"FFLLSSSSYY**CCWWLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+    ),
+    (
+        5,
+        b"FFLLSSSSYY**CCWWLLLLPPPPHHQQRRRRIIMMTTTTNNKKSSSSVVVVAAAADDEEGGGG",
+    ),
+    (
+        6,
+        b"FFLLSSSSYYQQCC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+    ),
+    (
+        9,
+        b"FFLLSSSSYY**CCWWLLLLPPPPHHQQRRRRIIIMTTTTNNNKSSSSVVVVAAAADDEEGGGG",
+    ),
+    (
+        10,
+        b"FFLLSSSSYY**CCCWLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+    ),
+    (
+        11,
+        b"FFLLSSSSYY**CC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+    ),
+    (
+        12,
+        b"FFLLSSSSYY**CC*WLLLSPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+    ),
+    (
+        13,
+        b"FFLLSSSSYY**CCWWLLLLPPPPHHQQRRRRIIMMTTTTNNKKSSGGVVVVAAAADDEEGGGG",
+    ),
+    (
+        14,
+        b"FFLLSSSSYYY*CCWWLLLLPPPPHHQQRRRRIIIMTTTTNNNKSSSSVVVVAAAADDEEGGGG",
+    ),
+    (
+        16,
+        b"FFLLSSSSYY*LCC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+    ),
+    (
+        21,
+        b"FFLLSSSSYY**CCWWLLLLPPPPHHQQRRRRIIMMTTTTNNNKSSSSVVVVAAAADDEEGGGG",
+    ),
+    (
+        22,
+        b"FFLLSS*SYY*LCC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+    ),
+    (
+        23,
+        b"FF*LSSSSYY**CC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+    ),
+    (
+        24,
+        b"FFLLSSSSYY**CCWWLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSSKVVVVAAAADDEEGGGG",
+    ),
+    (
+        25,
+        b"FFLLSSSSYY**CCGWLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+    ),
+    (
+        26,
+        b"FFLLSSSSYY**CC*WLLLAPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+    ),
+    (
+        27,
+        b"FFLLSSSSYYQQCCWWLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+    ),
+    (
+        28,
+        b"FFLLSSSSYYQQCCWWLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+    ),
+    (
+        29,
+        b"FFLLSSSSYYYYCC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+    ),
+    (
+        30,
+        b"FFLLSSSSYYEECC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+    ),
+    (
+        31,
+        b"FFLLSSSSYYEECCWWLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+    ),
+    (
+        33,
+        b"FFLLSSSSYYY*CCWWLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSSKVVVVAAAADDEEGGGG",
+    ),
+];
+
+pub fn genetic_code(id: i64) -> Option<&'static [u8; 64]> {
+    GENETIC_CODES
+        .iter()
+        .find(|(i, _)| *i as i64 == id)
+        .map(|(_, code)| *code)
+}
+
+pub fn genetic_code_ids() -> Vec<String> {
+    GENETIC_CODES.iter().map(|(i, _)| i.to_string()).collect()
+}
+
+/// Index of the bases in a codon, the order of T, C, A, G.
+const CODON_ORDER: [u8; 4] = [0b1000, 0b0010, 0b0001, 0b0100];
+
+/// Amino acid of a codon. Ambiguous codons are expanded, `X` if they code
+/// different amino acids or contain a character other than IUPAC codes.
+pub fn translate_codon(code: &[u8; 64], codon: &[u8]) -> u8 {
+    let mut aa = None;
+    for (i1, b1) in CODON_ORDER.iter().enumerate() {
+        if iupac_bits(codon[0]) & b1 == 0 {
+            continue;
+        }
+        for (i2, b2) in CODON_ORDER.iter().enumerate() {
+            if iupac_bits(codon[1]) & b2 == 0 {
+                continue;
+            }
+            for (i3, b3) in CODON_ORDER.iter().enumerate() {
+                if iupac_bits(codon[2]) & b3 == 0 {
+                    continue;
+                }
+                let a = code[i1 * 16 + i2 * 4 + i3];
+                match aa {
+                    None => aa = Some(a),
+                    Some(x) if x != a => return b'X',
+                    _ => {}
+                }
+            }
+        }
+    }
+    aa.unwrap_or(b'X')
+}
+
+/// Translate from the first base, the trailing incomplete codon is dropped.
+pub fn translate_bytes(code: &[u8; 64], seq: &[u8], to_stop: bool) -> String {
+    let mut protein = String::with_capacity(seq.len() / 3);
+    for codon in seq.chunks_exact(3) {
+        let aa = translate_codon(code, codon);
+        if to_stop && aa == b'*' {
+            break;
+        }
+        protein.push(aa as char);
+    }
+    protein
+}
+
+fn is_start(codon: &[u8]) -> bool {
+    codon.eq_ignore_ascii_case(b"ATG") || codon.eq_ignore_ascii_case(b"AUG")
+}
+
+/// Longest ORF on the forward strand, `(start, end)` from `ATG` to the stop
+/// codon included. The first one wins on ties.
+pub fn longest_orf(code: &[u8; 64], seq: &[u8]) -> Option<(usize, usize)> {
+    let mut best: Option<(usize, usize)> = None;
+    for frame in 0..3 {
+        let mut start = None;
+        let mut i = frame;
+        while i + 3 <= seq.len() {
+            let codon = &seq[i..i + 3];
+            match start {
+                None if is_start(codon) => start = Some(i),
+                Some(s) if translate_codon(code, codon) == b'*' => {
+                    let end = i + 3;
+                    if best
+                        .is_none_or(|(bs, be)| end - s > be - bs || (end - s == be - bs && s < bs))
+                    {
+                        best = Some((s, end));
+                    }
+                    start = None;
+                }
+                _ => {}
+            }
+            i += 3;
+        }
+    }
+    best
+}
+
+#[test]
+fn test_translate() {
+    let code = genetic_code(1).unwrap();
+    assert_eq!(translate_bytes(code, b"ATGGCCTAAGGN", false), "MA*G");
+    assert_eq!(translate_bytes(code, b"ATGGCCTAAGG", true), "MA");
+    // ambiguous codons
+    assert_eq!(translate_bytes(code, b"GGNTTYAAN", false), "GFX");
+    assert_eq!(translate_bytes(code, b"AUGUGA", false), "M*");
+    // TGA codes Trp in the vertebrate mitochondrial code
+    assert_eq!(
+        translate_bytes(genetic_code(2).unwrap(), b"ATGTGA", false),
+        "MW"
+    );
+    assert!(genetic_code(7).is_none());
+
+    assert_eq!(longest_orf(code, b"CCATGTAAGATGAAATTTTGACC"), Some((9, 21)));
+    assert_eq!(longest_orf(code, b"CCATGAAA"), None);
+}
@@ -0,0 +1,177 @@
+use std::collections::HashMap;
+
+/// Code of a base in 2 bits, `None` for bases other than A, C, G, T.
+#[inline(always)]
+fn base_code(b: u8) -> Option<usize> {
+    match b {
+        b'A' | b'a' => Some(0),
+        b'C' | b'c' => Some(1),
+        b'G' | b'g' => Some(2),
+        b'T' | b't' | b'U' | b'u' => Some(3),
+        _ => None,
+    }
+}
+
+/// Codes of all triplets of a sequence, `None` if a triplet has other bases.
+fn triplets(seq: &[u8]) -> Vec<Option<usize>> {
+    seq.windows(3)
+        .map(|t| Some(base_code(t[0])? << 4 | base_code(t[1])? << 2 | base_code(t[2])?))
+        .collect()
+}
+
+/// Shannon entropy of the k-mer frequencies in bits, case insensitive.
+pub fn shannon_entropy(seq: &[u8], k: usize) -> f64 {
+    if k == 0 || seq.len() < k {
+        return 0.0;
+    }
+    let seq = seq.to_ascii_uppercase();
+    let mut counts: HashMap<&[u8], usize> = HashMap::new();
+    for kmer in seq.windows(k) {
+        *counts.entry(kmer).or_default() += 1;
+    }
+    let total = (seq.len() - k + 1) as f64;
+    counts
+        .values()
+        .map(|&c| {
+            let p = c as f64 / total;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+/// Triplet counts of a window, the score is `sum(c * (c - 1) / 2) / (l - 1)`
+/// for `l` triplets in the window.
+struct DustWindow {
+    counts: [usize; 64],
+    pairs: usize,
+    len: usize,
+}
+
+impl DustWindow {
+    fn new() -> Self {
+        DustWindow {
+            counts: [0; 64],
+            pairs: 0,
+            len: 0,
+        }
+    }
+
+    fn push(&mut self, t: Option<usize>) {
+        if let Some(t) = t {
+            self.pairs += self.counts[t];
+            self.counts[t] += 1;
+            self.len += 1;
+        }
+    }
+
+    fn pop(&mut self, t: Option<usize>) {
+        if let Some(t) = t {
+            self.counts[t] -= 1;
+            self.pairs -= self.counts[t];
+            self.len -= 1;
+        }
+    }
+
+    fn score(&self) -> f64 {
+        if self.len < 2 {
+            return 0.0;
+        }
+        self.pairs as f64 / (self.len - 1) as f64
+    }
+}
+
+/// Call `f(start, window, score)` with the DUST score of every window of
+/// `window` bases, a sequence shorter than `window` is a single window.
+fn dust_windows(seq: &[u8], window: usize, mut f: impl FnMut(usize, usize, f64)) {
+    let n = seq.len();
+    if n < 3 {
+        return;
+    }
+    let window = window.clamp(3, n);
+    let triplets = triplets(seq);
+    // a window of `window` bases has `window - 2` triplets
+    let w = window - 2;
+    let mut dust = DustWindow::new();
+    triplets[..w].iter().for_each(|&t| dust.push(t));
+    for start in 0..=(n - window) {
+        if start > 0 {
+            dust.pop(triplets[start - 1]);
+            dust.push(triplets[start + w - 1]);
+        }
+        f(start, window, dust.score());
+    }
+}
+
+/// The highest symmetric DUST score of all windows of `window` bases, so the
+/// score does not grow with the length of the sequence. Triplets with bases
+/// other than A, C, G, T are not counted.
+pub fn dust_score(seq: &[u8], window: usize) -> f64 {
+    let mut max = 0.0f64;
+    dust_windows(seq, window, |_, _, score| max = max.max(score));
+    max
+}
+
+/// Mask all windows of `window` bases with a DUST score above `threshold`,
+/// a sequence shorter than `window` is a single window.
+pub fn dust_mask(seq: &[u8], window: usize, threshold: f64) -> Vec<bool> {
+    let mut masked = vec![false; seq.len()];
+    // end of the last masked range, the masked windows overlap
+    let mut masked_to = 0;
+    dust_windows(seq, window, |start, window, score| {
+        if score > threshold {
+            masked[start.max(masked_to)..start + window].fill(true);
+            masked_to = start + window;
+        }
+    });
+    masked
+}
+
+/// Length of the longest run of one base, case insensitive.
+pub fn max_homopolymer_len(seq: &[u8]) -> usize {
+    seq.chunk_by(|a, b| a.eq_ignore_ascii_case(b))
+        .map(|run| run.len())
+        .max()
+        .unwrap_or(0)
+}
+
+#[test]
+fn test_complexity() {
+    assert_eq!(shannon_entropy(b"AAAA", 1), 0.0);
+    assert_eq!(shannon_entropy(b"ACGT", 1), 2.0);
+    assert_eq!(shannon_entropy(b"acgtACGT", 1), 2.0);
+    assert_eq!(shannon_entropy(b"AACC", 2), 3f64.log2());
+    assert_eq!(shannon_entropy(b"AC", 3), 0.0);
+
+    // 8 identical triplets, 28 pairs
+    assert_eq!(dust_score(b"AAAAAAAAAA", 64), 4.0);
+    assert_eq!(dust_score(b"ACGTTGCA", 64), 0.0);
+    assert_eq!(dust_score(b"NNNNNN", 64), 0.0);
+    assert_eq!(dust_score(b"AAAAAAAAAAACGTTGCA", 10), 4.0);
+
+    // a pseudo-random sequence keeps a low score whatever its length
+    let mut x: u32 = 1;
+    let random: Vec<u8> = (0..2000)
+        .map(|_| {
+            x = x.wrapping_mul(1103515245).wrapping_add(12345);
+            b"ACGT"[(x >> 16) as usize % 4]
+        })
+        .collect();
+    assert!(dust_score(&random[..200], 64) < 2.0);
+    assert!(dust_score(&random, 64) < 2.0);
+    // a low complexity region in a long read is found
+    let mut seq = random[..500].to_vec();
+    seq.extend_from_slice(&b"CA".repeat(20));
+    seq.extend_from_slice(&random[500..1000]);
+    assert!(dust_score(&seq, 64) > 2.0);
+
+    let seq = b"ACGTTGCAGTCAAAAAAAAAAAAAAAAAAAAGTCCATGACTGA";
+    let masked = dust_mask(seq, 16, 2.0);
+    let first = masked.iter().position(|&m| m).unwrap();
+    let last = masked.iter().rposition(|&m| m).unwrap();
+    assert!(first <= 12 && last >= 30);
+    assert!(!masked[0] && !masked[seq.len() - 1]);
+    assert!(dust_mask(b"ACGTTGCAGTCA", 64, 2.0).iter().all(|&m| !m));
+
+    assert_eq!(max_homopolymer_len(b"ACCCGTTaaAA"), 4);
+    assert_eq!(max_homopolymer_len(b""), 0);
+}
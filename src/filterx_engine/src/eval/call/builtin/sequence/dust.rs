@@ -0,0 +1,46 @@
+use super::super::*;
+use super::complexity::dust_score;
+use polars::prelude::*;
+
+use polars_arrow::{
+    array::{Array, ArrayRef, Float32Array, Utf8ViewArray},
+    buffer::Buffer,
+    datatypes::ArrowDataType,
+};
+
+fn compute_dust_kernel(array: &Utf8ViewArray, window: usize) -> ArrayRef {
+    let values: Buffer<f32> = array
+        .values_iter()
+        .map(|seq| dust_score(seq.as_bytes(), window) as f32)
+        .collect::<Vec<_>>()
+        .into();
+    let array = Float32Array::new(ArrowDataType::Float32, values, array.validity().cloned());
+    Box::new(array)
+}
+
+pub fn dust(
+    vm: &mut Vm,
+    args: &[ast::Expr],
+    keywords: &[ast::Keyword],
+) -> FilterxResult<value::Value> {
+    expect_keywords(keywords, &["window"])?;
+    let col_name = eval_col!(
+        vm,
+        &args[0],
+        "dust: expected a column name as first argument"
+    );
+    if let Some(name) = col_name.name() {
+        vm.source_mut().has_column(name)?;
+    }
+    let window = keyword_number(vm, keywords, "window", 64.0)? as usize;
+    let e = col_name.expr()?.map(
+        move |s| {
+            let ca = s.str()?.as_string();
+            let ca =
+                ca.apply_kernel_cast::<Float32Type>(&|array| compute_dust_kernel(array, window));
+            Ok(Some(ca.into_column()))
+        },
+        GetOutput::float_type(),
+    );
+    Ok(value::Value::named_expr(None, e))
+}
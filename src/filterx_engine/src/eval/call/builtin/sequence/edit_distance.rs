@@ -0,0 +1,8 @@
+use super::super::*;
+use super::align::{edit_distance_bytes, map_pairs};
+
+pub fn edit_distance(vm: &mut Vm, args: &[ast::Expr]) -> FilterxResult<value::Value> {
+    map_pairs(vm, args, "edit_distance", |a, b| {
+        Some(edit_distance_bytes(a, b) as i64)
+    })
+}
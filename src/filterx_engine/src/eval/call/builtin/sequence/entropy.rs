@@ -0,0 +1,57 @@
+use super::super::*;
+use super::complexity::shannon_entropy;
+use polars::prelude::*;
+
+use polars_arrow::{
+    array::{Array, ArrayRef, Float32Array, Utf8ViewArray},
+    buffer::Buffer,
+    datatypes::ArrowDataType,
+};
+
+fn compute_entropy_kernel(array: &Utf8ViewArray, k: usize) -> ArrayRef {
+    let values: Buffer<f32> = array
+        .values_iter()
+        .map(|seq| shannon_entropy(seq.as_bytes(), k) as f32)
+        .collect::<Vec<_>>()
+        .into();
+    let array = Float32Array::new(ArrowDataType::Float32, values, array.validity().cloned());
+    Box::new(array)
+}
+
+pub fn entropy(
+    vm: &mut Vm,
+    args: &[ast::Expr],
+    keywords: &[ast::Keyword],
+) -> FilterxResult<value::Value> {
+    expect_keywords(keywords, &["k"])?;
+    let col_name = eval_col!(
+        vm,
+        &args[0],
+        "entropy: expected a column name as first argument"
+    );
+    if let Some(name) = col_name.name() {
+        vm.source_mut().has_column(name)?;
+    }
+    let k = match arg_or_keyword(args, keywords, 1, "k") {
+        Some(value) => int_arg(vm, value, "k")?,
+        None => 1,
+    };
+    if k < 1 {
+        let h = &mut vm.hint;
+        return h
+            .cyan("entropy")
+            .white(": expected a positive k, but got ")
+            .red(&k.to_string())
+            .bail();
+    }
+    let k = k as usize;
+    let e = col_name.expr()?.map(
+        move |s| {
+            let ca = s.str()?.as_string();
+            let ca = ca.apply_kernel_cast::<Float32Type>(&|array| compute_entropy_kernel(array, k));
+            Ok(Some(ca.into_column()))
+        },
+        GetOutput::float_type(),
+    );
+    Ok(value::Value::named_expr(None, e))
+}
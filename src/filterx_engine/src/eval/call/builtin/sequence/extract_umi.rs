@@ -0,0 +1,145 @@
+use std::borrow::Cow;
+
+use super::super::*;
+use filterx_source::block::fastq::MATE_SUFFIX;
+use polars::prelude::{col, ChunkApply, Column, Expr, GetOutput, IntoColumn, StringChunked};
+
+/// A UMI pattern like umi_tools, `N` are UMI bases and `X` are bases kept in
+/// the read, e.g. `NNNNNNNNXXXX`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UmiPattern {
+    umi: Vec<usize>,
+    keep: Vec<usize>,
+}
+
+impl UmiPattern {
+    pub fn parse(pattern: &str) -> Option<Self> {
+        let mut umi = vec![];
+        let mut keep = vec![];
+        for (i, c) in pattern.chars().enumerate() {
+            match c.to_ascii_uppercase() {
+                'N' => umi.push(i),
+                'X' => keep.push(i),
+                _ => return None,
+            }
+        }
+        if umi.is_empty() {
+            return None;
+        }
+        Some(UmiPattern { umi, keep })
+    }
+
+    fn len(&self) -> usize {
+        self.umi.len() + self.keep.len()
+    }
+
+    /// The UMI of a read, none if the read is shorter than the pattern.
+    pub fn umi(&self, seq: &str) -> Option<String> {
+        let seq = seq.as_bytes();
+        if seq.len() < self.len() {
+            return None;
+        }
+        Some(self.umi.iter().map(|&i| seq[i] as char).collect())
+    }
+
+    /// The read without the UMI bases, reads shorter than the pattern are kept.
+    pub fn cut<'a>(&self, seq: &'a str) -> Cow<'a, str> {
+        if seq.len() < self.len() || !seq.is_ascii() {
+            return Cow::Borrowed(seq);
+        }
+        let bytes = seq.as_bytes();
+        let mut s: String = self.keep.iter().map(|&i| bytes[i] as char).collect();
+        s.push_str(&seq[self.len()..]);
+        Cow::Owned(s)
+    }
+}
+
+/// Append the UMI of `seq` to `name`, like `read1_ACGTACGT`.
+fn tag_name(name: Expr, pattern: UmiPattern, sep: String) -> Expr {
+    name.map_many(
+        move |columns: &mut [Column]| {
+            let names = columns[0].str()?;
+            let seqs = columns[1].str()?;
+            let ca: StringChunked = names
+                .into_iter()
+                .zip(seqs)
+                .map(|(name, seq)| {
+                    let name = name?;
+                    match seq.and_then(|seq| pattern.umi(seq)) {
+                        Some(umi) => Some(format!("{}{}{}", name, sep, umi)),
+                        None => Some(name.to_string()),
+                    }
+                })
+                .collect();
+            Ok(Some(ca.with_name(names.name().clone()).into_column()))
+        },
+        &[col("seq")],
+        GetOutput::same_type(),
+    )
+}
+
+pub fn extract_umi(
+    vm: &mut Vm,
+    args: &[ast::Expr],
+    keywords: &[ast::Keyword],
+    inplace: bool,
+) -> FilterxResult<value::Value> {
+    expect_inplace(vm, "extract_umi", inplace)?;
+    expect_keywords(keywords, &["sep"])?;
+    let pattern = eval_str!(vm, &args[0], "extract_umi: expected a UMI pattern");
+    let pattern = match pattern.string().ok().as_deref().and_then(UmiPattern::parse) {
+        Some(pattern) => pattern,
+        None => {
+            let h = &mut vm.hint;
+            return h
+                .cyan("extract_umi")
+                .white(": expected a pattern of ")
+                .green("N")
+                .white(" (UMI bases) and ")
+                .green("X")
+                .white(" (bases kept in the read), e.g. ")
+                .green("extract_umi_('NNNNNNNNXXXX')")
+                .bail();
+        }
+    };
+    let sep = match keyword_arg(keywords, "sep") {
+        Some(value) => eval_str!(vm, value, "extract_umi: expected a string as sep").string()?,
+        None => "_".to_string(),
+    };
+    vm.source().has_column("name")?;
+    vm.source().has_column("seq")?;
+
+    let mut columns = vec![tag_name(col("name"), pattern.clone(), sep.clone())];
+    // both mates of paired reads are tagged with the UMI of R1
+    let name2 = format!("name{}", MATE_SUFFIX);
+    if vm.source().check_column(&name2) {
+        columns.push(tag_name(col(&name2), pattern.clone(), sep).alias(&name2));
+    }
+    for name in ["seq", "qual"] {
+        if !vm.source().check_column(name) {
+            continue;
+        }
+        let pattern = pattern.clone();
+        columns.push(col(name).map(
+            move |s| {
+                let ca = s.str()?.apply_values(|seq| pattern.cut(seq));
+                Ok(Some(ca.into_column()))
+            },
+            GetOutput::same_type(),
+        ));
+    }
+    let lazy = vm.source_mut().lazy().with_columns(columns);
+    vm.source_mut().update(lazy);
+    Ok(value::Value::None)
+}
+
+#[test]
+fn test_umi_pattern() {
+    let pattern = UmiPattern::parse("NNNXXNN").unwrap();
+    assert_eq!(pattern.umi("ACGTTGCAAA").as_deref(), Some("ACGGC"));
+    assert_eq!(pattern.cut("ACGTTGCAAA"), "TTAAA");
+    assert_eq!(pattern.umi("ACG"), None);
+    assert_eq!(pattern.cut("ACG"), "ACG");
+    assert_eq!(UmiPattern::parse("XXXX"), None);
+    assert_eq!(UmiPattern::parse("NNCC"), None);
+}
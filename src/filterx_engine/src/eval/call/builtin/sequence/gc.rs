@@ -50,7 +50,7 @@ fn compute_gc(s: Column) -> PolarsResult<Option<Column>> {
     Ok(Some(c))
 }
 
-pub fn gc<'a>(vm: &'a mut Vm, args: &Vec<ast::Expr>) -> FilterxResult<value::Value> {
+pub fn gc(vm: &mut Vm, args: &[ast::Expr]) -> FilterxResult<value::Value> {
     expect_args_len(args, 1)?;
     if vm.source.source_type.is_fasta() || vm.source.source_type.is_fastq() {
         if vm.source.source_type.is_fasta() {
@@ -58,8 +58,7 @@ pub fn gc<'a>(vm: &'a mut Vm, args: &Vec<ast::Expr>) -> FilterxResult<value::Val
             match fasta.record_type {
                 FastaRecordType::Protein => {
                     let h = &mut vm.hint;
-                    h.white("gc: protein sequence is not supported")
-                        .print_and_exit();
+                    return h.white("gc: protein sequence is not supported").bail();
                 }
                 _ => {}
             }
@@ -68,7 +67,7 @@ pub fn gc<'a>(vm: &'a mut Vm, args: &Vec<ast::Expr>) -> FilterxResult<value::Val
     let col_name = eval_col!(vm, &args[0], "gc: expected a column name as first argument");
     let name = col_name.column()?;
     let e = col_name.expr()?;
-    vm.source_mut().has_column(name);
+    vm.source_mut().has_column(name)?;
     let e = e.map(compute_gc, GetOutput::float_type());
     return Ok(value::Value::named_expr(None, e));
 }
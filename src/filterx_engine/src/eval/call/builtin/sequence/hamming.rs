@@ -0,0 +1,8 @@
+use super::super::*;
+use super::align::{hamming_bytes, map_pairs};
+
+pub fn hamming(vm: &mut Vm, args: &[ast::Expr]) -> FilterxResult<value::Value> {
+    map_pairs(vm, args, "hamming", |a, b| {
+        hamming_bytes(a, b).map(|d| d as i64)
+    })
+}
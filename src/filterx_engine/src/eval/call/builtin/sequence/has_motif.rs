@@ -0,0 +1,23 @@
+use super::super::*;
+use super::motif::Motif;
+use polars::prelude::*;
+
+pub fn has_motif(
+    vm: &mut Vm,
+    args: &[ast::Expr],
+    keywords: &[ast::Keyword],
+) -> FilterxResult<value::Value> {
+    let (e, motif) = Motif::from_args(vm, args, keywords, "has_motif", &["revcomp", "mismatch"])?;
+    let e = e.map(
+        move |s| {
+            let ca: BooleanChunked = s
+                .str()?
+                .into_iter()
+                .map(|seq| seq.map(|seq| motif.contains(seq.as_bytes())))
+                .collect();
+            Ok(Some(ca.with_name(s.name().clone()).into_column()))
+        },
+        GetOutput::from_type(DataType::Boolean),
+    );
+    Ok(value::Value::named_expr(None, e))
+}
@@ -20,11 +20,7 @@ fn compute_hpc(s: Column) -> PolarsResult<Option<Column>> {
     Ok(Some(ca.into_column()))
 }
 
-pub fn hpc<'a>(
-    vm: &'a mut Vm,
-    args: &Vec<ast::Expr>,
-    inplace: bool,
-) -> FilterxResult<value::Value> {
+pub fn hpc(vm: &mut Vm, args: &[ast::Expr], inplace: bool) -> FilterxResult<value::Value> {
     expect_args_len(args, 1)?;
 
     let col_name = eval_col!(
@@ -35,7 +31,7 @@ pub fn hpc<'a>(
     let name = col_name.column()?;
     let e = col_name.expr()?;
     let e = e.map(compute_hpc, GetOutput::same_type());
-    vm.source_mut().has_column(name);
+    vm.source_mut().has_column(name)?;
     if inplace {
         vm.source_mut().with_column(e.clone().alias(name), None);
         return Ok(value::Value::None);
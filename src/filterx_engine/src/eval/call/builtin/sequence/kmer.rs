@@ -0,0 +1,156 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use super::super::*;
+use filterx_source::block::fasta::Fasta;
+use filterx_source::FastaRecordType;
+
+/// k-mers are packed in 2 bits per base into a `u64`.
+pub const MAX_K: usize = 32;
+
+/// Canonical k-mers of a sequence with their 0-based start, the smaller of
+/// the k-mer and its reverse complement. k-mers with bases other than
+/// A, C, G, T are skipped.
+pub fn canonical_kmers(seq: &[u8], k: usize) -> impl Iterator<Item = (usize, u64)> + '_ {
+    let mask = if k == MAX_K {
+        u64::MAX
+    } else {
+        (1u64 << (2 * k)) - 1
+    };
+    let shift = 2 * (k as u64 - 1);
+    let mut forward = 0u64;
+    let mut reverse = 0u64;
+    // valid bases before the current one
+    let mut valid = 0usize;
+    seq.iter().enumerate().filter_map(move |(i, &b)| {
+        let code = match b {
+            b'A' | b'a' => 0,
+            b'C' | b'c' => 1,
+            b'G' | b'g' => 2,
+            b'T' | b't' | b'U' | b'u' => 3,
+            _ => {
+                valid = 0;
+                return None;
+            }
+        };
+        forward = ((forward << 2) | code) & mask;
+        reverse = (reverse >> 2) | ((3 - code) << shift);
+        valid += 1;
+        (valid >= k).then(|| (i + 1 - k, forward.min(reverse)))
+    })
+}
+
+/// Mix the bits of a k-mer, so that the smallest hashes are a random sample.
+#[inline(always)]
+pub fn hash_kmer(kmer: u64) -> u64 {
+    let mut x = kmer.wrapping_add(0x9e3779b97f4a7c15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d049bb133111eb);
+    x ^ (x >> 31)
+}
+
+/// Hashes of the distinct canonical k-mers of a sequence, only hashes below
+/// `u64::MAX / scaled` are kept, like FracMinHash.
+pub fn kmer_sketch(seq: &[u8], k: usize, scaled: u64) -> HashSet<u64> {
+    let max_hash = u64::MAX / scaled.max(1);
+    canonical_kmers(seq, k)
+        .map(|(_, kmer)| hash_kmer(kmer))
+        .filter(|&h| h <= max_hash)
+        .collect()
+}
+
+/// Start of the (w, k)-minimizers of a sequence, the k-mer with the smallest
+/// hash in every window of `w` consecutive k-mers, the leftmost on ties.
+pub fn minimizer_positions(seq: &[u8], k: usize, w: usize) -> Vec<usize> {
+    let kmers: Vec<(usize, u64)> = canonical_kmers(seq, k)
+        .map(|(pos, kmer)| (pos, hash_kmer(kmer)))
+        .collect();
+    let w = w.clamp(1, kmers.len().max(1));
+    let mut positions: Vec<usize> = vec![];
+    for window in kmers.windows(w) {
+        let (pos, _) = window.iter().min_by_key(|(pos, h)| (*h, *pos)).unwrap();
+        if positions.last() != Some(pos) {
+            positions.push(*pos);
+        }
+    }
+    positions
+}
+
+/// The k-mer size argument, between 1 and 32.
+pub fn kmer_size(
+    vm: &mut Vm,
+    value: Option<&ast::Expr>,
+    default: usize,
+    function: &str,
+) -> FilterxResult<usize> {
+    let k = match value {
+        Some(value) => int_arg(vm, value, "k")?,
+        None => return Ok(default),
+    };
+    if k < 1 || k > MAX_K as i64 {
+        let h = &mut vm.hint;
+        return h
+            .cyan(function)
+            .white(": expected k between 1 and 32, but got ")
+            .red(&k.to_string())
+            .bail();
+    }
+    Ok(k as usize)
+}
+
+/// The k-mer sketch of all records of a fasta file, read once and shared by
+/// all chunks.
+pub fn load_reference(
+    vm: &mut Vm,
+    path: &str,
+    k: usize,
+    scaled: u64,
+    function: &str,
+) -> FilterxResult<Arc<HashSet<u64>>> {
+    let key = format!("{}:{}:{}", path, k, scaled);
+    if let Some(set) = vm.kmer_sets.get(&key) {
+        return Ok(set.clone());
+    }
+    if !std::path::Path::new(path).is_file() {
+        let h = &mut vm.hint;
+        return h
+            .cyan(function)
+            .white(": expected a fasta file as reference, but got ")
+            .red(path)
+            .bail();
+    }
+    let mut fasta = Fasta::from_path(path, FastaRecordType::Dna, 0)?;
+    let mut set = HashSet::new();
+    while let Some(record) = fasta.parse_next()? {
+        set.extend(kmer_sketch(record.seq().as_bytes(), k, scaled));
+    }
+    let set = Arc::new(set);
+    vm.kmer_sets.insert(key, set.clone());
+    Ok(set)
+}
+
+#[test]
+fn test_kmer() {
+    use filterx_core::seq::{reverse_complement, Complement};
+
+    // ACG and its reverse complement CGT are the same canonical k-mer
+    let kmers: Vec<(usize, u64)> = canonical_kmers(b"ACGT", 3).collect();
+    assert_eq!(kmers, vec![(0, 0b000110), (1, 0b000110)]);
+    // k-mers with N are skipped
+    let kmers: Vec<usize> = canonical_kmers(b"ACGNACGTA", 3).map(|(p, _)| p).collect();
+    assert_eq!(kmers, vec![0, 4, 5, 6]);
+    assert_eq!(canonical_kmers(b"AC", 3).count(), 0);
+    let seq = b"ACGTTGCAGTCAAGGCTTACCGATTACGGATCCAGT";
+    assert_eq!(canonical_kmers(seq, 32).count(), seq.len() - 31);
+
+    assert_eq!(kmer_sketch(b"ACGTACGT", 4, 1).len(), 3);
+    let revcomp = reverse_complement(seq, Complement::Iupac);
+    assert_eq!(kmer_sketch(seq, 21, 1), kmer_sketch(&revcomp, 21, 1));
+
+    let positions = minimizer_positions(seq, 5, 4);
+    assert!(positions
+        .windows(2)
+        .all(|p| p[0] < p[1] && p[1] - p[0] <= 4));
+    assert_eq!(minimizer_positions(b"ACGTA", 5, 4), vec![0]);
+    assert!(minimizer_positions(b"ACG", 5, 4).is_empty());
+}
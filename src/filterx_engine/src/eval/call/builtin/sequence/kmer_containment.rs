@@ -0,0 +1,55 @@
+use super::super::*;
+use super::kmer::{kmer_size, kmer_sketch, load_reference};
+use polars::prelude::*;
+
+/// Fraction of the distinct k-mers of every sequence found in the reference,
+/// null if a sequence has no k-mer.
+pub fn kmer_containment(
+    vm: &mut Vm,
+    args: &[ast::Expr],
+    keywords: &[ast::Keyword],
+) -> FilterxResult<value::Value> {
+    expect_keywords(keywords, &["k", "scaled"])?;
+    let col_name = eval_col!(
+        vm,
+        &args[0],
+        "kmer_containment: expected a column name as first argument"
+    );
+    if let Some(name) = col_name.name() {
+        vm.source_mut().has_column(name)?;
+    }
+    let path = eval_str!(
+        vm,
+        &args[1],
+        "kmer_containment: expected a fasta file as second argument"
+    );
+    let path = path.string()?;
+    let k = kmer_size(
+        vm,
+        arg_or_keyword(args, keywords, 2, "k"),
+        21,
+        "kmer_containment",
+    )?;
+    let scaled = keyword_number(vm, keywords, "scaled", 1.0)?.max(1.0) as u64;
+    let reference = load_reference(vm, &path, k, scaled, "kmer_containment")?;
+
+    let e = col_name.expr()?.map(
+        move |s| {
+            let ca: Float32Chunked = s
+                .str()?
+                .into_iter()
+                .map(|seq| {
+                    let sketch = kmer_sketch(seq?.as_bytes(), k, scaled);
+                    if sketch.is_empty() {
+                        return None;
+                    }
+                    let found = sketch.iter().filter(|h| reference.contains(h)).count();
+                    Some(found as f32 / sketch.len() as f32)
+                })
+                .collect();
+            Ok(Some(ca.with_name(s.name().clone()).into_column()))
+        },
+        GetOutput::float_type(),
+    );
+    Ok(value::Value::named_expr(None, e))
+}
@@ -0,0 +1,29 @@
+use super::super::*;
+use super::kmer::{canonical_kmers, kmer_size};
+use polars::prelude::*;
+
+/// Number of k-mers of every sequence, k-mers with bases other than
+/// A, C, G, T are not counted.
+pub fn kmer_count(vm: &mut Vm, args: &[ast::Expr]) -> FilterxResult<value::Value> {
+    let col_name = eval_col!(
+        vm,
+        &args[0],
+        "kmer_count: expected a column name as first argument"
+    );
+    if let Some(name) = col_name.name() {
+        vm.source_mut().has_column(name)?;
+    }
+    let k = kmer_size(vm, args.get(1), 21, "kmer_count")?;
+    let e = col_name.expr()?.map(
+        move |s| {
+            let ca: UInt32Chunked = s
+                .str()?
+                .into_iter()
+                .map(|seq| seq.map(|seq| canonical_kmers(seq.as_bytes(), k).count() as u32))
+                .collect();
+            Ok(Some(ca.with_name(s.name().clone()).into_column()))
+        },
+        GetOutput::from_type(DataType::UInt32),
+    );
+    Ok(value::Value::named_expr(None, e))
+}
@@ -0,0 +1,70 @@
+use super::super::*;
+use super::complexity::dust_mask;
+use polars::prelude::*;
+
+use polars_arrow::array::{Array, ArrayRef, MutablePlString, Utf8ViewArray};
+
+fn compute_mask_kernel(
+    array: &Utf8ViewArray,
+    window: usize,
+    threshold: f64,
+    hard: bool,
+) -> ArrayRef {
+    let mut masked = MutablePlString::with_capacity(array.len());
+    let mut buf = Vec::new();
+    for seq in array.values_iter() {
+        buf.clear();
+        buf.extend_from_slice(seq.as_bytes());
+        for (b, m) in buf
+            .iter_mut()
+            .zip(dust_mask(seq.as_bytes(), window, threshold))
+        {
+            if m {
+                *b = if hard { b'N' } else { b.to_ascii_lowercase() };
+            }
+        }
+        masked.push_value_ignore_validity(std::str::from_utf8(&buf).unwrap_or(seq));
+    }
+    let masked: Utf8ViewArray = masked.into();
+    Box::new(masked.with_validity(array.validity().cloned()))
+}
+
+/// Mask low complexity regions found by DUST, soft-masked in lower case by
+/// default, or hard-masked with `N`.
+pub fn mask_lowcomplex(
+    vm: &mut Vm,
+    args: &[ast::Expr],
+    keywords: &[ast::Keyword],
+    inplace: bool,
+) -> FilterxResult<value::Value> {
+    expect_keywords(keywords, &["threshold", "window", "hard"])?;
+    let col_name = eval_col!(
+        vm,
+        &args[0],
+        "mask_lowcomplex: expected a column name as first argument"
+    );
+    if let Some(name) = col_name.name() {
+        vm.source_mut().has_column(name)?;
+    }
+    let threshold = match arg_or_keyword(args, keywords, 1, "threshold") {
+        Some(value) => float_arg(vm, value, "threshold")?,
+        None => 2.0,
+    };
+    let window = keyword_number(vm, keywords, "window", 64.0)? as usize;
+    let hard = keyword_bool(vm, keywords, "hard", false)?;
+
+    let e = col_name.expr()?.map(
+        move |s| {
+            let ca = s.str()?.as_string();
+            let ca = ca.apply_kernel(&|array| compute_mask_kernel(array, window, threshold, hard));
+            Ok(Some(ca.into_column()))
+        },
+        GetOutput::same_type(),
+    );
+    if inplace {
+        let name = col_name.column()?;
+        vm.source_mut().with_column(e.alias(name), None);
+        return Ok(value::Value::None);
+    }
+    Ok(value::Value::named_expr(None, e))
+}
@@ -0,0 +1,41 @@
+use super::super::*;
+use super::complexity::max_homopolymer_len;
+use polars::prelude::*;
+
+use polars_arrow::{
+    array::{Array, ArrayRef, UInt32Array, Utf8ViewArray},
+    buffer::Buffer,
+    datatypes::ArrowDataType,
+};
+
+fn compute_max_homopolymer_kernel(array: &Utf8ViewArray) -> ArrayRef {
+    let values: Buffer<u32> = array
+        .values_iter()
+        .map(|seq| max_homopolymer_len(seq.as_bytes()) as u32)
+        .collect::<Vec<_>>()
+        .into();
+    let array = UInt32Array::new(ArrowDataType::UInt32, values, array.validity().cloned());
+    Box::new(array)
+}
+
+fn compute_max_homopolymer(s: Column) -> PolarsResult<Option<Column>> {
+    let ca = s.str()?.as_string();
+    let ca = ca.apply_kernel_cast::<UInt32Type>(&compute_max_homopolymer_kernel);
+    Ok(Some(ca.into_column()))
+}
+
+pub fn max_homopolymer(vm: &mut Vm, args: &[ast::Expr]) -> FilterxResult<value::Value> {
+    let col_name = eval_col!(
+        vm,
+        &args[0],
+        "max_homopolymer: expected a column name as first argument"
+    );
+    if let Some(name) = col_name.name() {
+        vm.source_mut().has_column(name)?;
+    }
+    let e = col_name.expr()?.map(
+        compute_max_homopolymer,
+        GetOutput::from_type(DataType::UInt32),
+    );
+    Ok(value::Value::named_expr(None, e))
+}
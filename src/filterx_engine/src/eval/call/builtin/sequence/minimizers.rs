@@ -0,0 +1,48 @@
+use super::super::*;
+use super::kmer::{kmer_size, minimizer_positions};
+use polars::prelude::*;
+
+/// 0-based start of the (w, k)-minimizers of every sequence as a list.
+pub fn minimizers(
+    vm: &mut Vm,
+    args: &[ast::Expr],
+    keywords: &[ast::Keyword],
+) -> FilterxResult<value::Value> {
+    expect_keywords(keywords, &["k", "w"])?;
+    let col_name = eval_col!(
+        vm,
+        &args[0],
+        "minimizers: expected a column name as first argument"
+    );
+    if let Some(name) = col_name.name() {
+        vm.source_mut().has_column(name)?;
+    }
+    let k = kmer_size(vm, arg_or_keyword(args, keywords, 1, "k"), 15, "minimizers")?;
+    let w = match arg_or_keyword(args, keywords, 2, "w") {
+        Some(value) => int_arg(vm, value, "w")?.max(1) as usize,
+        None => 10,
+    };
+    let e = col_name.expr()?.map(
+        move |s| {
+            let ca = s.str()?;
+            let mut builder = ListPrimitiveChunkedBuilder::<Int64Type>::new(
+                s.name().clone(),
+                ca.len(),
+                ca.len() * 4,
+                DataType::Int64,
+            );
+            for seq in ca.into_iter() {
+                match seq {
+                    Some(seq) => {
+                        let positions = minimizer_positions(seq.as_bytes(), k, w);
+                        builder.append_values_iter(positions.into_iter().map(|p| p as i64));
+                    }
+                    None => builder.append_null(),
+                }
+            }
+            Ok(Some(builder.finish().into_column()))
+        },
+        GetOutput::from_type(DataType::List(Box::new(DataType::Int64))),
+    );
+    Ok(value::Value::named_expr(None, e))
+}
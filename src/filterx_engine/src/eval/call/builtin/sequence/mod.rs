@@ -2,11 +2,179 @@ use crate::builtin_function;
 
 builtin_function! {
     FUNCTION_SEQUENCE,
-    (gc, true, false),
-    (revcomp, true, true),
-    (to_fasta, false, false, (to_fa)),
-    (to_fastq, false, false, (to_fq)),
-    (qual, true, false),
-    (phred, false, false),
-    (hpc, true, true),
+    (gc, true, false), Exact(1), [Column] => |vm, c| gc(vm, c.args),
+    (revcomp, true, true), Exact(1), [Column] => |vm, c| revcomp(vm, c.args, c.inplace),
+    (to_fasta, false, false, (to_fa)), Exact(0), [] => |vm, _| to_fasta(vm),
+    (to_fastq, false, false, (to_fq)), Exact(0), [] => |vm, _| to_fastq(vm),
+    (qual, true, false), Exact(1), [Column] => |vm, c| qual(vm, c.args),
+    (phred, false, false), Exact(0), [] => |vm, _| phred(vm),
+    (hpc, true, true), Exact(1), [Column] => |vm, c| hpc(vm, c.args, c.inplace),
+    (trim_qual, false, true), Range(0, 1), [Int] => |vm, c| trim_qual(vm, c.args, c.inplace),
+    (trim_window, false, true), Range(0, 2), [Int, Int]
+        => |vm, c| trim_window(vm, c.args, c.inplace),
+    (trim_mott, false, true), Range(0, 1), [Number] => |vm, c| trim_mott(vm, c.args, c.inplace),
+    (trim_adapter, false, true), Exact(1), [Str]
+        => |vm, c| trim_adapter(vm, c.args, c.keywords, c.inplace),
+    (trim_primer, false, true), Exact(1), [Str]
+        => |vm, c| trim_primer(vm, c.args, c.keywords, c.inplace),
+    (has_motif, true, false), Exact(2), [Column, Str]
+        => |vm, c| has_motif(vm, c.args, c.keywords),
+    (motif_count, true, false), Exact(2), [Column, Str]
+        => |vm, c| motif_count(vm, c.args, c.keywords),
+    (motif_pos, true, false), Exact(2), [Column, Str]
+        => |vm, c| motif_pos(vm, c.args, c.keywords),
+    (translate, true, true), Range(1, 4), [Column, Int, Int, Bool]
+        => |vm, c| translate(vm, c.args, c.keywords, c.inplace),
+    (orf, true, true), Range(1, 3), [Column, Int, Int]
+        => |vm, c| orf(vm, c.args, c.keywords, c.inplace),
+    (entropy, true, false), Range(1, 2), [Column, Int] => |vm, c| entropy(vm, c.args, c.keywords),
+    (dust, true, false), Exact(1), [Column] => |vm, c| dust(vm, c.args, c.keywords),
+    (max_homopolymer, true, false), Exact(1), [Column] => |vm, c| max_homopolymer(vm, c.args),
+    (mask_lowcomplex, true, true), Range(1, 2), [Column, Number]
+        => |vm, c| mask_lowcomplex(vm, c.args, c.keywords, c.inplace),
+    (kmer_count, true, false), Range(1, 2), [Column, Int] => |vm, c| kmer_count(vm, c.args),
+    (uniq_kmers, true, false), Range(1, 2), [Column, Int] => |vm, c| uniq_kmers(vm, c.args),
+    (kmer_containment, true, false), Range(2, 3), [Column, Str, Int]
+        => |vm, c| kmer_containment(vm, c.args, c.keywords),
+    (minimizers, true, false), Range(1, 3), [Column, Int, Int]
+        => |vm, c| minimizers(vm, c.args, c.keywords),
+    (edit_distance, true, false), Exact(2), [Column, Any] => |vm, c| edit_distance(vm, c.args),
+    (hamming, true, false), Exact(2), [Column, Any] => |vm, c| hamming(vm, c.args),
+    (align_score, true, false), Range(2, 3), [Column, Any, Str]
+        => |vm, c| align_score(vm, c.args, c.keywords),
+    (extract_umi, false, true), Exact(1), [Str]
+        => |vm, c| extract_umi(vm, c.args, c.keywords, c.inplace),
+}
+
+pub mod adapter;
+pub mod align;
+pub mod codon;
+pub mod complexity;
+pub mod kmer;
+pub mod motif;
+
+use super::*;
+use polars::prelude::{col, lit, DataType, GetOutput, IntoColumn, UInt64Chunked};
+
+/// Read the `index`th argument of a trimming function, `default` if it is not given.
+pub fn trim_arg(
+    vm: &mut Vm,
+    args: &[ast::Expr],
+    index: usize,
+    default: f64,
+    function: &str,
+) -> FilterxResult<f64> {
+    let Some(arg) = args.get(index) else {
+        return Ok(default);
+    };
+    let v = eval_int!(vm, arg, "expected a number as argument");
+    match v.float() {
+        Ok(v) if v >= 0.0 => Ok(v),
+        _ => {
+            let h = &mut vm.hint;
+            h.cyan(function)
+                .white(": expected a non-negative number as argument")
+                .bail()
+        }
+    }
+}
+
+/// The trimming functions cut `seq` and `qual` together, only the inplace
+/// version makes sense.
+pub fn expect_inplace(vm: &mut Vm, function: &str, inplace: bool) -> FilterxResult<()> {
+    if inplace {
+        return Ok(());
+    }
+    let h = &mut vm.hint;
+    h.cyan(function)
+        .white(" cuts ")
+        .green("seq")
+        .white(" and ")
+        .green("qual")
+        .white(" together, use the inplace version ")
+        .cyan(&format!("{}_", function))
+        .white(".")
+        .bail()
+}
+
+const TRIM_RANGE: &str = "__filterx_trim_range__";
+
+/// Cut `seq` and `qual` of every read to the range `bounds` returns for its
+/// phred scores. The range is computed once and packed as `start << 32 | len`.
+pub fn trim_reads<F>(
+    vm: &mut Vm,
+    function: &str,
+    inplace: bool,
+    bounds: F,
+) -> FilterxResult<value::Value>
+where
+    F: Fn(&[u8]) -> (usize, usize) + Send + Sync + 'static,
+{
+    expect_inplace(vm, function, inplace)?;
+    if !vm.source.source_type.is_fastq() {
+        let h = &mut vm.hint;
+        return h
+            .cyan(function)
+            .white(": Only available on fastq source")
+            .bail();
+    }
+    let offset = match vm.source.get_fastq()?.quality_type {
+        filterx_source::QualityType::Phred33 => 33,
+        filterx_source::QualityType::Phred64 => 64,
+        filterx_source::QualityType::Auto => {
+            let h = &mut vm.hint;
+            return h
+                .cyan(function)
+                .white(": Unable to detect quality type")
+                .bail();
+        }
+    };
+    vm.source().has_column("seq")?;
+    vm.source().has_column("qual")?;
+
+    let range = col("qual").map(
+        move |s| {
+            let ca = s.str()?;
+            let ca: UInt64Chunked = ca
+                .into_iter()
+                .map(|qual| {
+                    qual.map(|qual| {
+                        let scores: Vec<u8> =
+                            qual.bytes().map(|b| b.saturating_sub(offset)).collect();
+                        let (start, end) = bounds(&scores);
+                        ((start as u64) << 32) | (end - start) as u64
+                    })
+                })
+                .collect();
+            Ok(Some(ca.with_name(s.name().clone()).into_column()))
+        },
+        GetOutput::from_type(DataType::UInt64),
+    );
+    let start = col(TRIM_RANGE).floor_div(lit(1u64 << 32));
+    let length = col(TRIM_RANGE) % lit(1u64 << 32);
+    let lazy = vm
+        .source_mut()
+        .lazy()
+        .with_column(range.alias(TRIM_RANGE))
+        .with_columns([
+            col("seq").str().slice(start.clone(), length.clone()),
+            col("qual").str().slice(start, length),
+        ])
+        .drop([TRIM_RANGE]);
+    vm.source_mut().update(lazy);
+    Ok(value::Value::None)
+}
+
+#[test]
+fn test_trim_bounds() {
+    let scores = [2, 30, 30, 30, 12, 30, 30, 10, 2, 2];
+    assert_eq!(trim_qual::trim_qual_bounds(&scores, 20), (1, 7));
+    assert_eq!(trim_qual::trim_qual_bounds(&[2, 2], 20), (2, 2));
+
+    assert_eq!(trim_window::trim_window_bounds(&scores, 4, 20.0), (0, 7));
+    assert_eq!(trim_window::trim_window_bounds(&scores, 2, 20.0), (0, 0));
+    assert_eq!(trim_window::trim_window_bounds(&[30, 30], 4, 20.0), (0, 2));
+
+    assert_eq!(trim_mott::trim_mott_bounds(&scores, 0.05), (1, 7));
+    assert_eq!(trim_mott::trim_mott_bounds(&[2, 2], 0.05), (0, 0));
 }
@@ -0,0 +1,107 @@
+use super::super::*;
+use super::adapter::{base_eq, iupac_bits};
+use filterx_core::seq::{reverse_complement, Complement};
+
+/// A motif and the options shared by `has_motif`, `motif_count` and `motif_pos`.
+pub struct Motif {
+    forward: Vec<u8>,
+    /// `None` if the reverse strand is not searched, or the motif is palindromic
+    reverse: Option<Vec<u8>>,
+    mismatch: usize,
+}
+
+impl Motif {
+    pub fn from_args(
+        vm: &mut Vm,
+        args: &[ast::Expr],
+        keywords: &[ast::Keyword],
+        function: &str,
+        allowed: &[&str],
+    ) -> FilterxResult<(polars::prelude::Expr, Self)> {
+        expect_keywords(keywords, allowed)?;
+        let (e, motif) = string_pattern_args(vm, args, function)?;
+        if motif.is_empty() || !motif.bytes().all(|b| iupac_bits(b) != 0) {
+            let h = &mut vm.hint;
+            return h
+                .cyan(function)
+                .white(": expected a motif of IUPAC codes, but got ")
+                .red(&motif)
+                .bail();
+        }
+        let forward = motif.to_ascii_uppercase().into_bytes();
+        let reverse = match keyword_bool(vm, keywords, "revcomp", false)? {
+            true => Some(reverse_complement(&forward, Complement::Iupac)).filter(|r| *r != forward),
+            false => None,
+        };
+        let mismatch = keyword_number(vm, keywords, "mismatch", 0.0)? as usize;
+        Ok((
+            e,
+            Motif {
+                forward,
+                reverse,
+                mismatch,
+            },
+        ))
+    }
+
+    fn hit(&self, window: &[u8], motif: &[u8]) -> bool {
+        let mut errors = 0;
+        for (&a, &b) in window.iter().zip(motif) {
+            if !base_eq(a, b) {
+                errors += 1;
+                if errors > self.mismatch {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
+    fn matches(&self, window: &[u8]) -> bool {
+        self.hit(window, &self.forward)
+            || self.reverse.as_ref().is_some_and(|r| self.hit(window, r))
+    }
+
+    /// 0-based start of all hits on both strands, in order. A position hit
+    /// by both strands is reported once.
+    pub fn positions(&self, seq: &[u8]) -> Vec<usize> {
+        seq.windows(self.forward.len())
+            .enumerate()
+            .filter(|(_, window)| self.matches(window))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    pub fn count(&self, seq: &[u8]) -> usize {
+        seq.windows(self.forward.len())
+            .filter(|window| self.matches(window))
+            .count()
+    }
+
+    pub fn contains(&self, seq: &[u8]) -> bool {
+        seq.windows(self.forward.len())
+            .any(|window| self.matches(window))
+    }
+}
+
+#[test]
+fn test_motif() {
+    let motif = |m: &str, reverse: bool, mismatch: usize| {
+        let forward = m.as_bytes().to_vec();
+        Motif {
+            reverse: Some(reverse_complement(&forward, Complement::Iupac))
+                .filter(|r| reverse && *r != forward),
+            forward,
+            mismatch,
+        }
+    };
+    let seq = b"AAGAATTCTTAGGTCCAGATC";
+    assert_eq!(motif("GAATTC", false, 0).positions(seq), vec![2]);
+    assert!(motif("RGATCY", false, 0).positions(seq).is_empty());
+    assert_eq!(motif("GATC", false, 0).positions(seq), vec![17]);
+    // CCTAAG on the reverse strand is CTTAGG
+    assert_eq!(motif("CCTAAG", true, 0).positions(seq), vec![7]);
+    assert_eq!(motif("CCTAAG", false, 0).count(seq), 0);
+    assert_eq!(motif("GAATTA", false, 1).positions(seq), vec![2]);
+    assert!(motif("NGG", false, 0).contains(seq));
+}
@@ -0,0 +1,23 @@
+use super::super::*;
+use super::motif::Motif;
+use polars::prelude::*;
+
+pub fn motif_count(
+    vm: &mut Vm,
+    args: &[ast::Expr],
+    keywords: &[ast::Keyword],
+) -> FilterxResult<value::Value> {
+    let (e, motif) = Motif::from_args(vm, args, keywords, "motif_count", &["revcomp", "mismatch"])?;
+    let e = e.map(
+        move |s| {
+            let ca: UInt32Chunked = s
+                .str()?
+                .into_iter()
+                .map(|seq| seq.map(|seq| motif.count(seq.as_bytes()) as u32))
+                .collect();
+            Ok(Some(ca.with_name(s.name().clone()).into_column()))
+        },
+        GetOutput::from_type(DataType::UInt32),
+    );
+    Ok(value::Value::named_expr(None, e))
+}
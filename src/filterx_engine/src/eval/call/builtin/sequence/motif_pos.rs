@@ -0,0 +1,52 @@
+use super::super::*;
+use super::motif::Motif;
+use polars::prelude::*;
+
+/// Start positions of all hits as a list, 0-based by default like `find`.
+pub fn motif_pos(
+    vm: &mut Vm,
+    args: &[ast::Expr],
+    keywords: &[ast::Keyword],
+) -> FilterxResult<value::Value> {
+    let (e, motif) = Motif::from_args(
+        vm,
+        args,
+        keywords,
+        "motif_pos",
+        &["revcomp", "mismatch", "base"],
+    )?;
+    let base = keyword_number(vm, keywords, "base", 0.0)? as i64;
+    if base > 1 {
+        let h = &mut vm.hint;
+        return h
+            .cyan("motif_pos")
+            .white(": expected ")
+            .green("base=0")
+            .white(" or ")
+            .green("base=1")
+            .bail();
+    }
+    let e = e.map(
+        move |s| {
+            let ca = s.str()?;
+            let mut builder = ListPrimitiveChunkedBuilder::<Int64Type>::new(
+                s.name().clone(),
+                ca.len(),
+                ca.len() * 2,
+                DataType::Int64,
+            );
+            for seq in ca {
+                match seq {
+                    Some(seq) => {
+                        let positions = motif.positions(seq.as_bytes());
+                        builder.append_values_iter(positions.iter().map(|&p| p as i64 + base));
+                    }
+                    None => builder.append_null(),
+                }
+            }
+            Ok(Some(builder.finish().into_column()))
+        },
+        GetOutput::from_type(DataType::List(Box::new(DataType::Int64))),
+    );
+    Ok(value::Value::named_expr(None, e))
+}
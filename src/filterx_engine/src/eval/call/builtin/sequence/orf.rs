@@ -0,0 +1,61 @@
+use super::super::*;
+use super::codon::longest_orf;
+use super::translate::table_arg;
+use filterx_core::seq::{reverse_complement, Complement};
+use polars::prelude::*;
+
+/// The longest ORF of both strands, from `ATG` to the stop codon, null if
+/// none is at least `min_len` bases long.
+pub fn orf(
+    vm: &mut Vm,
+    args: &[ast::Expr],
+    keywords: &[ast::Keyword],
+    inplace: bool,
+) -> FilterxResult<value::Value> {
+    expect_keywords(keywords, &["min_len", "table"])?;
+    let col_name = eval_col!(
+        vm,
+        &args[0],
+        "orf: expected a column name as first argument"
+    );
+    if let Some(name) = col_name.name() {
+        vm.source_mut().has_column(name)?;
+    }
+    let min_len = match arg_or_keyword(args, keywords, 1, "min_len") {
+        Some(value) => int_arg(vm, value, "min_len")?.max(0) as usize,
+        None => 0,
+    };
+    let code = table_arg(vm, arg_or_keyword(args, keywords, 2, "table"), "orf")?;
+
+    let e = col_name.expr()?.map(
+        move |s| {
+            let ca: StringChunked = s
+                .str()?
+                .into_iter()
+                .map(|seq| {
+                    let seq = seq?.as_bytes();
+                    let forward = longest_orf(code, seq).map(|(s, e)| seq[s..e].to_vec());
+                    let rc = reverse_complement(seq, Complement::Iupac);
+                    let reverse = longest_orf(code, &rc).map(|(s, e)| rc[s..e].to_vec());
+                    let orf = match (forward, reverse) {
+                        (Some(f), Some(r)) if r.len() > f.len() => r,
+                        (Some(f), _) => f,
+                        (None, r) => r?,
+                    };
+                    if orf.len() < min_len {
+                        return None;
+                    }
+                    String::from_utf8(orf).ok()
+                })
+                .collect();
+            Ok(Some(ca.with_name(s.name().clone()).into_column()))
+        },
+        GetOutput::same_type(),
+    );
+    if inplace {
+        let name = col_name.column()?;
+        vm.source_mut().with_column(e.alias(name), None);
+        return Ok(value::Value::None);
+    }
+    Ok(value::Value::named_expr(None, e))
+}
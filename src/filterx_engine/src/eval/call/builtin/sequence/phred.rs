@@ -3,13 +3,14 @@ pub fn phred(vm: &mut Vm) -> FilterxResult<value::Value> {
     if vm.source_type() == SourceType::Fastq {
         let fastp = vm.source.get_fastq()?;
         let h = &mut vm.hint;
-        h.white("phred: ")
+        return h
+            .white("phred: ")
             .green(&format!("{}", fastp.quality_type))
-            .print_and_exit();
+            .bail();
     }
     let h = &mut vm.hint;
     h.white("phred: Only ")
         .cyan("fastq")
         .white(" format is supported for now.")
-        .print_and_exit();
+        .bail()
 }
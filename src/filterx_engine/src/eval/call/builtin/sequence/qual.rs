@@ -34,9 +34,9 @@ fn compute_qual_phred33_kernel(array: &Utf8ViewArray) -> ArrayRef {
             if length == 0 {
                 return 0.0;
             }
-            let max = seq.bytes().max().unwrap();
-            let min = seq.bytes().min().unwrap();
-            if max > 127 || min < 33 {
+            let max_qual = seq.bytes().max().unwrap();
+            let min_qual = seq.bytes().min().unwrap();
+            if max_qual > 127 || min_qual < 33 {
                 return 0.0;
             }
             let sum_qual: f32 = seq
@@ -65,9 +65,9 @@ fn compute_qual_phred64_kernel(array: &Utf8ViewArray) -> ArrayRef {
             if length == 0 {
                 return 0.0;
             }
-            let max = seq.bytes().max().unwrap();
-            let min = seq.bytes().min().unwrap();
-            if max > 127 || min < 33 {
+            let max_qual = seq.bytes().max().unwrap();
+            let min_qual = seq.bytes().min().unwrap();
+            if max_qual > 127 || min_qual < 33 {
                 return 0.0;
             }
             let qual_sum: f32 = seq
@@ -128,12 +128,11 @@ fn compute_qual_phred33(s: Column) -> PolarsResult<Option<Column>> {
     Ok(Some(c))
 }
 
-pub fn qual<'a>(vm: &'a mut Vm, args: &Vec<ast::Expr>) -> FilterxResult<value::Value> {
+pub fn qual(vm: &mut Vm, args: &[ast::Expr]) -> FilterxResult<value::Value> {
     expect_args_len(args, 1)?;
     if !vm.source.source_type.is_fastq() {
         let h = &mut vm.hint;
-        h.white("qual: Only available on fastq source")
-            .print_and_exit();
+        return h.white("qual: Only available on fastq source").bail();
     }
     let col_name = eval_col!(
         vm,
@@ -143,7 +142,7 @@ pub fn qual<'a>(vm: &'a mut Vm, args: &Vec<ast::Expr>) -> FilterxResult<value::V
     let qtype = vm.source.get_fastq()?;
     let name = col_name.column()?;
     let mut e = col_name.expr()?;
-    vm.source().has_column(name);
+    vm.source().has_column(name)?;
     match qtype.quality_type {
         QualityType::Phred33 => {
             e = e.map(compute_qual_phred33, GetOutput::float_type());
@@ -153,8 +152,7 @@ pub fn qual<'a>(vm: &'a mut Vm, args: &Vec<ast::Expr>) -> FilterxResult<value::V
         }
         QualityType::Auto => {
             let h = &mut vm.hint;
-            h.white("qual: Unable to detect quality type")
-                .print_and_exit();
+            return h.white("qual: Unable to detect quality type").bail();
         }
     };
     return Ok(value::Value::named_expr(None, e));
@@ -2,25 +2,19 @@ use std::borrow::Cow;
 
 use super::super::*;
 
+use filterx_core::seq::{complement, Complement};
 use filterx_source::FastaRecordType;
 use polars::prelude::*;
 
-fn compute_revcomp_dna(s: Column) -> PolarsResult<Option<Column>> {
+fn compute_revcomp(s: Column, mode: Complement) -> PolarsResult<Option<Column>> {
     let ca = s.str()?;
     let ca = ca.apply_values(|s| {
         let s: String = s
             .chars()
             .rev()
-            .map(|b| match b {
-                'A' => 'T',
-                'T' => 'A',
-                'C' => 'G',
-                'G' => 'C',
-                'a' => 't',
-                't' => 'a',
-                'c' => 'g',
-                'g' => 'c',
-                _ => b,
+            .map(|c| match c.is_ascii() {
+                true => complement(c as u8, mode) as char,
+                false => c,
             })
             .collect();
         Cow::Owned(s)
@@ -28,34 +22,15 @@ fn compute_revcomp_dna(s: Column) -> PolarsResult<Option<Column>> {
     Ok(Some(ca.into_column()))
 }
 
+fn compute_revcomp_dna(s: Column) -> PolarsResult<Option<Column>> {
+    compute_revcomp(s, Complement::Dna)
+}
+
 fn compute_revcomp_rna(s: Column) -> PolarsResult<Option<Column>> {
-    let ca = s.str()?;
-    let ca = ca.apply_values(|s| {
-        let s: String = s
-            .chars()
-            .rev()
-            .map(|b| match b {
-                'A' => 'U',
-                'U' => 'A',
-                'C' => 'G',
-                'G' => 'C',
-                'a' => 'u',
-                'u' => 'a',
-                'c' => 'g',
-                'g' => 'c',
-                _ => b,
-            })
-            .collect();
-        Cow::Owned(s)
-    });
-    Ok(Some(ca.into_column()))
+    compute_revcomp(s, Complement::Rna)
 }
 
-pub fn revcomp<'a>(
-    vm: &'a mut Vm,
-    args: &Vec<ast::Expr>,
-    inplace: bool,
-) -> FilterxResult<value::Value> {
+pub fn revcomp(vm: &mut Vm, args: &[ast::Expr], inplace: bool) -> FilterxResult<value::Value> {
     expect_args_len(args, 1)?;
 
     let col_name = eval_col!(
@@ -63,10 +38,11 @@ pub fn revcomp<'a>(
         &args[0],
         "revcomp: expected a column name as first argument"
     );
-    if vm.source.source_type.is_fasta() || vm.source.source_type.is_fastq() {
+    let source_type = vm.source.source_type;
+    if source_type.is_fasta() || source_type.is_fastq() || source_type.is_genbank() {
         let name = col_name.column()?;
         let mut e = col_name.expr()?;
-        vm.source_mut().has_column(name);
+        vm.source_mut().has_column(name)?;
         if vm.source.source_type.is_fasta() {
             let fasta = vm.source.get_fasta()?;
             match fasta.record_type {
@@ -78,14 +54,17 @@ pub fn revcomp<'a>(
                 }
                 FastaRecordType::Protein => {
                     let h = &mut vm.hint;
-                    h.white("revcomp: protein sequences are not supported")
-                        .print_and_exit();
+                    return h
+                        .white("revcomp: protein sequences are not supported")
+                        .bail();
                 }
                 FastaRecordType::Auto => {
                     let h = &mut vm.hint;
-                    h.white("revcomp: unknown sequence type.").print_and_exit();
+                    return h.white("revcomp: unknown sequence type.").bail();
                 }
             }
+        } else if source_type.is_genbank() {
+            e = e.map(compute_revcomp_dna, GetOutput::same_type());
         }
 
         if inplace {
@@ -95,7 +74,20 @@ pub fn revcomp<'a>(
         return Ok(value::Value::named_expr(Some(name.to_string()), e));
     } else {
         let h = &mut vm.hint;
-        h.white("revcomp: Only fastq and fasta are supported.")
-            .print_and_exit()
+        h.white("revcomp: Only fastq, fasta and genbank are supported.")
+            .bail()
     }
 }
+
+#[test]
+fn test_revcomp() {
+    let seq = Column::new("seq".into(), ["ACGTRYKMN", "acgtn"]);
+    let rc = compute_revcomp_dna(seq).unwrap().unwrap();
+    let rc: Vec<&str> = rc.str().unwrap().into_no_null_iter().collect();
+    // ambiguity codes are kept as they are
+    assert_eq!(rc, vec!["NMKYRACGT", "nacgt"]);
+    let seq = Column::new("seq".into(), ["ACGUT", "acgu"]);
+    let rc = compute_revcomp_rna(seq).unwrap().unwrap();
+    let rc: Vec<&str> = rc.str().unwrap().into_no_null_iter().collect();
+    assert_eq!(rc, vec!["TACGU", "acgu"]);
+}
@@ -3,23 +3,25 @@ use std::io::Write;
 
 fn print_fasta(vm: &mut Vm) -> FilterxResult<value::Value> {
     vm.status.printed = true;
-    let name_index = vm.source_mut().ret_column_names.iter().position(|x| x == "name");
-    let seq_index = vm.source_mut().ret_column_names.iter().position(|x| x == "seq");
+    let name_index = vm
+        .source_mut()
+        .ret_column_names
+        .iter()
+        .position(|x| x == "name");
+    let seq_index = vm
+        .source_mut()
+        .ret_column_names
+        .iter()
+        .position(|x| x == "seq");
 
     if name_index.is_none() {
         let h = &mut vm.hint;
-        h.white("Lost ")
-            .cyan("'name'")
-            .white(" column.")
-            .print_and_exit();
+        return h.white("Lost ").cyan("'name'").white(" column.").bail();
     }
 
     if seq_index.is_none() {
         let h = &mut vm.hint;
-        h.white("Lost ")
-            .cyan("'seq'")
-            .white(" column.")
-            .print_and_exit();
+        return h.white("Lost ").cyan("'seq'").white(" column.").bail();
     }
     let name_index = name_index.unwrap();
     let seq_index = seq_index.unwrap();
@@ -29,12 +31,13 @@ fn print_fasta(vm: &mut Vm) -> FilterxResult<value::Value> {
     let seq_col = &columns[seq_index];
     if name_col.len() != seq_col.len() {
         let h = &mut vm.hint;
-        h.white("Length of ")
+        return h
+            .white("Length of ")
             .cyan("'name'")
             .white(" and ")
             .cyan("'seq'")
             .white(" columns are different.")
-            .print_and_exit();
+            .bail();
     }
     let writer = &mut vm.writer;
     for i in 0..name_col.len() {
@@ -52,12 +55,15 @@ fn print_fasta(vm: &mut Vm) -> FilterxResult<value::Value> {
 }
 
 pub fn to_fasta(vm: &mut Vm) -> FilterxResult<value::Value> {
-    if vm.source_type() == SourceType::Fasta || vm.source_type() == SourceType::Fastq {
+    if matches!(
+        vm.source_type(),
+        SourceType::Fasta | SourceType::Fastq | SourceType::GenBank
+    ) {
         return print_fasta(vm);
     }
     let h = &mut vm.hint;
     h.white("Only ")
-        .cyan("fastq, fasta ")
+        .cyan("fastq, fasta, genbank ")
         .white("formats are supported for now.")
-        .print_and_exit();
+        .bail()
 }
@@ -3,22 +3,28 @@ use std::io::Write;
 
 fn print_fastq(vm: &mut Vm) -> FilterxResult<value::Value> {
     vm.status.printed = true;
-    let name_index = vm.source_mut().ret_column_names.iter().position(|x| x == "name");
-    let seq_index = vm.source_mut().ret_column_names.iter().position(|x| x == "seq");
-    let qual_index = vm.source_mut().ret_column_names.iter().position(|x| x == "qual");
+    let name_index = vm
+        .source_mut()
+        .ret_column_names
+        .iter()
+        .position(|x| x == "name");
+    let seq_index = vm
+        .source_mut()
+        .ret_column_names
+        .iter()
+        .position(|x| x == "seq");
+    let qual_index = vm
+        .source_mut()
+        .ret_column_names
+        .iter()
+        .position(|x| x == "qual");
     if name_index.is_none() {
         let h = &mut vm.hint;
-        h.white("Lost ")
-            .cyan("'name'")
-            .white(" column.")
-            .print_and_exit();
+        return h.white("Lost ").cyan("'name'").white(" column.").bail();
     }
     if seq_index.is_none() {
         let h = &mut vm.hint;
-        h.white("Lost ")
-            .cyan("'seq'")
-            .white(" column.")
-            .print_and_exit();
+        return h.white("Lost ").cyan("'seq'").white(" column.").bail();
     }
     let name_index = name_index.unwrap();
     let seq_index = seq_index.unwrap();
@@ -28,12 +34,13 @@ fn print_fastq(vm: &mut Vm) -> FilterxResult<value::Value> {
     let seq_col = &columns[seq_index];
     if name_col.len() != seq_col.len() {
         let h = &mut vm.hint;
-        h.white("Length of ")
+        return h
+            .white("Length of ")
             .cyan("'name'")
             .white(" and ")
             .cyan("'seq'")
             .white(" columns are different.")
-            .print_and_exit();
+            .bail();
     }
     let writer = &mut vm.writer;
     if qual_index.is_some() {
@@ -89,5 +96,5 @@ pub fn to_fastq(vm: &mut Vm) -> FilterxResult<value::Value> {
     h.white("Only ")
         .cyan("fastq, fasta ")
         .white("formats are supported for now.")
-        .print_and_exit();
+        .bail()
 }
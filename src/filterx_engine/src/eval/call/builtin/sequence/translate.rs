@@ -0,0 +1,90 @@
+use super::super::*;
+use super::codon::{genetic_code, genetic_code_ids, translate_bytes};
+use filterx_core::seq::{reverse_complement, Complement};
+use polars::prelude::*;
+
+/// The genetic code of `table`, the id of NCBI translation tables.
+pub fn table_arg(
+    vm: &mut Vm,
+    value: Option<&ast::Expr>,
+    function: &str,
+) -> FilterxResult<&'static [u8; 64]> {
+    let table = match value {
+        Some(value) => int_arg(vm, value, "table")?,
+        None => 1,
+    };
+    match genetic_code(table) {
+        Some(code) => Ok(code),
+        None => {
+            let h = &mut vm.hint;
+            h.cyan(function)
+                .white(": unknown translation table ")
+                .red(&table.to_string())
+                .white(", expected one of ")
+                .green(&genetic_code_ids().join(", "))
+                .bail()
+        }
+    }
+}
+
+pub fn translate(
+    vm: &mut Vm,
+    args: &[ast::Expr],
+    keywords: &[ast::Keyword],
+    inplace: bool,
+) -> FilterxResult<value::Value> {
+    expect_keywords(keywords, &["frame", "table", "to_stop"])?;
+    let col_name = eval_col!(
+        vm,
+        &args[0],
+        "translate: expected a column name as first argument"
+    );
+    if let Some(name) = col_name.name() {
+        vm.source_mut().has_column(name)?;
+    }
+    let frame = match arg_or_keyword(args, keywords, 1, "frame") {
+        Some(value) => int_arg(vm, value, "frame")?,
+        None => 1,
+    };
+    if !matches!(frame, 1..=3 | -3..=-1) {
+        let h = &mut vm.hint;
+        return h
+            .cyan("translate")
+            .white(": frame must be one of ")
+            .green("1, 2, 3, -1, -2, -3")
+            .white(", but got ")
+            .red(&frame.to_string())
+            .bail();
+    }
+    let code = table_arg(vm, arg_or_keyword(args, keywords, 2, "table"), "translate")?;
+    let to_stop = match arg_or_keyword(args, keywords, 3, "to_stop") {
+        Some(value) => bool_arg(vm, value, "to_stop")?,
+        None => false,
+    };
+
+    let offset = frame.unsigned_abs() as usize - 1;
+    let e = col_name.expr()?.map(
+        move |s| {
+            let ca = s.str()?;
+            let ca = ca.apply_values(|seq| {
+                let seq = seq.as_bytes();
+                let protein = match frame > 0 {
+                    true => translate_bytes(code, seq.get(offset..).unwrap_or(&[]), to_stop),
+                    false => {
+                        let seq = reverse_complement(seq, Complement::Iupac);
+                        translate_bytes(code, seq.get(offset..).unwrap_or(&[]), to_stop)
+                    }
+                };
+                protein.into()
+            });
+            Ok(Some(ca.into_column()))
+        },
+        GetOutput::same_type(),
+    );
+    if inplace {
+        let name = col_name.column()?;
+        vm.source_mut().with_column(e.alias(name), None);
+        return Ok(value::Value::None);
+    }
+    Ok(value::Value::named_expr(None, e))
+}
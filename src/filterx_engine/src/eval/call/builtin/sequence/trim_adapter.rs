@@ -0,0 +1,37 @@
+use super::super::*;
+use super::adapter::{find_3prime, load_adapters, trim_matches, TrimOptions};
+
+/// Evaluate the adapter argument, a sequence or a fasta file.
+pub fn adapter_arg(vm: &mut Vm, arg: &ast::Expr, function: &str) -> FilterxResult<String> {
+    let v = eval_str!(vm, arg, "expected an adapter sequence or a fasta file");
+    match v.string() {
+        Ok(s) => Ok(s),
+        Err(_) => {
+            let h = &mut vm.hint;
+            h.cyan(function)
+                .white(": expected an adapter sequence or a fasta file, example: ")
+                .green(&format!("{}_('AGATCGGAAGAGC')", function))
+                .bail()
+        }
+    }
+}
+
+pub fn trim_adapter(
+    vm: &mut Vm,
+    args: &[ast::Expr],
+    keywords: &[ast::Keyword],
+    inplace: bool,
+) -> FilterxResult<value::Value> {
+    expect_inplace(vm, "trim_adapter", inplace)?;
+    let opt = TrimOptions::from_keywords(vm, keywords)?;
+    let arg = adapter_arg(vm, &args[0], "trim_adapter")?;
+    let adapters = load_adapters(vm, &arg, "trim_adapter")?;
+    trim_matches(
+        vm,
+        "adapter",
+        &adapters,
+        |read, adapter| find_3prime(read, adapter, opt.max_error, opt.min_overlap, opt.indel),
+        // cut from the start of the adapter
+        |m, _| (0, m.start, m.start as i64 + 1),
+    )
+}
@@ -0,0 +1,30 @@
+use super::super::*;
+
+/// Modified Mott algorithm, the same as BWA `-q` and Phred: every base scores
+/// `limit - error_probability`, keep the segment with the maximum sum.
+pub fn trim_mott_bounds(scores: &[u8], limit: f64) -> (usize, usize) {
+    let mut best = 0.0;
+    let mut best_range = (0, 0);
+    let mut sum = 0.0;
+    let mut start = 0;
+    for (i, &q) in scores.iter().enumerate() {
+        sum += limit - 10f64.powf(q as f64 / -10.0);
+        if sum < 0.0 {
+            sum = 0.0;
+            start = i + 1;
+            continue;
+        }
+        if sum > best {
+            best = sum;
+            best_range = (start, i + 1);
+        }
+    }
+    best_range
+}
+
+pub fn trim_mott(vm: &mut Vm, args: &[ast::Expr], inplace: bool) -> FilterxResult<value::Value> {
+    let limit = trim_arg(vm, args, 0, 0.05, "trim_mott")?;
+    trim_reads(vm, "trim_mott", inplace, move |scores| {
+        trim_mott_bounds(scores, limit)
+    })
+}
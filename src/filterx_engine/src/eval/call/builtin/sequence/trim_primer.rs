@@ -0,0 +1,23 @@
+use super::super::*;
+use super::adapter::{find_5prime, load_adapters, trim_matches, TrimOptions};
+use super::trim_adapter::adapter_arg;
+
+pub fn trim_primer(
+    vm: &mut Vm,
+    args: &[ast::Expr],
+    keywords: &[ast::Keyword],
+    inplace: bool,
+) -> FilterxResult<value::Value> {
+    expect_inplace(vm, "trim_primer", inplace)?;
+    let opt = TrimOptions::from_keywords(vm, keywords)?;
+    let arg = adapter_arg(vm, &args[0], "trim_primer")?;
+    let primers = load_adapters(vm, &arg, "trim_primer")?;
+    trim_matches(
+        vm,
+        "primer",
+        &primers,
+        |read, primer| find_5prime(read, primer, opt.max_error, opt.min_overlap, opt.indel),
+        // keep the bases after the primer
+        |m, len| (m.end, len, m.end as i64),
+    )
+}
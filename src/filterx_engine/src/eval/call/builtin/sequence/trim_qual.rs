@@ -0,0 +1,21 @@
+use super::super::*;
+
+/// Cut the leading and trailing bases with quality below `cutoff`.
+pub fn trim_qual_bounds(scores: &[u8], cutoff: u8) -> (usize, usize) {
+    let start = scores
+        .iter()
+        .position(|&q| q >= cutoff)
+        .unwrap_or(scores.len());
+    let end = scores
+        .iter()
+        .rposition(|&q| q >= cutoff)
+        .map_or(start, |i| i + 1);
+    (start, end)
+}
+
+pub fn trim_qual(vm: &mut Vm, args: &[ast::Expr], inplace: bool) -> FilterxResult<value::Value> {
+    let cutoff = trim_arg(vm, args, 0, 20.0, "trim_qual")?.min(255.0) as u8;
+    trim_reads(vm, "trim_qual", inplace, move |scores| {
+        trim_qual_bounds(scores, cutoff)
+    })
+}
@@ -0,0 +1,35 @@
+use super::super::*;
+
+/// Scan the read from 5' end with a window, cut when the mean quality of the
+/// window drops below `cutoff`. Like the SLIDINGWINDOW step of Trimmomatic,
+/// the leading good bases of the failed window are kept.
+pub fn trim_window_bounds(scores: &[u8], window: usize, cutoff: f64) -> (usize, usize) {
+    if scores.is_empty() {
+        return (0, 0);
+    }
+    // a read shorter than the window is checked as a whole
+    let window = window.clamp(1, scores.len());
+    let required = cutoff * window as f64;
+    let mut total: u32 = scores[..window].iter().map(|&q| q as u32).sum();
+    for i in 0..=scores.len() - window {
+        if i > 0 {
+            total = total + scores[i + window - 1] as u32 - scores[i - 1] as u32;
+        }
+        if (total as f64) < required {
+            let keep = scores[i..i + window]
+                .iter()
+                .take_while(|&&q| q as f64 >= cutoff)
+                .count();
+            return (0, i + keep);
+        }
+    }
+    (0, scores.len())
+}
+
+pub fn trim_window(vm: &mut Vm, args: &[ast::Expr], inplace: bool) -> FilterxResult<value::Value> {
+    let window = trim_arg(vm, args, 0, 4.0, "trim_window")? as usize;
+    let cutoff = trim_arg(vm, args, 1, 20.0, "trim_window")?;
+    trim_reads(vm, "trim_window", inplace, move |scores| {
+        trim_window_bounds(scores, window, cutoff)
+    })
+}
@@ -0,0 +1,28 @@
+use super::super::*;
+use super::kmer::{kmer_size, kmer_sketch};
+use polars::prelude::*;
+
+/// Number of distinct canonical k-mers of every sequence.
+pub fn uniq_kmers(vm: &mut Vm, args: &[ast::Expr]) -> FilterxResult<value::Value> {
+    let col_name = eval_col!(
+        vm,
+        &args[0],
+        "uniq_kmers: expected a column name as first argument"
+    );
+    if let Some(name) = col_name.name() {
+        vm.source_mut().has_column(name)?;
+    }
+    let k = kmer_size(vm, args.get(1), 21, "uniq_kmers")?;
+    let e = col_name.expr()?.map(
+        move |s| {
+            let ca: UInt32Chunked = s
+                .str()?
+                .into_iter()
+                .map(|seq| seq.map(|seq| kmer_sketch(seq.as_bytes(), k, 1).len() as u32))
+                .collect();
+            Ok(Some(ca.with_name(s.name().clone()).into_column()))
+        },
+        GetOutput::from_type(DataType::UInt32),
+    );
+    Ok(value::Value::named_expr(None, e))
+}
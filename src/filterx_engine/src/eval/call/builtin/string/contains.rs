@@ -0,0 +1,23 @@
+use polars::prelude::lit;
+
+use super::super::*;
+
+/// `full` is true for `match`, the whole string must match the pattern.
+pub fn contains(
+    vm: &mut Vm,
+    args: &[ast::Expr],
+    keywords: &[ast::Keyword],
+    full: bool,
+) -> FilterxResult<value::Value> {
+    let function = if full { "match" } else { "contains" };
+    expect_keywords(keywords, &["regex"])?;
+    let (e, patt) = string_pattern_args(vm, args, function)?;
+    let regex = keyword_bool(vm, keywords, "regex", true)?;
+    let e = match (full, regex) {
+        (true, true) => e.str().contains(lit(format!("^(?:{})$", patt)), true),
+        (true, false) => e.eq(lit(patt)),
+        (false, true) => e.str().contains(lit(patt), true),
+        (false, false) => e.str().contains_literal(lit(patt)),
+    };
+    Ok(value::Value::named_expr(None, e))
+}
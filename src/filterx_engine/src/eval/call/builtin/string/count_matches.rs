@@ -0,0 +1,15 @@
+use polars::prelude::lit;
+
+use super::super::*;
+
+pub fn count_matches(
+    vm: &mut Vm,
+    args: &[ast::Expr],
+    keywords: &[ast::Keyword],
+) -> FilterxResult<value::Value> {
+    expect_keywords(keywords, &["regex"])?;
+    let (e, patt) = string_pattern_args(vm, args, "count")?;
+    let regex = keyword_bool(vm, keywords, "regex", true)?;
+    let e = e.str().count_matches(lit(patt), !regex);
+    Ok(value::Value::named_expr(None, e))
+}
@@ -2,11 +2,7 @@ use polars::prelude::lit;
 
 use super::super::*;
 
-pub fn extract<'a>(
-    vm: &'a mut Vm,
-    args: &Vec<ast::Expr>,
-    inplace: bool,
-) -> FilterxResult<value::Value> {
+pub fn extract(vm: &mut Vm, args: &[ast::Expr], inplace: bool) -> FilterxResult<value::Value> {
     expect_args_len(args, 2)?;
 
     let col_name = eval_col!(
@@ -16,7 +12,7 @@ pub fn extract<'a>(
     );
     let name = col_name.column()?;
     let e = col_name.expr()?;
-    vm.source_mut().has_column(name);
+    vm.source_mut().has_column(name)?;
 
     let patt = eval!(
         vm,
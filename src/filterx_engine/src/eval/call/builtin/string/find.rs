@@ -0,0 +1,21 @@
+use polars::prelude::lit;
+
+use super::super::*;
+
+/// 0-based position of the first match, -1 if not found.
+pub fn find(
+    vm: &mut Vm,
+    args: &[ast::Expr],
+    keywords: &[ast::Keyword],
+) -> FilterxResult<value::Value> {
+    expect_keywords(keywords, &["regex"])?;
+    let (e, patt) = string_pattern_args(vm, args, "find")?;
+    let regex = keyword_bool(vm, keywords, "regex", true)?;
+    let e = if regex {
+        e.str().find(lit(patt), true)
+    } else {
+        e.str().find_literal(lit(patt))
+    };
+    let e = e.cast(polars::prelude::DataType::Int64).fill_null(lit(-1));
+    Ok(value::Value::named_expr(None, e))
+}
@@ -1,7 +1,7 @@
 use super::super::*;
 use filterx_source::source::SourceType;
 
-pub fn len<'a>(vm: &'a mut Vm, args: &Vec<ast::Expr>) -> FilterxResult<value::Value> {
+pub fn len(vm: &mut Vm, args: &[ast::Expr]) -> FilterxResult<value::Value> {
     expect_args_len(args, 1)?;
     let col_name = eval_col!(
         vm,
@@ -10,7 +10,7 @@ pub fn len<'a>(vm: &'a mut Vm, args: &Vec<ast::Expr>) -> FilterxResult<value::Va
     );
     let name = col_name.column()?;
     let mut e = col_name.expr()?;
-    vm.source_mut().has_column(name);
+    vm.source_mut().has_column(name)?;
     // !Note: Only csv has a character length.
     match vm.source_type() {
         SourceType::Csv => {
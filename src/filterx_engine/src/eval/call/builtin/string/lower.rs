@@ -2,11 +2,7 @@ use filterx_core::value::NamedExpr;
 
 use super::super::*;
 
-pub fn lower<'a>(
-    vm: &'a mut Vm,
-    args: &Vec<ast::Expr>,
-    inplace: bool,
-) -> FilterxResult<value::Value> {
+pub fn lower(vm: &mut Vm, args: &[ast::Expr], inplace: bool) -> FilterxResult<value::Value> {
     expect_args_len(args, 1)?;
     let col_name = eval_col!(
         vm,
@@ -15,7 +11,7 @@ pub fn lower<'a>(
     );
     let name = col_name.column()?;
     let e = col_name.expr()?;
-    vm.source_mut().has_column(name);
+    vm.source_mut().has_column(name)?;
     if inplace {
         vm.source_mut()
             .with_column(e.str().to_lowercase().alias(name), None);
@@ -2,14 +2,103 @@ use crate::builtin_function;
 
 builtin_function! {
     FUNCTION_STRING,
-    (len, true, false),
-    (upper, true, true),
-    (lower, true, true),
-    (slice, true, true),
-    (replace, true, true, (replace_one)),
-    (strip, true, true, (lstrip, rstrip)),
-    (rev, true, true),
-    (width, true, true, (w)),
-    (trim, true, true),
-    (extract, true, true, (get)),
+    (len, true, false), Exact(1), [Column] => |vm, c| len(vm, c.args),
+    (upper, true, true), Exact(1), [Column] => |vm, c| upper(vm, c.args, c.inplace),
+    (lower, true, true), Exact(1), [Column] => |vm, c| lower(vm, c.args, c.inplace),
+    (slice, true, true), Range(2, 3), [Column, Int, Int] => |vm, c| slice(vm, c.args, c.inplace),
+    (replace, true, true, (replace_one)), Exact(3), [Column, Str, Str]
+        => |vm, c| replace(vm, c.args, c.inplace, c.name == "replace"),
+    (strip, true, true, (lstrip, rstrip)), Exact(2), [Column, Str] => |vm, c| {
+        let (right, left) = match c.name {
+            "lstrip" => (false, true),
+            "rstrip" => (true, false),
+            _ => (true, true),
+        };
+        strip(vm, c.args, c.inplace, right, left)
+    },
+    (rev, true, true), Exact(1), [Column] => |vm, c| rev(vm, c.args, c.inplace),
+    (width, true, true, (w)), Exact(2), [Column, Int] => |vm, c| width(vm, c.args, c.inplace),
+    (trim, true, true), Exact(3), [Column, Int, Int] => |vm, c| trim(vm, c.args, c.inplace),
+    (extract, true, true, (get)), Exact(2), [Column, Str] => |vm, c| extract(vm, c.args, c.inplace),
+    (contains, true, false, (match)), Exact(2), [Column, Str]
+        => |vm, c| contains(vm, c.args, c.keywords, c.name == "match"),
+    (startswith, true, false, (endswith)), Exact(2), [Column, Str]
+        => |vm, c| startswith(vm, c.args, c.name == "endswith"),
+    (count_matches, true, false), Exact(2), [Column, Str]
+        => |vm, c| count_matches(vm, c.args, c.keywords),
+    (find, true, false), Exact(2), [Column, Str] => |vm, c| find(vm, c.args, c.keywords),
+}
+
+use super::*;
+
+/// Evaluate `(column, pattern)`, the leading arguments of string predicates.
+pub fn string_pattern_args(
+    vm: &mut Vm,
+    args: &[ast::Expr],
+    function: &str,
+) -> FilterxResult<(polars::prelude::Expr, String)> {
+    expect_args_len(args, 2)?;
+    let col_name = eval_col!(vm, &args[0], "expected a column name as first argument");
+    if let Some(name) = col_name.name() {
+        vm.source_mut().has_column(name)?;
+    }
+    let patt = eval_str!(vm, &args[1], "expected a string pattern as second argument");
+    let patt = match patt.string() {
+        Ok(patt) => patt,
+        Err(_) => {
+            let h = &mut vm.hint;
+            return h
+                .cyan(function)
+                .white(": expected a string pattern as second argument, example: ")
+                .green(&format!("{}(name, 'chr')", function))
+                .bail();
+        }
+    };
+    Ok((col_name.expr()?, patt))
+}
+
+#[test]
+fn test_string_predicates() {
+    use polars::prelude::*;
+
+    let run = |script: &str| {
+        let df = df!("name" => ["chr1", "chr10", "scaffold_a.1", "CHR2"]).unwrap();
+        crate::vm::run_df(df, script)
+    };
+    let names = |script: &str| -> Vec<String> {
+        let df = run(script).unwrap();
+        let name = df.column("name").unwrap().str().unwrap().clone();
+        name.into_no_null_iter().map(String::from).collect()
+    };
+    let ints = |script: &str| -> Vec<i64> {
+        let df = run(&format!("alias(c) = {}", script)).unwrap();
+        let c = df.column("c").unwrap().cast(&DataType::Int64).unwrap();
+        c.i64().unwrap().into_no_null_iter().collect()
+    };
+
+    assert_eq!(
+        names("contains(name, '1')"),
+        vec!["chr1", "chr10", "scaffold_a.1"]
+    );
+    assert_eq!(
+        names("contains(name, '.', regex=False)"),
+        vec!["scaffold_a.1"]
+    );
+    assert_eq!(names("contains(name, '.')").len(), 4);
+    assert_eq!(names(r"match(name, 'chr\d+')"), vec!["chr1", "chr10"]);
+    assert_eq!(names("match(name, 'chr1', regex=False)"), vec!["chr1"]);
+    assert_eq!(names("startswith(name, 'chr')"), vec!["chr1", "chr10"]);
+    assert_eq!(names("endswith(name, '1')"), vec!["chr1", "scaffold_a.1"]);
+    assert_eq!(names("name.startswith('scaf')"), vec!["scaffold_a.1"]);
+    assert_eq!(names("not name.endswith('1')"), vec!["chr10", "CHR2"]);
+
+    assert_eq!(ints("count(name, '1')"), vec![1, 1, 1, 0]);
+    assert_eq!(ints("count_matches(name, '[a-z]')"), vec![3, 3, 9, 0]);
+    assert_eq!(ints("count(name, '.', regex=False)"), vec![0, 0, 1, 0]);
+    assert_eq!(ints("find(name, 'r')"), vec![2, 2, -1, -1]);
+    assert_eq!(ints("find(name, '.', regex=False)"), vec![-1, -1, 10, -1]);
+
+    assert!(run("startswith(name, 1)").is_err());
+    assert!(run("contains(name, 'a', case=False)").is_err());
+    assert!(run("endswith(other, 'a')").is_err());
 }
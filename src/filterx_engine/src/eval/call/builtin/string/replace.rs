@@ -2,9 +2,9 @@ use polars::prelude::lit;
 
 use super::super::*;
 
-pub fn replace<'a>(
-    vm: &'a mut Vm,
-    args: &Vec<ast::Expr>,
+pub fn replace(
+    vm: &mut Vm,
+    args: &[ast::Expr],
     inplace: bool,
     many: bool,
 ) -> FilterxResult<value::Value> {
@@ -17,7 +17,7 @@ pub fn replace<'a>(
     );
     let name = col_name.column()?;
     let e = col_name.expr()?;
-    vm.source_mut().has_column(name);
+    vm.source_mut().has_column(name)?;
 
     let patt = eval!(
         vm,
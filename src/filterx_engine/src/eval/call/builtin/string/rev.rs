@@ -1,10 +1,6 @@
 use super::super::*;
 
-pub fn rev<'a>(
-    vm: &'a mut Vm,
-    args: &Vec<ast::Expr>,
-    inplace: bool,
-) -> FilterxResult<value::Value> {
+pub fn rev(vm: &mut Vm, args: &[ast::Expr], inplace: bool) -> FilterxResult<value::Value> {
     expect_args_len(args, 1)?;
 
     let col_name = eval_col!(
@@ -14,7 +10,7 @@ pub fn rev<'a>(
     );
     let name = col_name.column()?;
     let e = col_name.expr()?;
-    vm.source_mut().has_column(name);
+    vm.source_mut().has_column(name)?;
     let e = e.str().reverse();
     if inplace {
         vm.source_mut().with_column(e.clone().alias(name), None);
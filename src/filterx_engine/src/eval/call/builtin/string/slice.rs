@@ -11,14 +11,10 @@ fn check_number<'a>(vm: &'a mut Vm, n: &ast::Expr) -> FilterxResult<u32> {
     }
     let h = &mut vm.hint;
     h.white("head: expected a non-negative number as argument")
-        .print_and_exit();
+        .bail()
 }
 
-pub fn slice<'a>(
-    vm: &'a mut Vm,
-    args: &Vec<ast::Expr>,
-    inplace: bool,
-) -> FilterxResult<value::Value> {
+pub fn slice(vm: &mut Vm, args: &[ast::Expr], inplace: bool) -> FilterxResult<value::Value> {
     let col_name = eval_col!(
         vm,
         &args[0],
@@ -26,7 +22,7 @@ pub fn slice<'a>(
     );
     let name = col_name.column()?;
     let e = col_name.expr()?;
-    vm.source_mut().has_column(name);
+    vm.source_mut().has_column(name)?;
     let length;
     let mut start = 0;
     if args.len() == 2 {
@@ -0,0 +1,14 @@
+use polars::prelude::lit;
+
+use super::super::*;
+
+pub fn startswith(vm: &mut Vm, args: &[ast::Expr], end: bool) -> FilterxResult<value::Value> {
+    let function = if end { "endswith" } else { "startswith" };
+    let (e, patt) = string_pattern_args(vm, args, function)?;
+    let e = if end {
+        e.str().ends_with(lit(patt))
+    } else {
+        e.str().starts_with(lit(patt))
+    };
+    Ok(value::Value::named_expr(None, e))
+}
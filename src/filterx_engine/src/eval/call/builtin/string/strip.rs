@@ -2,9 +2,9 @@ use polars::prelude::Literal;
 
 use super::super::*;
 
-pub fn strip<'a>(
-    vm: &'a mut Vm,
-    args: &Vec<ast::Expr>,
+pub fn strip(
+    vm: &mut Vm,
+    args: &[ast::Expr],
     inplace: bool,
     right: bool,
     left: bool,
@@ -19,7 +19,7 @@ pub fn strip<'a>(
 
     let name = col_name.column()?;
     let e = col_name.expr()?;
-    vm.source_mut().has_column(&name);
+    vm.source_mut().has_column(name)?;
 
     let patt = eval!(
         vm,
@@ -1,11 +1,7 @@
 use super::super::*;
 use polars::prelude::*;
 
-pub fn trim<'a>(
-    vm: &'a mut Vm,
-    args: &Vec<ast::Expr>,
-    inplace: bool,
-) -> FilterxResult<value::Value> {
+pub fn trim(vm: &mut Vm, args: &[ast::Expr], inplace: bool) -> FilterxResult<value::Value> {
     expect_args_len(args, 3)?;
 
     let col_name = eval_col!(
@@ -15,27 +11,20 @@ pub fn trim<'a>(
     );
     let name = col_name.column()?;
     let e = col_name.expr()?;
-    vm.source_mut().has_column(name);
+    vm.source_mut().has_column(name)?;
 
-    let start = eval_int!(
-        vm,
-        &args[1],
-        "trim: expected a string as second argument"
-    );
+    let start = eval_int!(vm, &args[1], "trim: expected a string as second argument");
 
-    let end = eval_int!(
-        vm,
-        &args[2],
-        "trim: expected a string as third argument"
-    );
+    let end = eval_int!(vm, &args[2], "trim: expected a string as third argument");
 
     let start = start.int()?;
     let mut end = end.int()?;
 
     if start < 0 || end < 0 {
         let h = &mut vm.hint;
-        h.white("trim: expected a non-negative number as argument")
-            .print_and_exit();
+        return h
+            .white("trim: expected a non-negative number as argument")
+            .bail();
     }
 
     end += 1;
@@ -1,11 +1,6 @@
 use super::super::*;
 
-
-pub fn upper<'a>(
-    vm: &'a mut Vm,
-    args: &Vec<ast::Expr>,
-    inplace: bool,
-) -> FilterxResult<value::Value> {
+pub fn upper(vm: &mut Vm, args: &[ast::Expr], inplace: bool) -> FilterxResult<value::Value> {
     expect_args_len(args, 1)?;
 
     let col_name = eval_col!(
@@ -15,7 +10,7 @@ pub fn upper<'a>(
     );
     let name = col_name.column()?;
     let e = col_name.expr()?;
-    vm.source_mut().has_column(name);
+    vm.source_mut().has_column(name)?;
     if inplace {
         vm.source_mut()
             .with_column(e.str().to_uppercase().alias(name), None);
@@ -41,11 +41,7 @@ fn compute_width_string(s: Column) -> PolarsResult<Option<Column>> {
     Ok(Some(ca.into_column()))
 }
 
-pub fn width<'a>(
-    vm: &'a mut Vm,
-    args: &Vec<ast::Expr>,
-    inplace: bool,
-) -> FilterxResult<value::Value> {
+pub fn width(vm: &mut Vm, args: &[ast::Expr], inplace: bool) -> FilterxResult<value::Value> {
     expect_args_len(args, 2)?;
 
     let col_name = eval_col!(
@@ -61,15 +57,16 @@ pub fn width<'a>(
     );
 
     let name = col_name.column()?;
-    vm.source_mut().has_column(name);
+    vm.source_mut().has_column(name)?;
     let e = col_name.expr()?;
     let width = width.int()?;
 
     if width < 0 {
         let h = &mut vm.hint;
-        h.white("width: expected a non-negative number as argument, but got ")
+        return h
+            .white("width: expected a non-negative number as argument, but got ")
             .cyan(&format!("{}", width))
-            .print_and_exit();
+            .bail();
     }
 
     unsafe { STRING_WIDTH = width as usize };
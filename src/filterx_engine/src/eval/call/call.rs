@@ -1,56 +1,63 @@
 use std::ops::Deref;
 
-use polars::frame::UniqueKeepStrategy;
-
 use super::super::ast;
 
 use crate::vm::{Vm, VmMode};
 use filterx_core::{value, FilterxResult};
 
-use super::functions::get_function;
-use crate::eval::call::builtin as call;
+use super::functions::{check_arity, get_function, CallArgs};
 use crate::eval::Eval;
 
+/// Rewrite the method call `recv.method(args)` to `method(recv, args)`, the
+/// receiver is passed as the first argument of the builtin.
+pub fn method_call(
+    attr: &ast::ExprAttribute,
+    args: Vec<ast::Expr>,
+    keywords: Vec<ast::Keyword>,
+) -> ast::ExprCall {
+    let func = ast::ExprName {
+        range: attr.range,
+        id: attr.attr.clone(),
+        ctx: ast::ExprContext::Load,
+    };
+    let mut call_args = Vec::with_capacity(args.len() + 1);
+    call_args.push(attr.value.deref().clone());
+    call_args.extend(args);
+    ast::ExprCall {
+        range: attr.range,
+        func: Box::new(ast::Expr::Name(func)),
+        args: call_args,
+        keywords,
+    }
+}
+
 impl<'a> Eval<'a> for ast::ExprCall {
     type Output = value::Value;
     fn eval(&self, vm: &'a mut Vm) -> FilterxResult<Self::Output> {
         let original_function_name: String = match self.func.deref() {
-            ast::Expr::Name(e) => {
-                let v = e.eval(vm)?;
-                v.text()?
-            }
+            ast::Expr::Name(e) => e.id.to_string(),
+            // seq.revcomp() -> revcomp(seq)
             ast::Expr::Attribute(a) => {
-                let v = a.eval(vm)?;
-                return Ok(v);
+                let call = method_call(a, self.args.clone(), self.keywords.clone());
+                return call.eval(vm);
             }
             _ => unreachable!(),
         };
 
-        let mut function_name = original_function_name.as_str();
+        let f = get_function(&original_function_name)?;
 
-        let f = get_function(function_name);
-
-        let inplace = function_name.ends_with("_");
-        let mut original_function_name = function_name;
-
-        if inplace {
-            function_name = function_name.strip_suffix("_").unwrap();
-            original_function_name = function_name;
-        }
-        let mut sub_function_name = "";
-
-        if function_name.starts_with("cast_") {
-            sub_function_name = function_name.strip_prefix("cast_").unwrap();
-        }
-        if function_name.starts_with("cast") {
-            function_name = "cast";
-        }
+        let inplace = original_function_name.ends_with("_");
+        let function_name = match inplace {
+            true => original_function_name.strip_suffix("_").unwrap(),
+            false => original_function_name.as_str(),
+        };
 
         if vm.mode == VmMode::Print {
-            if !f.can_expression {
+            if !f.can_expression() {
                 let h = &mut vm.hint;
-                h.white("Function `")
-                    .cyan(&original_function_name)
+                return h
+                    .white("Function `")
+                    .cyan(function_name)
                     .bold()
                     .white("` can not be used in ")
                     .green("`print`")
@@ -58,13 +65,14 @@ impl<'a> Eval<'a> for ast::ExprCall {
                     .white(" formatter. But got ")
                     .cyan(&vm.print_expr)
                     .white(".")
-                    .print_and_exit();
+                    .bail();
             }
 
             if inplace {
                 let h = &mut vm.hint;
-                h.white("Function `")
-                    .cyan(&original_function_name)
+                return h
+                    .white("Function `")
+                    .cyan(function_name)
                     .bold()
                     .white("(")
                     .cyan("inplace")
@@ -72,60 +80,47 @@ impl<'a> Eval<'a> for ast::ExprCall {
                     .green("`print`")
                     .bold()
                     .white(" formatter.")
-                    .print_and_exit();
+                    .bail();
             }
         }
 
-        match function_name {
-            "alias" => call::alias(vm, &self.args),
-            "rm" => call::rm(vm, &self.args),
-            "select" => call::select(vm, &self.args),
-            "col" | "c" => call::col(vm, &self.args),
-            "rename" => call::rename(vm, &self.args),
-            "head" | "limit" => call::head(vm, &self.args),
-            "tail" => call::tail(vm, &self.args),
-            "Sort" => call::sort(vm, &self.args, false),
-            "sorT" => call::sort(vm, &self.args, true),
-            "sort" => call::sort(vm, &self.args, true),
-            "len" => call::len(vm, &self.args),
-            "print" | "format" | "fmt" | "f" => call::print(vm, &self.args),
-            "gc" => call::gc(vm, &self.args),
-            "qual" => call::qual(vm, &self.args),
-            "phred" => call::phred(vm),
-            "rev" => call::rev(vm, &self.args, inplace),
-            "revcomp" => call::revcomp(vm, &self.args, inplace),
-            "hpc" => call::hpc(vm, &self.args, inplace),
-            "upper" => call::upper(vm, &self.args, inplace),
-            "lower" => call::lower(vm, &self.args, inplace),
-            "replace" => call::replace(vm, &self.args, inplace, true),
-            "replace_one" => call::replace(vm, &self.args, inplace, false),
-            "extract" | "get" => call::extract(vm, &self.args, inplace),
-            "strip" => call::strip(vm, &self.args, inplace, true, true),
-            "lstrip" => call::strip(vm, &self.args, inplace, false, true),
-            "rstrip" => call::strip(vm, &self.args, inplace, true, false),
-            "slice" => call::slice(vm, &self.args, inplace),
-            "trim" => call::trim(vm, &self.args, inplace),
-            "header" => call::header(vm),
-            "cast" => call::cast(vm, &self.args, &sub_function_name, inplace),
-            "fill" | "fill_null" => call::fill(vm, &self.args, inplace, false),
-            "fill_nan" => call::fill(vm, &self.args, inplace, true),
-            "dup" => call::dup(vm, &self.args, UniqueKeepStrategy::First),
-            "dup_none" => call::dup(vm, &self.args, UniqueKeepStrategy::None),
-            "dup_last" => call::dup(vm, &self.args, UniqueKeepStrategy::Last),
-            "dup_any" => call::dup(vm, &self.args, UniqueKeepStrategy::Any),
-            "abs" => call::abs(vm, &self.args, inplace),
-            "is_null" => call::is_null(vm, &self.args, false),
-            "is_not_null" => call::is_null(vm, &self.args, true),
-            "drop_null" => call::drop_null(vm, &self.args),
-            "to_fa" | "to_fasta" => call::to_fasta(vm),
-            "to_fq" | "to_fastq" => call::to_fastq(vm),
-            "w" | "width" => call::width(vm, &self.args, inplace),
-            "occ_lte" => call::occ(vm, &self.args, true),
-            "occ_gte" => call::occ(vm, &self.args, false),
-            "occ" => call::occ(vm, &self.args, false),
-            _ => {
-                unreachable!();
-            }
-        }
+        check_arity(vm, f, function_name, self.args.len())?;
+        let call = CallArgs {
+            name: function_name,
+            args: &self.args,
+            keywords: &self.keywords,
+            inplace,
+        };
+        f.eval(vm, &call)
     }
 }
+
+#[test]
+fn test_method_call() {
+    use polars::prelude::*;
+
+    let run = |script: &str| {
+        let df = df!("name" => ["r1", "r2", "r3"], "seq" => ["ACGT", "AAAA", "GGCA"]).unwrap();
+        crate::vm::run_df(df, script)
+    };
+    let strs = |df: DataFrame, name: &str| -> Vec<String> {
+        let c = df.column(name).unwrap().str().unwrap().clone();
+        c.into_no_null_iter().map(String::from).collect()
+    };
+
+    // the receiver is the first argument, seq.slice(2) is slice(seq, 2)
+    let df = run("alias(c) = seq.lower(); alias(d) = seq.slice(2)").unwrap();
+    assert_eq!(strs(df.clone(), "c"), vec!["acgt", "aaaa", "ggca"]);
+    assert_eq!(strs(df, "d"), vec!["AC", "AA", "GG"]);
+    // chained calls, and an attribute without `()` in a condition
+    let df = run("seq.lower().slice(1) == 'a' and seq.upper != 'AAAA'").unwrap();
+    assert_eq!(strs(df, "name"), vec!["r1"]);
+    let df = run("alias(c) = seq.len; alias(d) = len(seq); c == d").unwrap();
+    assert_eq!(df.height(), 3);
+    // inplace version
+    let df = run("seq.lower_()").unwrap();
+    assert_eq!(strs(df, "seq"), vec!["acgt", "aaaa", "ggca"]);
+
+    assert!(run("seq.foo()").is_err());
+    assert!(run("seq.slice()").is_err());
+}
@@ -1,29 +1,211 @@
-use filterx_core::Hint;
+use std::sync::RwLock;
+
+use filterx_core::{value::Value, FilterxResult, Hint};
+use lazy_static::lazy_static;
 
 use super::builtin::*;
+use crate::ast;
+use crate::vm::Vm;
+
+/// Number of positional arguments accepted by a builtin function.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Arity {
+    Exact(usize),
+    /// min and max, both inclusive
+    Range(usize, usize),
+    AtLeast(usize),
+    Any,
+}
+
+impl Arity {
+    pub fn check(&self, n: usize) -> bool {
+        match *self {
+            Arity::Exact(e) => n == e,
+            Arity::Range(min, max) => n >= min && n <= max,
+            Arity::AtLeast(min) => n >= min,
+            Arity::Any => true,
+        }
+    }
+}
+
+impl std::fmt::Display for Arity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Arity::Exact(e) => write!(f, "{}", e),
+            Arity::Range(min, max) => write!(f, "{} to {}", min, max),
+            Arity::AtLeast(min) => write!(f, "at least {}", min),
+            Arity::Any => write!(f, "any number of"),
+        }
+    }
+}
+
+/// Kind of a positional argument, the last one repeats for variadic functions.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ArgType {
+    Column,
+    Str,
+    Int,
+    Number,
+    Bool,
+    Any,
+}
+
+impl std::fmt::Display for ArgType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            ArgType::Column => "column",
+            ArgType::Str => "str",
+            ArgType::Int => "int",
+            ArgType::Number => "number",
+            ArgType::Bool => "bool",
+            ArgType::Any => "value",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Arguments of a call to a builtin function.
+pub struct CallArgs<'a> {
+    /// the name used in expression without the trailing `_`, one of the aliases
+    pub name: &'a str,
+    pub args: &'a [ast::Expr],
+    pub keywords: &'a [ast::Keyword],
+    pub inplace: bool,
+}
+
+/// A builtin function which can be called in expression. Downstream crates
+/// implement it, or use [`BuiltinFunction`], and add it by [`register_function`].
+pub trait Builtin: Sync + Send {
+    fn name(&self) -> &str;
+    /// all names of the function, including `name`
+    fn aliases(&self) -> &[&str];
+    fn arity(&self) -> Arity {
+        Arity::Any
+    }
+    fn arg_types(&self) -> &[ArgType] {
+        &[]
+    }
+    /// can be used in `print` and on the right of `alias(x) = ...`
+    fn can_expression(&self) -> bool;
+    /// can be called with a trailing `_` to update the column in place
+    fn can_inplace(&self) -> bool;
+    /// markdown shown by `filterx info`
+    fn doc(&self) -> &str;
+    fn eval(&self, vm: &mut Vm, call: &CallArgs) -> FilterxResult<Value>;
+
+    /// e.g. `replace(column, str, str)`
+    fn signature(&self) -> String {
+        let types = self.arg_types();
+        let mut args: Vec<String> = types.iter().map(|t| t.to_string()).collect();
+        match self.arity() {
+            Arity::Range(min, _) => {
+                for arg in args.iter_mut().skip(min) {
+                    *arg = format!("[{}]", arg);
+                }
+            }
+            Arity::AtLeast(_) | Arity::Any if !args.is_empty() => {
+                args.push("...".to_string());
+            }
+            _ => {}
+        }
+        format!("{}({})", self.name(), args.join(", "))
+    }
+}
+
+pub type BuiltinEval = fn(&mut Vm, &CallArgs) -> FilterxResult<Value>;
 
 pub struct BuiltinFunction {
     pub name: &'static str,
     pub alias: &'static [&'static str],
+    pub arity: Arity,
+    pub arg_types: &'static [ArgType],
     pub can_expression: bool,
     pub can_inplace: bool,
     pub doc: &'static str,
+    pub eval: BuiltinEval,
+}
+
+impl Builtin for BuiltinFunction {
+    fn name(&self) -> &str {
+        self.name
+    }
+    fn aliases(&self) -> &[&str] {
+        self.alias
+    }
+    fn arity(&self) -> Arity {
+        self.arity
+    }
+    fn arg_types(&self) -> &[ArgType] {
+        self.arg_types
+    }
+    fn can_expression(&self) -> bool {
+        self.can_expression
+    }
+    fn can_inplace(&self) -> bool {
+        self.can_inplace
+    }
+    fn doc(&self) -> &str {
+        self.doc
+    }
+    fn eval(&self, vm: &mut Vm, call: &CallArgs) -> FilterxResult<Value> {
+        (self.eval)(vm, call)
+    }
 }
 
-pub static ALL_FUNCTIONS: [&'static [BuiltinFunction]; 5] = [
+pub static ALL_FUNCTIONS: [&[BuiltinFunction]; 6] = [
     FUNCTION_COLUMN,
     FUNCTION_STRING,
     FUNCTION_SEQUENCE,
     FUNCTION_NUMBER,
     FUNCTION_ROW,
+    FUNCTION_GROUP,
 ];
 
-fn compute_similarity(target: &str) -> Option<&str> {
-    let mut best_score = 0.0;
-    let mut best_name = "";
-    for function_list in ALL_FUNCTIONS.iter() {
-        for function in function_list.iter() {
-            for alias in function.alias.iter() {
+/// A set of builtin functions, looked up by name or alias.
+pub struct Registry {
+    functions: Vec<&'static dyn Builtin>,
+}
+
+impl Default for Registry {
+    /// All functions of filterx.
+    fn default() -> Self {
+        Registry {
+            functions: ALL_FUNCTIONS
+                .iter()
+                .flat_map(|group| group.iter())
+                .map(|f| f as &'static dyn Builtin)
+                .collect(),
+        }
+    }
+}
+
+impl Registry {
+    /// Add a function, its name and aliases must not be used by other
+    /// functions.
+    pub fn register<F: Builtin + 'static>(&mut self, function: F) -> FilterxResult<()> {
+        for alias in function.aliases() {
+            if self.functions.iter().any(|f| f.aliases().contains(alias)) {
+                let mut h = Hint::new();
+                return h
+                    .white("Function `")
+                    .cyan(alias)
+                    .white("` is already registered.")
+                    .bail();
+            }
+        }
+        self.functions.push(Box::leak(Box::new(function)));
+        Ok(())
+    }
+
+    pub fn list(&self) -> Vec<&'static dyn Builtin> {
+        self.functions.clone()
+    }
+
+    fn compute_similarity(&self, target: &str) -> Option<&'static str> {
+        let mut best_score = 0.0;
+        let mut best_name = "";
+        for function in &self.functions {
+            for alias in function.aliases() {
                 let score = strsim::jaro_winkler(target, alias);
                 if score > best_score {
                     best_score = score;
@@ -31,55 +213,144 @@ fn compute_similarity(target: &str) -> Option<&str> {
                 }
             }
         }
-    }
 
-    if best_score >= 0.6 {
-        return Some(best_name);
+        if best_score >= 0.6 {
+            return Some(best_name);
+        }
+        None
     }
-    None
-}
 
-pub fn get_function(name: &str) -> &'static BuiltinFunction {
-    let inplace = name.ends_with("_");
-    let pure_name = if inplace {
-        &name[..name.len() - 1]
-    } else {
-        name
-    };
-    for functions in ALL_FUNCTIONS.iter() {
-        for function in functions.iter() {
-            if function.alias.contains(&pure_name) {
-                if inplace && !function.can_inplace {
+    pub fn get(&self, name: &str) -> FilterxResult<&'static dyn Builtin> {
+        let inplace = name.ends_with("_");
+        let pure_name = if inplace {
+            &name[..name.len() - 1]
+        } else {
+            name
+        };
+        for &function in &self.functions {
+            if function.aliases().contains(&pure_name) {
+                if inplace && !function.can_inplace() {
                     let mut h = Hint::new();
-                    h.white("Function: ")
+                    return h
+                        .white("Function: ")
                         .cyan(pure_name)
                         .bold()
                         .white(" can't be used as inplace.")
-                        .print_and_exit();
+                        .bail();
                 }
-                return function;
+                return Ok(function);
             }
         }
+
+        let mut h = Hint::new();
+        let simi = self.compute_similarity(pure_name);
+        h.white("Function `").cyan(name).white("` does not found.");
+
+        if let Some(simi) = simi {
+            h.suggestion(simi);
+        }
+        h.bail()
     }
+}
 
-    let mut h = Hint::new();
-    let simi = compute_similarity(&pure_name);
-    h.white("Function `").cyan(&name).white("` does not found.");
+lazy_static! {
+    static ref REGISTRY: RwLock<Registry> = RwLock::new(Registry::default());
+}
+
+/// Add a function to the global registry, its name and aliases must not be
+/// used by other functions.
+pub fn register_function<F: Builtin + 'static>(function: F) -> FilterxResult<()> {
+    REGISTRY.write().unwrap().register(function)
+}
+
+pub fn get_function(name: &str) -> FilterxResult<&'static dyn Builtin> {
+    REGISTRY.read().unwrap().get(name)
+}
 
-    if simi.is_some() {
-        h.white(" Similar function `")
-            .green(simi.unwrap())
-            .white("` found.");
+pub fn list_functions() -> Vec<&'static dyn Builtin> {
+    REGISTRY.read().unwrap().list()
+}
+
+/// Check the number of positional arguments of a call.
+pub fn check_arity(vm: &mut Vm, function: &dyn Builtin, name: &str, n: usize) -> FilterxResult<()> {
+    let arity = function.arity();
+    if arity.check(n) {
+        return Ok(());
+    }
+    let h = &mut vm.hint;
+    h.white("Function `")
+        .cyan(name)
+        .white("` expects ")
+        .cyan(&arity.to_string())
+        .white(match arity {
+            Arity::Exact(1) | Arity::AtLeast(1) => " argument, but got ",
+            _ => " arguments, but got ",
+        })
+        .red(&n.to_string())
+        .white(".");
+    if !function.arg_types().is_empty() {
+        h.white(" Usage: ").green(&function.signature());
     }
-    h.print_and_exit();
+    h.bail()
 }
 
-pub fn list_functions() -> Vec<&'static BuiltinFunction> {
-    let mut funcions = vec![];
-    for function_group in ALL_FUNCTIONS.iter() {
-        for function in function_group.iter() {
-            funcions.push(function);
+#[test]
+fn test_register_function() {
+    use crate::eval::Eval;
+    use crate::{eval, eval_col};
+    use filterx_core::writer::FilterxWriter;
+    use filterx_source::{DataframeSource, Source, SourceType};
+    use polars::prelude::*;
+
+    struct Twice;
+    impl Builtin for Twice {
+        fn name(&self) -> &str {
+            "twice"
+        }
+        fn aliases(&self) -> &[&str] {
+            &["twice"]
+        }
+        fn arity(&self) -> Arity {
+            Arity::Exact(1)
+        }
+        fn can_expression(&self) -> bool {
+            true
+        }
+        fn can_inplace(&self) -> bool {
+            false
+        }
+        fn doc(&self) -> &str {
+            ""
+        }
+        fn eval(&self, vm: &mut Vm, call: &CallArgs) -> FilterxResult<Value> {
+            let v = eval_col!(vm, &call.args[0], "twice: expected a column");
+            Ok(Value::named_expr(None, v.expr()? * lit(2)))
         }
     }
-    funcions
+
+    let mut registry = Registry::default();
+    registry.register(Twice).unwrap();
+    assert!(registry.register(Twice).is_err());
+    // the global registry is not changed
+    assert!(get_function("twice").is_err());
+
+    let df = df!("a" => [1, 2, 3]).unwrap();
+    let mut source = DataframeSource::new(df.clone().lazy());
+    source.set_init_column_names(&vec!["a".to_string()]);
+    let writer = FilterxWriter::new(None, None, None).unwrap();
+    let mut vm = Vm::from_source(Source::new(source.into(), SourceType::Csv), writer);
+    let args = match vm.ast("a").unwrap() {
+        rustpython_parser::ast::Mod::Expression(e) => vec![*e.body],
+        _ => unreachable!(),
+    };
+    let call = CallArgs {
+        name: "twice",
+        args: &args,
+        keywords: &[],
+        inplace: false,
+    };
+    let v = registry.get("twice").unwrap().eval(&mut vm, &call).unwrap();
+    let df = df.lazy().select([v.expr().unwrap()]).collect().unwrap();
+    let a: Vec<Option<i32>> = df.column("a").unwrap().i32().unwrap().into_iter().collect();
+    assert_eq!(a, vec![Some(2), Some(4), Some(6)]);
 }
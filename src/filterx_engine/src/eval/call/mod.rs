@@ -2,4 +2,6 @@ pub mod builtin;
 pub mod call;
 pub mod functions;
 
-pub use functions::BuiltinFunction;
+pub use functions::{
+    register_function, ArgType, Arity, Builtin, BuiltinEval, BuiltinFunction, CallArgs, Registry,
+};
@@ -6,8 +6,9 @@ pub mod ops;
 use std::ops::Deref;
 
 use super::ast;
-use super::vm::Vm;
+use super::vm::{Vm, VmMode};
 use filterx_core::{value, FilterxError, FilterxResult};
+use polars::prelude::DataType;
 
 pub trait Eval<'a> {
     type Output;
@@ -17,38 +18,87 @@ pub trait Eval<'a> {
 impl<'a> Eval<'a> for ast::ModExpression {
     type Output = value::Value;
     fn eval(&self, vm: &'a mut Vm) -> FilterxResult<Self::Output> {
-        match self.body.deref() {
-            // in/not in
-            // >, <, >=, <=, ==, !=
-            ast::Expr::Compare(c) => {
-                let v = c.eval(vm)?;
-                Ok(v)
-            }
-            // and, or
-            ast::Expr::BoolOp(b) => {
-                let v = b.eval(vm)?;
-                Ok(v)
-            }
-            // a.b
-            // a(1)
-            ast::Expr::Call(c) => {
-                let v = c.eval(vm)?;
-                Ok(v)
-            }
-            ast::Expr::Tuple(t) => {
-                let v = t.eval(vm)?;
-                Ok(v)
-            }
-            ast::Expr::BinOp(b) => {
-                let v = b.eval(vm)?;
-                Ok(v)
-            }
-            _ => {
-                let err = format!("A expr is required. But got {}", vm.eval_expr);
-                return Err(FilterxError::RuntimeError(err));
+        eval_expression(vm, self.body.deref())
+    }
+}
+
+/// A statement which is an expression, e.g. `len(seq) > 10` or `print("{name}")`.
+fn eval_expression(vm: &mut Vm, expr: &ast::Expr) -> FilterxResult<value::Value> {
+    match expr {
+        // in/not in
+        // >, <, >=, <=, ==, !=
+        ast::Expr::Compare(c) => {
+            let v = c.eval(vm)?;
+            filter_rows(vm, &v)?;
+            Ok(v)
+        }
+        // and, or
+        ast::Expr::BoolOp(b) => {
+            let v = b.eval(vm)?;
+            filter_rows(vm, &v)?;
+            Ok(v)
+        }
+        // not
+        ast::Expr::UnaryOp(u) if u.op == ast::UnaryOp::Not => {
+            let v = u.eval(vm)?;
+            filter_rows(vm, &v)?;
+            Ok(v)
+        }
+        // a.b
+        // a(1)
+        ast::Expr::Call(c) => {
+            let v = c.eval(vm)?;
+            // predicates like `is_null(a)` filter rows as well
+            if vm.mode != VmMode::Print && v.is_expr() && is_boolean(vm, &v)? {
+                filter_rows(vm, &v)?;
             }
+            Ok(v)
         }
+        // a.b, the same as a.b()
+        ast::Expr::Attribute(a) => {
+            let v = a.eval(vm)?;
+            Ok(v)
+        }
+        ast::Expr::Tuple(t) => {
+            let v = t.eval(vm)?;
+            Ok(v)
+        }
+        ast::Expr::BinOp(b) => {
+            let v = b.eval(vm)?;
+            Ok(v)
+        }
+        // a if cond else b
+        ast::Expr::IfExp(i) => {
+            let v = i.eval(vm)?;
+            Ok(v)
+        }
+        _ => {
+            let err = format!("A expr is required. But got {}", vm.eval_expr);
+            Err(FilterxError::RuntimeError(err))
+        }
+    }
+}
+
+/// Keep the rows where a top level boolean expression is true.
+/// Values in `print` format strings never filter.
+fn filter_rows(vm: &mut Vm, v: &value::Value) -> FilterxResult<()> {
+    if vm.mode == VmMode::Print {
+        return Ok(());
     }
+    let e = v.expr()?;
+    vm.source_mut().filter(e);
+    Ok(())
+}
+
+fn is_boolean(vm: &mut Vm, v: &value::Value) -> FilterxResult<bool> {
+    let e = v.expr()?;
+    let mut lazy = vm.source_mut().lazy().select([e]);
+    let schema = lazy.collect_schema()?;
+    let is_boolean = schema
+        .iter_values()
+        .next()
+        .is_some_and(|dtype| *dtype == DataType::Boolean);
+    Ok(is_boolean)
 }
 
 impl<'a> Eval<'a> for ast::ModInteractive {
@@ -59,8 +109,15 @@ impl<'a> Eval<'a> for ast::ModInteractive {
                 "Only support one line".to_string(),
             ));
         }
-        let stmt = self.body.first().unwrap();
-        match stmt {
+        self.body.first().unwrap().eval(vm)
+    }
+}
+
+impl<'a> Eval<'a> for ast::Stmt {
+    type Output = value::Value;
+    fn eval(&self, vm: &'a mut Vm) -> FilterxResult<Self::Output> {
+        match self {
+            ast::Stmt::Expr(e) => eval_expression(vm, e.value.deref()),
             ast::Stmt::Assign(a) => {
                 let v = a.eval(vm)?;
                 Ok(v)
@@ -69,12 +126,10 @@ impl<'a> Eval<'a> for ast::ModInteractive {
                 let v = a.eval(vm)?;
                 Ok(v)
             }
-            _ => {
-                return Err(FilterxError::RuntimeError(format!(
-                    "Only support assign and augassign. But got {}",
-                    vm.eval_expr
-                )));
-            }
+            _ => Err(FilterxError::RuntimeError(format!(
+                "Only support expression, assign and augassign. But got {}",
+                vm.eval_expr
+            ))),
         }
     }
 }
@@ -1,4 +1,5 @@
 use super::super::ast;
+use crate::eval::call::call::method_call;
 use crate::eval::Eval;
 use crate::vm::Vm;
 use filterx_core::{value, FilterxResult};
@@ -25,7 +26,7 @@ impl<'a> Eval<'a> for ast::ExprConstant {
                     .white(", ")
                     .cyan("str")
                     .white(" are supported in expression.");
-                h.print_and_exit();
+                return h.bail();
             }
         };
         Ok(r)
@@ -55,7 +56,7 @@ impl<'a> Eval<'a> for ast::ExprTuple {
                         .white(", ")
                         .cyan("str")
                         .white(" are supported in expression.");
-                    h.print_and_exit();
+                    return h.bail();
                 }
             }
         }
@@ -70,7 +71,7 @@ impl<'a> Eval<'a> for ast::ExprName {
             ast::ExprContext::Del => {
                 let h = &mut vm.hint;
                 h.white("Can't use ").cyan("del").white(" on name.");
-                h.print_and_exit();
+                return h.bail();
             }
             _ => {}
         };
@@ -89,6 +90,12 @@ impl<'a> Eval<'a> for ast::ExprName {
             _ => return Ok(keywords),
         }
 
+        if matches!(self.ctx, ast::ExprContext::Load) {
+            if let Some(v) = vm.variables.get(id) {
+                return Ok(v.clone());
+            }
+        }
+
         let name = value::Name {
             name: id.to_string(),
             ctx: match self.ctx {
@@ -102,10 +109,10 @@ impl<'a> Eval<'a> for ast::ExprName {
     }
 }
 
+/// `seq.gc` is the same as `seq.gc()`
 impl<'a> Eval<'a> for ast::ExprAttribute {
     type Output = value::Value;
-    fn eval(&self, _vm: &'a mut Vm) -> FilterxResult<Self::Output> {
-        // TODO implement attribute
-        Ok(value::Value::None)
+    fn eval(&self, vm: &'a mut Vm) -> FilterxResult<Self::Output> {
+        method_call(self, vec![], vec![]).eval(vm)
     }
 }
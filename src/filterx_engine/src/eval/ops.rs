@@ -13,6 +13,11 @@ use filterx_core::{value::Value, FilterxResult, Hint};
 impl<'a> Eval<'a> for ast::ExprUnaryOp {
     type Output = Value;
     fn eval(&self, vm: &'a mut Vm) -> FilterxResult<Self::Output> {
+        if self.op == ast::UnaryOp::Not {
+            let e = boolean_expr(vm, self.operand.deref())?;
+            return Ok(Value::named_expr(None, e.not()));
+        }
+
         let v = eval!(
             vm,
             self.operand.deref(),
@@ -31,14 +36,16 @@ impl<'a> Eval<'a> for ast::ExprUnaryOp {
                 .red(&format!("{:?}", self.operand.deref())),
             Constant,
             Call,
+            Attribute,
             UnaryOp,
             Name
         );
 
         match self.op {
-            ast::UnaryOp::Invert | ast::UnaryOp::Not | ast::UnaryOp::UAdd => {
+            ast::UnaryOp::Invert | ast::UnaryOp::UAdd => {
                 let h = &mut vm.hint;
-                h.white("only support -")
+                return h
+                    .white("only support - and not, ")
                     .white("example: ")
                     .cyan("-1")
                     .white(", ")
@@ -47,7 +54,9 @@ impl<'a> Eval<'a> for ast::ExprUnaryOp {
                     .cyan("-(a)")
                     .white(", ")
                     .cyan("-gc(seq)")
-                    .print_and_exit();
+                    .white(", ")
+                    .cyan("not a > 1")
+                    .bail();
             }
             _ => {}
         }
@@ -85,14 +94,14 @@ fn unary(v: Value, _op: ast::UnaryOp) -> FilterxResult<Value> {
 ///    Mult,    *
 ///    MatMult,
 ///    Div,     *
-///    Mod,
-///    Pow,
+///    Mod,     *
+///    Pow,     *
 ///    LShift,
 ///    RShift,
 ///    BitOr,
 ///    BitXor,
 ///    BitAnd,
-///    FloorDiv,
+///    FloorDiv,*
 ///}
 impl<'a> Eval<'a> for ast::ExprBinOp {
     type Output = Value;
@@ -103,6 +112,7 @@ impl<'a> Eval<'a> for ast::ExprBinOp {
             "Only support constant and constant, column and constant, column and column",
             Constant,
             Call,
+            Attribute,
             UnaryOp,
             Name,
             BinOp
@@ -113,6 +123,7 @@ impl<'a> Eval<'a> for ast::ExprBinOp {
             "Only support constant and constant, column and constant, column and column",
             Constant,
             Call,
+            Attribute,
             UnaryOp,
             Name,
             BinOp
@@ -122,11 +133,11 @@ impl<'a> Eval<'a> for ast::ExprBinOp {
         let rname = r.name();
 
         if let Some(name) = lname {
-            vm.source_mut().has_column(name);
+            vm.source_mut().has_column(name)?;
         }
 
         if let Some(name) = rname {
-            vm.source_mut().has_column(name);
+            vm.source_mut().has_column(name)?;
         }
 
         match self.op {
@@ -135,13 +146,16 @@ impl<'a> Eval<'a> for ast::ExprBinOp {
             | ast::Operator::Mult
             | ast::Operator::Div
             | ast::Operator::Mod
+            | ast::Operator::Pow
+            | ast::Operator::FloorDiv
             | ast::Operator::BitAnd
             | ast::Operator::BitOr => {}
             _ => {
                 let h = &mut vm.hint;
-                h.white("Only support binary op: ")
-                    .cyan("+, -, *, /, %, &, |")
-                    .print_and_exit();
+                return h
+                    .white("Only support binary op: ")
+                    .cyan("+, -, *, /, //, %, **, &, |")
+                    .bail();
             }
         }
 
@@ -152,13 +166,15 @@ impl<'a> Eval<'a> for ast::ExprBinOp {
                 ast::Operator::Mult => binop(l, r, ast::Operator::Mult),
                 ast::Operator::Div => binop(l, r, ast::Operator::Div),
                 ast::Operator::Mod => binop(l, r, ast::Operator::Mod),
+                ast::Operator::Pow => binop(l, r, ast::Operator::Pow),
+                ast::Operator::FloorDiv => binop(l, r, ast::Operator::FloorDiv),
                 ast::Operator::BitAnd => binop(l, r, ast::Operator::BitAnd),
                 ast::Operator::BitOr => binop(l, r, ast::Operator::BitOr),
                 _ => {
                     unreachable!();
                 }
             };
-            return Ok(Value::named_expr(None, ret.expr()?));
+            return ret;
         }
 
         if l.is_expr() || r.is_expr() {
@@ -170,6 +186,8 @@ impl<'a> Eval<'a> for ast::ExprBinOp {
                 ast::Operator::Mult => l * r,
                 ast::Operator::Div => l / r,
                 ast::Operator::Mod => l % r,
+                ast::Operator::Pow => l.pow(r),
+                ast::Operator::FloorDiv => l.floor_div(r),
                 ast::Operator::BitAnd => l.and(r),
                 ast::Operator::BitOr => l.or(r),
                 _ => {
@@ -182,8 +200,8 @@ impl<'a> Eval<'a> for ast::ExprBinOp {
     }
 }
 
-fn binop(l: Value, r: Value, op: ast::Operator) -> Value {
-    match op {
+pub(crate) fn binop(l: Value, r: Value, op: ast::Operator) -> FilterxResult<Value> {
+    let ret = match op {
         ast::Operator::Add => match (l, r) {
             (Value::Int(l), Value::Int(r)) => Value::Int(l + r),
             (Value::Float(l), Value::Float(r)) => Value::Float(l + r),
@@ -197,11 +215,12 @@ fn binop(l: Value, r: Value, op: ast::Operator) -> Value {
             (Value::Int(l), Value::Float(r)) => Value::Float(l as f64 + r),
             (l, r) => {
                 let mut h = Hint::new();
-                h.white("can't perform add(+) operation bwtween left: ")
+                return h
+                    .white("can't perform add(+) operation bwtween left: ")
                     .cyan(&format!("{}", l))
                     .white(" and right: ")
                     .cyan(&format!("{}", r))
-                    .print_and_exit();
+                    .bail();
             }
         },
         ast::Operator::Sub => match (l, r) {
@@ -211,11 +230,12 @@ fn binop(l: Value, r: Value, op: ast::Operator) -> Value {
             (Value::Int(l), Value::Float(r)) => Value::Float(l as f64 - r),
             (l, r) => {
                 let mut h = Hint::new();
-                h.white("can't perform sub(-) operation bwtween left: ")
+                return h
+                    .white("can't perform sub(-) operation bwtween left: ")
                     .cyan(&format!("{}", l))
                     .white(" and right: ")
                     .cyan(&format!("{}", r))
-                    .print_and_exit();
+                    .bail();
             }
         },
         ast::Operator::Mult => match (l, r) {
@@ -225,11 +245,12 @@ fn binop(l: Value, r: Value, op: ast::Operator) -> Value {
             (Value::Int(l), Value::Float(r)) => Value::Float(l as f64 * r),
             (l, r) => {
                 let mut h = Hint::new();
-                h.white("can't perform mult(*) operation bwtween left: ")
+                return h
+                    .white("can't perform mult(*) operation bwtween left: ")
                     .cyan(&format!("{}", l))
                     .white(" and right: ")
                     .cyan(&format!("{}", r))
-                    .print_and_exit();
+                    .bail();
             }
         },
         ast::Operator::Div => match (l, r) {
@@ -239,48 +260,100 @@ fn binop(l: Value, r: Value, op: ast::Operator) -> Value {
             (Value::Int(l), Value::Float(r)) => Value::Float(l as f64 / r),
             (l, r) => {
                 let mut h = Hint::new();
-                h.white("can't perform div(/) operation bwtween left: ")
+                return h
+                    .white("can't perform div(/) operation bwtween left: ")
                     .cyan(&format!("{}", l))
                     .white(" and right: ")
                     .cyan(&format!("{}", r))
-                    .print_and_exit();
+                    .bail();
             }
         },
         ast::Operator::Mod => match (l.clone(), r.clone()) {
             (Value::Int(l), Value::Int(r)) => Value::Int(l % r),
             (l, r) => {
                 let mut h = Hint::new();
-                h.white("can't perform mod(%) operation bwtween left: ")
+                return h
+                    .white("can't perform mod(%) operation bwtween left: ")
+                    .cyan(&format!("{}", l))
+                    .white(" and right: ")
+                    .cyan(&format!("{}", r))
+                    .bail();
+            }
+        },
+        ast::Operator::Pow => match (l, r) {
+            (Value::Int(l), Value::Int(r)) if r >= 0 => {
+                match u32::try_from(r).ok().and_then(|r| l.checked_pow(r)) {
+                    Some(v) => Value::Int(v),
+                    None => Value::Float((l as f64).powf(r as f64)),
+                }
+            }
+            (l @ (Value::Int(_) | Value::Float(_)), r @ (Value::Int(_) | Value::Float(_))) => {
+                Value::Float(l.float().unwrap().powf(r.float().unwrap()))
+            }
+            (l, r) => {
+                let mut h = Hint::new();
+                return h
+                    .white("can't perform pow(**) operation bwtween left: ")
+                    .cyan(&format!("{}", l))
+                    .white(" and right: ")
+                    .cyan(&format!("{}", r))
+                    .bail();
+            }
+        },
+        ast::Operator::FloorDiv => match (l, r) {
+            (Value::Int(_), Value::Int(0)) => {
+                let mut h = Hint::new();
+                return h
+                    .white("can't perform floordiv(//) operation: ")
+                    .red("division by zero")
+                    .bail();
+            }
+            (Value::Int(l), Value::Int(r)) => match l.checked_div(r) {
+                // round towards negative infinity like python
+                Some(v) if l % r != 0 && (l < 0) != (r < 0) => Value::Int(v - 1),
+                Some(v) => Value::Int(v),
+                None => Value::Float((l as f64 / r as f64).floor()),
+            },
+            (l @ (Value::Int(_) | Value::Float(_)), r @ (Value::Int(_) | Value::Float(_))) => {
+                Value::Float((l.float().unwrap() / r.float().unwrap()).floor())
+            }
+            (l, r) => {
+                let mut h = Hint::new();
+                return h
+                    .white("can't perform floordiv(//) operation bwtween left: ")
                     .cyan(&format!("{}", l))
                     .white(" and right: ")
                     .cyan(&format!("{}", r))
-                    .print_and_exit();
+                    .bail();
             }
         },
         ast::Operator::BitAnd => match (l.clone(), r.clone()) {
             (Value::Int(l), Value::Int(r)) => Value::Int(l & r),
             (l, r) => {
                 let mut h = Hint::new();
-                h.white("can't perform bitand(&) operation bwtween left: ")
+                return h
+                    .white("can't perform bitand(&) operation bwtween left: ")
                     .cyan(&format!("{}", l))
                     .white(" and right: ")
                     .cyan(&format!("{}", r))
-                    .print_and_exit();
+                    .bail();
             }
         },
         ast::Operator::BitOr => match (l.clone(), r.clone()) {
             (Value::Int(l), Value::Int(r)) => Value::Int(l | r),
             (l, r) => {
                 let mut h = Hint::new();
-                h.white("can't perform bitor(|) operation bwtween left: ")
+                return h
+                    .white("can't perform bitor(|) operation bwtween left: ")
                     .cyan(&format!("{}", l))
                     .white(" and right: ")
                     .cyan(&format!("{}", r))
-                    .print_and_exit();
+                    .bail();
             }
         },
         _ => unreachable!(),
-    }
+    };
+    Ok(ret)
 }
 
 fn binop_for_dataframe(left: Value, right: Value, op: ast::Operator) -> FilterxResult<Value> {
@@ -290,6 +363,8 @@ fn binop_for_dataframe(left: Value, right: Value, op: ast::Operator) -> FilterxR
         ast::Operator::Mult => left.expr()? * right.expr()?,
         ast::Operator::Div => left.expr()? / right.expr()?,
         ast::Operator::Mod => left.expr()? % right.expr()?,
+        ast::Operator::Pow => left.expr()?.pow(right.expr()?),
+        ast::Operator::FloorDiv => left.expr()?.floor_div(right.expr()?),
         ast::Operator::BitAnd => left.expr()?.and(right.expr()?),
         ast::Operator::BitOr => left.expr()?.or(right.expr()?),
         _ => unreachable!(),
@@ -301,54 +376,45 @@ fn binop_for_dataframe(left: Value, right: Value, op: ast::Operator) -> FilterxR
 impl<'a> Eval<'a> for ast::ExprBoolOp {
     type Output = Value;
     fn eval(&self, vm: &'a mut Vm) -> FilterxResult<Self::Output> {
-        execuable!(vm, "`and` or `or`");
-        let source = vm.source_mut();
-        source.enter_and_ctx();
-        let left = &self.values[0];
-        let left = eval!(
-            vm,
-            left,
-            "Only support chain compare, like a > 1 and a < 2",
-            Compare,
-            BoolOp
-        );
-        let right = &self.values[1];
-        // let right_exprs = vec![];
-        // let ops = vec![];
-        let right = eval!(
-            vm,
-            right,
-            "Only support chain compare, like a > 1 and a < 2",
-            Compare,
-            BoolOp
-        );
-        boolop_in_dataframe(vm, &left, &right, self.op.clone())
+        let mut exprs = Vec::with_capacity(self.values.len());
+        for value in &self.values {
+            exprs.push(boolean_expr(vm, value)?);
+        }
+        let e = exprs
+            .into_iter()
+            .reduce(|l, r| match self.op {
+                ast::BoolOp::And => l.and(r),
+                ast::BoolOp::Or => l.or(r),
+            })
+            .unwrap();
+        Ok(Value::named_expr(None, e))
     }
 }
 
-fn boolop_in_dataframe<'a>(
-    vm: &'a mut Vm,
-    l: &Value,
-    r: &Value,
-    op: ast::BoolOp,
-) -> FilterxResult<Value> {
-    let source = vm.source_mut();
-    let mut lazy = source.lazy();
-    match op {
-        ast::BoolOp::And => match (l, r) {
-            (_, _) => {
-                lazy = lazy.filter(l.expr()?.and(r.clone().expr()?));
-            }
-        },
-        ast::BoolOp::Or => match (l, r) {
-            (_, _) => {
-                lazy = lazy.filter(l.expr()?.or(r.expr()?));
-            }
-        },
+/// Evaluate an operand of `and`, `or`, `not` or the condition of
+/// `a if cond else b` to a boolean expression.
+fn boolean_expr(vm: &mut Vm, e: &ast::Expr) -> FilterxResult<Expr> {
+    let v = eval!(
+        vm,
+        e,
+        vm.hint
+            .white(
+                "Only support compare, `and`, `or`, `not`, column and call in boolean expression, "
+            )
+            .white("example: ")
+            .cyan("not (a > 1 or b in ('x', 'y'))"),
+        Compare,
+        BoolOp,
+        UnaryOp,
+        Name,
+        Call,
+        Attribute,
+        Constant
+    );
+    if let Some(name) = v.name() {
+        vm.source_mut().has_column(name)?;
     }
-    source.update(lazy);
-    source.exit_and_ctx();
-    Ok(Value::None)
+    v.expr()
 }
 
 /// pub enum CmpOp {
@@ -364,20 +430,11 @@ fn boolop_in_dataframe<'a>(
 ///     NotIn,
 /// }
 
-/// build lazyframe query plan
+/// build a boolean expression, rows are filtered by the statement
 impl<'a> Eval<'a> for ast::ExprCompare {
     type Output = Value;
     fn eval(&self, vm: &'a mut Vm) -> FilterxResult<Self::Output> {
-        execuable!(vm, "`>=`, `<=`, `==`, `!=`, `<`, `>`, `in`, `not in`");
-        if self.ops.len() > 1 {
-            let h = &mut vm.hint;
-            h.white("Only support one compare op, like a > 1. If you want to chain compare, use `and` or `or`")
-                .print_and_exit();
-        }
-
-        let right = &self.comparators[0];
-
-        let left = eval!(
+        let mut left = eval!(
             vm,
             self.left.deref(),
             vm.hint
@@ -388,46 +445,49 @@ impl<'a> Eval<'a> for ast::ExprCompare {
                 .cyan("a in (1, 2, 3)"),
             Constant,
             Call,
+            Attribute,
             UnaryOp,
             BinOp,
             BoolOp,
             Name
         );
-        let op = &self.ops[0];
-        let right = eval!(
-            vm,
-            right,
-            vm.hint
-                .white("In `in` compare, right must be column, or constant, ")
-                .white("example: ")
-                .cyan("'a' in a")
-                .white(", ")
-                .cyan("a in (1, 2, 3)"),
-            Constant,
-            Call,
-            UnaryOp,
-            BinOp,
-            BoolOp,
-            Name,
-            Tuple
-        );
-
-        let lname = left.name();
-        let rname = right.name();
-
-        if let Some(name) = lname {
-            vm.source_mut().has_column(name);
+        if let Some(name) = left.name() {
+            vm.source_mut().has_column(name)?;
         }
 
-        if let Some(name) = rname {
-            vm.source_mut().has_column(name);
+        // 10 < len(seq) <= 500  ->  10 < len(seq) and len(seq) <= 500
+        let mut exprs = Vec::with_capacity(self.ops.len());
+        for (op, right) in self.ops.iter().zip(self.comparators.iter()) {
+            let right = eval!(
+                vm,
+                right,
+                vm.hint
+                    .white("In `in` compare, right must be column, or constant, ")
+                    .white("example: ")
+                    .cyan("'a' in a")
+                    .white(", ")
+                    .cyan("a in (1, 2, 3)"),
+                Constant,
+                Call,
+                Attribute,
+                UnaryOp,
+                BinOp,
+                BoolOp,
+                Name,
+                Tuple
+            );
+            if let Some(name) = right.name() {
+                vm.source_mut().has_column(name)?;
+            }
+            exprs.push(compare_in(vm, left, right.clone(), op)?);
+            left = right;
         }
-
-        compare_in(vm, left, right, op)
+        let e = exprs.into_iter().reduce(|l, r| l.and(r)).unwrap();
+        Ok(Value::named_expr(None, e))
     }
 }
 
-fn compare_in<'a>(vm: &'a mut Vm, left: Value, right: Value, op: &CmpOp) -> FilterxResult<Value> {
+fn compare_in(vm: &mut Vm, left: Value, right: Value, op: &CmpOp) -> FilterxResult<Expr> {
     match op {
         CmpOp::In | CmpOp::NotIn => {
             if left.is_str() && right.is_column() {
@@ -445,33 +505,34 @@ fn compare_in<'a>(vm: &'a mut Vm, left: Value, right: Value, op: &CmpOp) -> Filt
                 .white(" or ")
                 .white("string in column: ")
                 .cyan("'a' in a")
-                .print_and_exit();
+                .bail()
         }
         CmpOp::Eq | CmpOp::NotEq | CmpOp::Lt | CmpOp::LtE | CmpOp::Gt | CmpOp::GtE => {
             let l = left.is_const() | left.is_keyword();
             let r = right.is_const() | right.is_keyword();
             if l && r {
                 let h = &mut vm.hint;
-                h.white("Only support compare between column and constant")
-                    .print_and_exit();
+                return h
+                    .white("Only support compare between column and constant")
+                    .bail();
             }
             return compare_cond_expr_in_dataframe(vm, left, right, op);
         }
         _ => {
             let h = &mut vm.hint;
             h.white("Only support compare op : ==, !=, >, >=, <, <=")
-                .print_and_exit();
+                .bail()
         }
-    };
+    }
 }
 
-fn col_in_list<'a>(vm: &'a mut Vm, left: Value, right: Value, op: &CmpOp) -> FilterxResult<Value> {
+fn col_in_list(vm: &mut Vm, left: Value, right: Value, op: &CmpOp) -> FilterxResult<Expr> {
     let left_col: &str = left.column().unwrap();
-    vm.source().has_column(left_col);
+    vm.source().has_column(left_col)?;
     let right_list: Vec<Value> = right.list().unwrap();
     if right_list.is_empty() {
         let h = &mut vm.hint;
-        h.white("List can't be empty").print_and_exit();
+        return h.white("List can't be empty").bail();
     }
     let columns = vm.source().columns().unwrap();
     let left_col_type = columns
@@ -494,8 +555,9 @@ fn col_in_list<'a>(vm: &'a mut Vm, left: Value, right: Value, op: &CmpOp) -> Fil
             for v in &right_list {
                 if v.is_err() {
                     let h = &mut vm.hint;
-                    h.white("List must be int type, because left col's type is int.")
-                        .print_and_exit();
+                    return h
+                        .white("List must be int type, because left col's type is int.")
+                        .bail();
                 }
             }
             let mut right_values = Vec::with_capacity(right_list.len());
@@ -510,8 +572,9 @@ fn col_in_list<'a>(vm: &'a mut Vm, left: Value, right: Value, op: &CmpOp) -> Fil
             for v in &right_list {
                 if v.is_err() {
                     let h = &mut vm.hint;
-                    h.white("List must be float type, because left col's type is float.")
-                        .print_and_exit();
+                    return h
+                        .white("List must be float type, because left col's type is float.")
+                        .bail();
                 }
             }
             let mut right_values = Vec::with_capacity(right_list.len());
@@ -526,8 +589,9 @@ fn col_in_list<'a>(vm: &'a mut Vm, left: Value, right: Value, op: &CmpOp) -> Fil
             for v in &right_list {
                 if v.is_err() {
                     let h = &mut vm.hint;
-                    h.white("List must be string type, because left col's type is string.")
-                        .print_and_exit();
+                    return h
+                        .white("List must be string type, because left col's type is string.")
+                        .bail();
                 }
             }
             let mut right_values = Vec::with_capacity(right_list.len());
@@ -539,8 +603,7 @@ fn col_in_list<'a>(vm: &'a mut Vm, left: Value, right: Value, op: &CmpOp) -> Fil
         }
         _ => {
             let h = &mut vm.hint;
-            h.white("Only support int, float, string type")
-                .print_and_exit();
+            return h.white("Only support int, float, string type").bail();
         }
     }
     let left_expr = left.expr()?;
@@ -551,34 +614,31 @@ fn col_in_list<'a>(vm: &'a mut Vm, left: Value, right: Value, op: &CmpOp) -> Fil
             unreachable!();
         }
     };
-    vm.source_mut().filter(e);
-    Ok(Value::None)
+    Ok(e)
 }
 
-fn str_in_col<'a>(vm: &'a mut Vm, left: Value, right: Value, op: &CmpOp) -> FilterxResult<Value> {
+fn str_in_col(vm: &mut Vm, left: Value, right: Value, op: &CmpOp) -> FilterxResult<Expr> {
     let left_str = left.string().unwrap();
     let right_col: &str = right.column().unwrap();
-    vm.source().has_column(right_col);
+    vm.source().has_column(right_col)?;
     let e = col(right_col).str().contains(left_str.lit(), true);
     let e = match op {
         CmpOp::In => e.eq(true.lit()),
         CmpOp::NotIn => e.eq(false.lit()),
         _ => {
             let h = &mut vm.hint;
-            h.white("Only support in/not in for string")
-                .print_and_exit();
+            return h.white("Only support in/not in for string").bail();
         }
     };
-    vm.source_mut().filter(e.clone());
-    Ok(Value::named_expr(None, e))
+    Ok(e)
 }
 
-fn compare_cond_expr_in_dataframe<'a>(
-    vm: &'a mut Vm,
+fn compare_cond_expr_in_dataframe(
+    vm: &mut Vm,
     left: Value,
     right: Value,
     op: &CmpOp,
-) -> FilterxResult<Value> {
+) -> FilterxResult<Expr> {
     let left_expr = left.expr()?;
     let right_expr = right.expr()?;
     let e = match op {
@@ -598,10 +658,155 @@ fn compare_cond_expr_in_dataframe<'a>(
         CmpOp::GtE => left_expr.gt_eq(right_expr),
         _ => {
             let h = &mut vm.hint;
-            h.white("Only support compare op : ==, !=, >, >=, <, <=")
-                .print_and_exit();
+            return h
+                .white("Only support compare op : ==, !=, >, >=, <, <=")
+                .bail();
         }
     };
-    vm.source_mut().filter(e.clone());
-    Ok(Value::named_expr(None, e))
+    Ok(e)
+}
+
+/// a if cond else b
+impl<'a> Eval<'a> for ast::ExprIfExp {
+    type Output = Value;
+    fn eval(&self, vm: &'a mut Vm) -> FilterxResult<Self::Output> {
+        let cond = boolean_expr(vm, self.test.deref())?;
+        let body = eval!(
+            vm,
+            self.body.deref(),
+            "Only support constant, column, call, unaryop, binop, comparison and nested `if else` as value of condition expression, like 'long' if len(seq) > 100 else 'short'",
+            Constant,
+            Name,
+            Call,
+            Attribute,
+            UnaryOp,
+            BinOp,
+            Compare,
+            BoolOp,
+            IfExp
+        );
+        let orelse = eval!(
+            vm,
+            self.orelse.deref(),
+            "Only support constant, column, call, unaryop, binop, comparison and nested `if else` as value of condition expression, like 'long' if len(seq) > 100 else 'short'",
+            Constant,
+            Name,
+            Call,
+            Attribute,
+            UnaryOp,
+            BinOp,
+            Compare,
+            BoolOp,
+            IfExp
+        );
+        for v in [&body, &orelse] {
+            if let Some(name) = v.name() {
+                vm.source_mut().has_column(name)?;
+            }
+        }
+        let e = when(cond).then(body.expr()?).otherwise(orelse.expr()?);
+        Ok(Value::named_expr(None, e))
+    }
+}
+
+#[cfg(test)]
+fn test_run(script: &str) -> FilterxResult<DataFrame> {
+    let df = df!("a" => [1, 2, 3, 4], "b" => ["x", "yy", "zzz", "w"]).unwrap();
+    crate::vm::run_df(df, script)
+}
+
+#[test]
+fn test_if_exp() {
+    let strs = |df: DataFrame| -> Vec<Option<String>> {
+        let c = df.column("c").unwrap().str().unwrap().clone();
+        c.into_iter().map(|s| s.map(String::from)).collect()
+    };
+    let ints = |df: DataFrame| -> Vec<Option<i32>> {
+        df.column("c").unwrap().i32().unwrap().into_iter().collect()
+    };
+    let s = |v: &[&str]| -> Vec<Option<String>> { v.iter().map(|s| Some(s.to_string())).collect() };
+
+    let df = test_run("alias(c) = 'big' if a > 2 else 'small'").unwrap();
+    assert_eq!(strs(df), s(&["small", "small", "big", "big"]));
+    let df = test_run("alias(c) = 'big' if a > 3 else ('mid' if a > 1 else 'small')").unwrap();
+    assert_eq!(strs(df), s(&["small", "mid", "mid", "big"]));
+    let df = test_run("alias(c) = a * 10 if b == 'yy' else a").unwrap();
+    assert_eq!(ints(df), vec![Some(1), Some(20), Some(3), Some(4)]);
+    let df = test_run("alias(c) = 1 if len(b) > 1 and a != 4 else 0").unwrap();
+    assert_eq!(ints(df), vec![Some(0), Some(1), Some(1), Some(0)]);
+    // a condition expression creates a column, rows are filtered by the next statement
+    let df = test_run("alias(c) = 'big' if a > 2 else 'small'; c == 'big'").unwrap();
+    assert_eq!(strs(df), s(&["big", "big"]));
+
+    assert!(test_run("alias(c) = d if a > 2 else 0").is_err());
+    assert!(test_run("alias(c) = 1 if d > 2 else 0").is_err());
+
+    // a comparison as a value
+    let df = test_run("alias(c) = (a > 1) if b != 'x' else False").unwrap();
+    let c: Vec<Option<bool>> = df
+        .column("c")
+        .unwrap()
+        .bool()
+        .unwrap()
+        .into_iter()
+        .collect();
+    assert_eq!(c, vec![Some(false), Some(true), Some(true), Some(true)]);
+    let df = test_run("alias(c) = not (a > 3) if a > 1 else (a > 0 and b == 'x')").unwrap();
+    let c: Vec<Option<bool>> = df
+        .column("c")
+        .unwrap()
+        .bool()
+        .unwrap()
+        .into_iter()
+        .collect();
+    assert_eq!(c, vec![Some(true), Some(true), Some(true), Some(false)]);
+}
+
+#[test]
+fn test_floor_div() {
+    let floor_div = |l: i64, r: i64| binop(Value::Int(l), Value::Int(r), ast::Operator::FloorDiv);
+    let int = |l: i64, r: i64| match floor_div(l, r).unwrap() {
+        Value::Int(v) => v,
+        v => panic!("expected int, got {}", v),
+    };
+    assert_eq!(int(7, 2), 3);
+    assert_eq!(int(-7, 2), -4);
+    assert_eq!(int(7, -2), -4);
+    assert_eq!(int(-7, -2), 3);
+    assert_eq!(int(-8, 2), -4);
+    assert_eq!(int(9007199254740993, 1), 9007199254740993);
+    assert!(floor_div(7, 0).is_err());
+    assert!(matches!(floor_div(i64::MIN, -1), Ok(Value::Float(_))));
+}
+
+#[test]
+fn test_boolean() {
+    let a = |script: &str| -> Vec<Option<i32>> {
+        let df = test_run(script).unwrap();
+        df.column("a").unwrap().i32().unwrap().into_iter().collect()
+    };
+    let rows = |v: &[i32]| -> Vec<Option<i32>> { v.iter().map(|x| Some(*x)).collect() };
+
+    assert_eq!(a("a > 1 and b != 'zzz'"), rows(&[2, 4]));
+    assert_eq!(a("not (a > 2 or b == 'x')"), rows(&[2]));
+    assert_eq!(a("a == 1 or a == 4 and b == 'w'"), rows(&[1, 4]));
+    assert_eq!(a("1 < a <= 3"), rows(&[2, 3]));
+    assert_eq!(a("a in (1, 3)"), rows(&[1, 3]));
+    assert_eq!(a("a not in (1, 3)"), rows(&[2, 4]));
+    assert_eq!(a("'^z' in b"), rows(&[3]));
+    // every statement filters rows
+    assert_eq!(a("a > 1; a < 4"), rows(&[2, 3]));
+
+    // a comparison used as a value creates a boolean column
+    let df = test_run("alias(c) = a > 2 and b != 'w'").unwrap();
+    let c: Vec<Option<bool>> = df
+        .column("c")
+        .unwrap()
+        .bool()
+        .unwrap()
+        .into_iter()
+        .collect();
+    assert_eq!(c, vec![Some(false), Some(false), Some(true), Some(false)]);
+
+    assert!(test_run("a > 1 and d < 2").is_err());
 }
@@ -0,0 +1,139 @@
+use polars::prelude::*;
+
+use filterx_core::FilterxResult;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AggKind {
+    Count,
+    Sum,
+    Mean,
+    Min,
+    Max,
+    NUnique,
+}
+
+impl AggKind {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "count" => Some(AggKind::Count),
+            "sum" => Some(AggKind::Sum),
+            "mean" => Some(AggKind::Mean),
+            "min" => Some(AggKind::Min),
+            "max" => Some(AggKind::Max),
+            "n_unique" => Some(AggKind::NUnique),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Agg {
+    pub name: String,
+    pub kind: AggKind,
+    /// `None` means counting rows, e.g. `count()`
+    pub input: Option<Expr>,
+}
+
+/// State of `group_by`.
+///
+/// Every chunk is reduced to a partial aggregation which is stacked into
+/// `partial`, the final table is computed from these partial results. So
+/// fasta/fastq sources get correct results across chunks.
+#[derive(Debug, Clone)]
+pub struct GroupBy {
+    pub keys: Vec<String>,
+    pub aggs: Vec<Agg>,
+    pub partial: Option<DataFrame>,
+    /// set by `group_by`, statements before it in a chunk are evaluated as usual
+    pub active: bool,
+    pub in_agg: bool,
+}
+
+fn partial_name(name: &str, suffix: &str) -> String {
+    format!("__filterx_{}_{}__", name, suffix)
+}
+
+impl GroupBy {
+    pub fn new(keys: Vec<String>) -> Self {
+        Self {
+            keys,
+            aggs: vec![],
+            partial: None,
+            active: true,
+            in_agg: false,
+        }
+    }
+
+    fn key_exprs(&self) -> Vec<Expr> {
+        self.keys.iter().map(|k| col(k.as_str())).collect()
+    }
+
+    fn partial_exprs(&self) -> Vec<Expr> {
+        let mut exprs = Vec::with_capacity(self.aggs.len());
+        for agg in &self.aggs {
+            let name = agg.name.as_str();
+            match (agg.kind, agg.input.clone()) {
+                (AggKind::Count, None) => exprs.push(len().alias(name)),
+                (AggKind::Count, Some(e)) => exprs.push(e.count().alias(name)),
+                (AggKind::Sum, Some(e)) => exprs.push(e.sum().alias(name)),
+                (AggKind::Min, Some(e)) => exprs.push(e.min().alias(name)),
+                (AggKind::Max, Some(e)) => exprs.push(e.max().alias(name)),
+                (AggKind::Mean, Some(e)) => {
+                    exprs.push(e.clone().sum().alias(partial_name(name, "sum")));
+                    exprs.push(e.count().alias(partial_name(name, "count")));
+                }
+                (AggKind::NUnique, Some(e)) => exprs.push(e.unique().alias(name)),
+                (_, None) => unreachable!(),
+            }
+        }
+        exprs
+    }
+
+    fn final_exprs(&self) -> Vec<Expr> {
+        let mut exprs = Vec::with_capacity(self.aggs.len());
+        for agg in &self.aggs {
+            let name = agg.name.as_str();
+            let e = match agg.kind {
+                AggKind::Count | AggKind::Sum => col(name).sum(),
+                AggKind::Min => col(name).min(),
+                AggKind::Max => col(name).max(),
+                AggKind::Mean => {
+                    col(partial_name(name, "sum")).sum().cast(DataType::Float64)
+                        / col(partial_name(name, "count")).sum()
+                }
+                AggKind::NUnique => col(name).explode().n_unique(),
+            };
+            exprs.push(e.alias(name));
+        }
+        exprs
+    }
+
+    /// Reduce a chunk and stack it on the previous partial results.
+    pub fn collect(&mut self, lazy: LazyFrame) -> FilterxResult<()> {
+        let partial = lazy
+            .group_by_stable(self.key_exprs())
+            .agg(self.partial_exprs())
+            .collect()?;
+        self.partial = match self.partial.take() {
+            Some(mut df) => {
+                df.vstack_mut(&partial)?;
+                Some(df)
+            }
+            None => Some(partial),
+        };
+        Ok(())
+    }
+
+    pub fn finish(&mut self) -> FilterxResult<Option<DataFrame>> {
+        let partial = match self.partial.take() {
+            Some(df) => df,
+            None => return Ok(None),
+        };
+        let df = partial
+            .lazy()
+            .group_by_stable(self.key_exprs())
+            .agg(self.final_exprs())
+            .collect()?;
+        Ok(Some(df))
+    }
+}
@@ -1,5 +1,6 @@
 pub mod ast;
 pub mod engine_macro;
 pub mod eval;
+pub mod group;
 pub mod util;
 pub mod vm;
@@ -1,14 +1,28 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 
 use polars::{prelude::*, sql::SQLContext};
 
-use filterx_core::{writer::FilterxWriter, FilterxError, FilterxResult, Hint};
+use filterx_core::{
+    hint::Colorize, value::Value, writer::FilterxWriter, FilterxError, FilterxResult, Hint,
+};
 use filterx_source::{
     source::SourceType, DataframeSource, FastaRecordType, FastaSource, FastqSource, QualityType,
     Source, SourceInner,
 };
 
+use super::ast;
 use super::eval::Eval;
+use super::group::GroupBy;
+use rustpython_parser::ast::Ranged;
+
+/// 1-based line and 0-based column of a byte offset.
+fn line_column(source: &str, offset: usize) -> (usize, usize) {
+    let before = &source[..offset.min(source.len())];
+    let line = before.matches('\n').count() + 1;
+    let column = before.len() - before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+    (line, column)
+}
 
 #[derive(Debug, PartialEq)]
 pub enum VmMode {
@@ -25,6 +39,8 @@ pub struct VmStatus {
     pub printed: bool,
     pub consume_rows: usize,
     pub chunk_size: usize,
+    /// columns returned by `header()`, the statements after it are skipped
+    pub schema: Option<Value>,
 }
 
 impl VmStatus {
@@ -37,6 +53,7 @@ impl VmStatus {
             printed: false,
             consume_rows: 0,
             chunk_size: 10000,
+            schema: None,
         }
     }
 }
@@ -70,6 +87,11 @@ pub struct Vm {
     pub expr_cache: HashMap<String, (String, Vec<polars::prelude::Expr>)>,
     pub hint: Hint,
     pub sql_ctx: SQLContext,
+    pub group: Option<GroupBy>,
+    /// scalar variables, from `--define` or `name = value` in expression
+    pub variables: HashMap<String, Value>,
+    /// k-mer sets of reference files, loaded once for all chunks
+    pub kmer_sets: HashMap<String, Arc<HashSet<u64>>>,
 }
 
 impl Vm {
@@ -97,6 +119,9 @@ impl Vm {
             expr_cache: HashMap::new(),
             hint: Hint::new(),
             sql_ctx: SQLContext::new(),
+            group: None,
+            variables: HashMap::new(),
+            kmer_sets: HashMap::new(),
         };
         vm
     }
@@ -113,6 +138,66 @@ impl Vm {
             expr_cache: HashMap::new(),
             hint: Hint::new(),
             sql_ctx: SQLContext::new(),
+            group: None,
+            variables: HashMap::new(),
+            kmer_sets: HashMap::new(),
+        }
+    }
+
+    pub fn set_variables(&mut self, variables: Vec<(String, Value)>) {
+        self.variables.extend(variables);
+    }
+
+    /// Run `script` over the whole `source` and collect the rows left.
+    /// Column names should be set on the source before, `print` writes to stdout.
+    pub fn run(source: Source, script: &str) -> FilterxResult<DataFrame> {
+        let writer = FilterxWriter::new(None, None, None)?;
+        let mut vm = Vm::from_source(source, writer);
+        let mut ret: Option<DataFrame> = None;
+        while let Some(df) = vm.next_df(script)? {
+            match ret.as_mut() {
+                Some(ret) => {
+                    ret.vstack_mut(&df)?;
+                }
+                None => ret = Some(df),
+            }
+        }
+        if let Some(df) = vm.finish_group()? {
+            return Ok(df);
+        }
+        Ok(ret.unwrap_or_default())
+    }
+
+    /// Evaluate `script` on the next chunk and return the rows left, `None`
+    /// when the source is exhausted or the row limit is reached.
+    pub fn next_df(&mut self, script: &str) -> FilterxResult<Option<DataFrame>> {
+        let chunked = matches!(
+            self.source_type(),
+            SourceType::Fasta | SourceType::Fastq | SourceType::GenBank
+        );
+        loop {
+            if self.status.consume_rows >= self.status.limit_rows {
+                return Ok(None);
+            }
+            // sources which are not read in chunks are evaluated only once
+            if !chunked && self.status.stop {
+                return Ok(None);
+            }
+            if self.next_batch()?.is_none() {
+                return Ok(None);
+            }
+            self.eval_once(script, None)?;
+            if !chunked {
+                self.status.stop = true;
+            }
+            if self.status.printed {
+                continue;
+            }
+            let need = self.status.limit_rows - self.status.consume_rows;
+            let df = self.into_df()?;
+            let df = df.head(Some(need.min(df.height())));
+            self.status.consume_rows += df.height();
+            return Ok(Some(df));
         }
     }
 
@@ -160,20 +245,22 @@ impl Vm {
                 match err {
                     FilterxError::ParseError(e) => {
                         let pos = e.offset;
-                        h.white("expr: ")
+                        return h
+                            .white("expr: ")
                             .cyan(expr)
                             .white(" gets a parse error ")
                             .next_line()
                             .white(&(" ".repeat(pos.to_usize() + 5)))
                             .red(&format!("^{}", e.error.to_string()))
-                            .print_and_exit();
+                            .bail();
                     }
                     _ => {
-                        h.white("expr: ")
+                        return h
+                            .white("expr: ")
                             .cyan(expr)
                             .white(" gets a parse error ")
                             .red(&format!("{}", err))
-                            .print_and_exit();
+                            .bail();
                     }
                 }
             }
@@ -181,47 +268,125 @@ impl Vm {
         Ok(true)
     }
 
+    /// Parse the whole program as a python module, statements are separated
+    /// by new lines or `;`.
+    pub fn parse_program(&mut self, source: &str) -> FilterxResult<Vec<ast::Stmt>> {
+        if let Some(rustpython_parser::ast::Mod::Module(m)) = self.parse_cache.get(source) {
+            return Ok(m.body.clone());
+        }
+        let program = match rustpython_parser::parse(source, rustpython_parser::Mode::Module, "") {
+            Ok(program) => program,
+            Err(e) => {
+                let offset = e.offset.to_usize();
+                let (line, column) = line_column(source, offset);
+                let text = source.lines().nth(line - 1).unwrap_or("");
+                let h = &mut self.hint;
+                return h
+                    .clear()
+                    .white(&format!("line {}: ", line))
+                    .cyan(text)
+                    .white(" gets a parse error")
+                    .next_line()
+                    .white(&" ".repeat(column + format!("line {}: ", line).len()))
+                    .red(&format!("^ {}", e.error))
+                    .span(offset, offset)
+                    .bail();
+            }
+        };
+        self.parse_cache.insert(source.to_string(), program.clone());
+        match program {
+            rustpython_parser::ast::Mod::Module(m) => Ok(m.body),
+            _ => unreachable!(),
+        }
+    }
+
     pub fn eval_once(&mut self, expr: &str, sql: Option<String>) -> FilterxResult<()> {
-        // split the expr by ;
-        if expr.is_empty() {
+        if expr.trim().is_empty() {
             self.sql(sql)?;
             return Ok(());
         }
-        // check ast process
-        let exprs: Vec<&str> = expr.split(";").collect();
+        let program = self.parse_program(expr)?;
+        if let Some(group) = self.group.as_mut() {
+            group.active = false;
+        }
 
-        for expr in exprs {
-            if expr.is_empty() {
-                continue;
-            }
-            self.eval_expr = expr.to_string();
-            let eval_expr;
-            if self.parse_cache.contains_key(expr) {
-                eval_expr = self.parse_cache.get(expr).unwrap().clone();
-            } else {
-                eval_expr = self.ast(expr)?;
-                self.parse_cache.insert(expr.to_string(), eval_expr.clone());
-            }
+        // report line numbers when there are several lines
+        let multi_line = expr.trim().contains('\n');
+        for stmt in program {
+            let range = stmt.range();
+            let (line, _) = line_column(expr, range.start().to_usize());
             self.eval_expr.clear();
-            self.eval_expr.push_str(expr);
-            if eval_expr.is_expression() {
-                let expr = eval_expr.as_expression().unwrap();
-                expr.eval(self)?;
-            } else if eval_expr.is_interactive() {
-                let expr = eval_expr.as_interactive().unwrap();
-                expr.eval(self)?;
-            } else {
-                return Err(FilterxError::RuntimeError("Parse Error".to_string()));
+            self.eval_expr
+                .push_str(&expr[range.start().to_usize()..range.end().to_usize()]);
+            self.hint.clear();
+            match stmt.eval(self) {
+                Ok(_) if self.status.schema.is_some() => return Ok(()),
+                Ok(_) => {}
+                Err(FilterxError::HintError(mut h)) => {
+                    if multi_line {
+                        h.msg.insert(0, format!("line {}: ", line).white());
+                    }
+                    h.span(range.start().to_usize(), range.end().to_usize());
+                    return Err(FilterxError::HintError(h));
+                }
+                Err(e) if multi_line => {
+                    return Err(FilterxError::RuntimeError(format!("line {}: {}", line, e)));
+                }
+                Err(e) => return Err(e),
             }
         }
+        self.hint.clear();
+        if self.group.is_some() {
+            self.collect_group()?;
+        }
         self.sql(sql)?;
         Ok(())
     }
 
+    fn collect_group(&mut self) -> FilterxResult<()> {
+        let lazy = self.source_mut().lazy();
+        let group = self.group.as_mut().unwrap();
+        if group.aggs.is_empty() {
+            let h = &mut self.hint;
+            return h
+                .white("group_by: no aggregation found. Use ")
+                .green("alias(n) = count()")
+                .white(" to add one.")
+                .bail();
+        }
+        group.collect(lazy)?;
+        match self.source_type() {
+            SourceType::Fasta | SourceType::Fastq | SourceType::GenBank => {
+                // the summary table is written by `finish_group` after the last chunk
+                self.status.printed = true;
+            }
+            _ => {
+                let df = self.finish_group()?.unwrap();
+                let names = df
+                    .get_column_names()
+                    .iter()
+                    .map(|x| x.to_string())
+                    .collect();
+                let source = self.source_mut();
+                source.set_init_column_names(&names);
+                source.update(df.lazy());
+            }
+        }
+        Ok(())
+    }
+
+    /// Compute the `group_by` summary table, `None` if `group_by` is not used.
+    pub fn finish_group(&mut self) -> FilterxResult<Option<DataFrame>> {
+        match self.group.take() {
+            Some(mut group) => group.finish(),
+            None => Ok(None),
+        }
+    }
+
     pub fn next_batch(&mut self) -> FilterxResult<Option<()>> {
         self.status.printed = false;
         match self.source_type() {
-            SourceType::Fasta | SourceType::Fastq => {
+            SourceType::Fasta | SourceType::Fastq | SourceType::GenBank => {
                 if self.status.stop {
                     return Ok(None);
                 }
@@ -234,12 +399,28 @@ impl Vm {
                             if count < fetch || count == 0 {
                                 self.status.stop = true;
                             }
+                            // the dataframe of last chunk is not updated
+                            if count == 0 {
+                                return Ok(None);
+                            }
                         }
                         SourceInner::Fastq(ref mut fastq) => {
                             let count = fastq.into_dataframe(fetch)?;
                             if count < fetch || count == 0 {
                                 self.status.stop = true;
                             }
+                            if count == 0 {
+                                return Ok(None);
+                            }
+                        }
+                        SourceInner::GenBank(ref mut genbank) => {
+                            let count = genbank.into_dataframe(fetch)?;
+                            if count < fetch || count == 0 {
+                                self.status.stop = true;
+                            }
+                            if count == 0 {
+                                return Ok(None);
+                            }
                         }
                         _ => {
                             unreachable!();
@@ -282,3 +463,60 @@ impl Vm {
         Ok(())
     }
 }
+
+/// Run a script on `df` as a csv source, the columns of `df` are the initial columns.
+#[cfg(test)]
+pub(crate) fn run_df(df: DataFrame, script: &str) -> FilterxResult<DataFrame> {
+    let names = df
+        .get_column_names()
+        .iter()
+        .map(|x| x.to_string())
+        .collect();
+    let mut source = DataframeSource::new(df.lazy());
+    source.set_init_column_names(&names);
+    Vm::run(Source::new(source.into(), SourceType::Csv), script)
+}
+
+#[test]
+fn test_run() {
+    let df = df!("a" => [1, 2, 3], "b" => ["x", "y", "z"]).unwrap();
+    assert_eq!(run_df(df.clone(), "a > 1").unwrap().height(), 2);
+
+    let err = run_df(df.clone(), "c > 1").unwrap_err();
+    match err {
+        FilterxError::HintError(h) => assert!(h.message().contains("Column c not found")),
+        _ => panic!("expected a hint error"),
+    }
+    // an invalid pattern is an error, not a panic
+    for script in ["select('x[')", "rename('x[', 'y')"] {
+        assert!(run_df(df.clone(), script).is_err());
+    }
+}
+
+#[test]
+fn test_script() {
+    let run = |script: &str| {
+        let df = df!("a" => [1, 2, 3, 4], "b" => ["x", "y", "z", "w"]).unwrap();
+        run_df(df, script)
+    };
+    let script = "# keep a > 1
+a > 1
+
+alias(c) = ('big'
+            if a > 2 else 'small')
+b != 'z' and \\
+    c == 'big'; a < 10  # trailing comment
+";
+    let df = run(script).unwrap();
+    let b: Vec<Option<&str>> = df.column("b").unwrap().str().unwrap().into_iter().collect();
+    assert_eq!(b, vec![Some("w")]);
+
+    // errors report the line of the statement
+    let err = run("a > 1\n\nd > 2").unwrap_err();
+    assert!(err.to_string().contains("line 3"));
+    let err = run("a > 1\nalias(c) = (a +").unwrap_err();
+    match err {
+        FilterxError::HintError(h) => assert!(h.message().starts_with("line 2: ")),
+        _ => panic!("expected a hint error"),
+    }
+}
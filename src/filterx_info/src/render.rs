@@ -10,11 +10,15 @@ pub fn render_markdown_help(help: &str) {
     println!();
 }
 
-pub fn render_alias_function(alias_names: &[&'static str]) {
+pub fn render_signature(signature: &str) {
+    println!("\nUsage: {}", signature.cyan());
+}
+
+pub fn render_alias_function(alias_names: &[&str]) {
     if alias_names.len() == 1 {
         return;
     }
-    print!("\n\nOther alias: ");
+    print!("\nOther alias: ");
     for name in alias_names.iter().skip(1) {
         print!("{} ", name.green())
     }
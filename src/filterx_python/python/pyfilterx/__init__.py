@@ -0,0 +1,3 @@
+from pyfilterx._pyfilterx import FilterxError, filter_df, filter_fasta, filter_fastq
+
+__all__ = ["FilterxError", "filter_df", "filter_fasta", "filter_fastq"]
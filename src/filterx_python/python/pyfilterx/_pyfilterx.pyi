@@ -0,0 +1,28 @@
+from typing import Iterator
+
+import polars as pl
+
+class FilterxError(Exception): ...
+
+class FastxIter(Iterator[pl.DataFrame]):
+    def __iter__(self) -> "FastxIter": ...
+    def __next__(self) -> pl.DataFrame: ...
+
+def filter_fastq(
+    path: str,
+    expr: str,
+    chunk_size: int = 4096,
+    comment: bool = True,
+    quality: bool = True,
+    phred: str = "auto",
+    detect_size: int = 100,
+) -> FastxIter: ...
+def filter_fasta(
+    path: str,
+    expr: str,
+    chunk_size: int = 4096,
+    comment: bool = True,
+    record_type: str = "auto",
+    detect_size: int = 3,
+) -> FastxIter: ...
+def filter_df(df: pl.DataFrame, expr: str) -> pl.DataFrame: ...
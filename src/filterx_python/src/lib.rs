@@ -0,0 +1,143 @@
+//! Python bindings of filterx. The `Vm` runs in process, polars DataFrames
+//! cross the boundary through Arrow without copying.
+
+use clap::ValueEnum;
+use filterx_core::writer::FilterxWriter;
+use filterx_engine::vm::Vm;
+use filterx_source::{
+    DataframeSource, FastaRecordType, FastaSource, FastqSource, QualityType, Source, SourceType,
+};
+use polars::prelude::*;
+use pyo3::create_exception;
+use pyo3::exceptions::PyException;
+use pyo3::prelude::*;
+use pyo3_polars::PyDataFrame;
+
+create_exception!(_pyfilterx, FilterxError, PyException);
+
+fn to_py_err(e: filterx_core::FilterxError) -> PyErr {
+    let msg = match e {
+        // the hint is colored for terminal, use the plain message
+        filterx_core::FilterxError::HintError(h) => match &h.suggestion {
+            Some(suggestion) => format!("{} Did you mean `{}`?", h.message(), suggestion),
+            None => h.message(),
+        },
+        e => e.to_string(),
+    };
+    FilterxError::new_err(msg)
+}
+
+fn value_enum<T: ValueEnum>(name: &str, value: &str) -> PyResult<T> {
+    T::from_str(value, true)
+        .map_err(|_| FilterxError::new_err(format!("invalid {}: {}", name, value)))
+}
+
+/// Records left after the expression, one polars DataFrame per chunk.
+#[pyclass(unsendable)]
+struct FastxIter {
+    vm: Vm,
+    expr: String,
+    done: bool,
+}
+
+#[pymethods]
+impl FastxIter {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&mut self) -> PyResult<Option<PyDataFrame>> {
+        if self.done {
+            return Ok(None);
+        }
+        if let Some(df) = self.vm.next_df(&self.expr).map_err(to_py_err)? {
+            return Ok(Some(PyDataFrame(df)));
+        }
+        self.done = true;
+        // the summary table of `group_by` comes after the last chunk
+        let group = self.vm.finish_group().map_err(to_py_err)?;
+        Ok(group.map(PyDataFrame))
+    }
+}
+
+impl FastxIter {
+    fn new(source: Source, names: &[&str], expr: &str, chunk_size: usize) -> PyResult<Self> {
+        let writer = FilterxWriter::new(None, None, None).map_err(to_py_err)?;
+        let mut vm = Vm::from_source(source, writer);
+        let names = names.iter().map(|x| x.to_string()).collect();
+        vm.source_mut().set_init_column_names(&names);
+        vm.status.set_chunk_size(chunk_size);
+        Ok(FastxIter {
+            vm,
+            expr: expr.to_string(),
+            done: false,
+        })
+    }
+}
+
+/// Filter a fastq file, yields polars DataFrames with columns name, comm, seq, qual.
+#[pyfunction]
+#[pyo3(signature = (path, expr, chunk_size = 4096, comment = true, quality = true, phred = "auto", detect_size = 100))]
+fn filter_fastq(
+    path: &str,
+    expr: &str,
+    chunk_size: usize,
+    comment: bool,
+    quality: bool,
+    phred: &str,
+    detect_size: usize,
+) -> PyResult<FastxIter> {
+    let phred: QualityType = value_enum("phred", phred)?;
+    let source = FastqSource::new(path, comment, quality, phred, detect_size).map_err(to_py_err)?;
+    let mut names = vec!["name", "comm", "seq", "qual"];
+    names.retain(|x| (comment || *x != "comm") && (quality || *x != "qual"));
+    let source = Source::new(source.into(), SourceType::Fastq);
+    FastxIter::new(source, &names, expr, chunk_size)
+}
+
+/// Filter a fasta file, yields polars DataFrames with columns name, comm, seq.
+#[pyfunction]
+#[pyo3(signature = (path, expr, chunk_size = 4096, comment = true, record_type = "auto", detect_size = 3))]
+fn filter_fasta(
+    path: &str,
+    expr: &str,
+    chunk_size: usize,
+    comment: bool,
+    record_type: &str,
+    detect_size: usize,
+) -> PyResult<FastxIter> {
+    let record_type: FastaRecordType = value_enum("record_type", record_type)?;
+    let source = FastaSource::new(path, comment, record_type, detect_size).map_err(to_py_err)?;
+    let names = match comment {
+        true => vec!["name", "comm", "seq"],
+        false => vec!["name", "seq"],
+    };
+    let source = Source::new(source.into(), SourceType::Fasta);
+    FastxIter::new(source, &names, expr, chunk_size)
+}
+
+/// Filter a polars DataFrame and return a new one.
+#[pyfunction]
+fn filter_df(df: PyDataFrame, expr: &str) -> PyResult<PyDataFrame> {
+    let df: DataFrame = df.into();
+    let names = df
+        .get_column_names()
+        .iter()
+        .map(|x| x.to_string())
+        .collect();
+    let mut source = DataframeSource::new(df.lazy());
+    source.set_init_column_names(&names);
+    let source = Source::new(source.into(), SourceType::Csv);
+    let df = Vm::run(source, expr).map_err(to_py_err)?;
+    Ok(PyDataFrame(df))
+}
+
+#[pymodule]
+fn _pyfilterx(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add("FilterxError", m.py().get_type::<FilterxError>())?;
+    m.add_class::<FastxIter>()?;
+    m.add_function(wrap_pyfunction!(filter_fastq, m)?)?;
+    m.add_function(wrap_pyfunction!(filter_fasta, m)?)?;
+    m.add_function(wrap_pyfunction!(filter_df, m)?)?;
+    Ok(())
+}
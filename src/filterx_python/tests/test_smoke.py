@@ -0,0 +1,40 @@
+# Smoke test of the built wheel: python src/filterx_python/tests/test_smoke.py
+
+import os
+
+import polars as pl
+
+import pyfilterx
+
+TEST_DATA = os.path.join(os.path.dirname(__file__), "..", "..", "..", "test_data")
+
+
+def test_filter_df():
+    df = pl.DataFrame({"name": ["Alice", "Bob", "Charlie"], "age": [20, 30, 40]})
+    out = pyfilterx.filter_df(df, "age > 25; alias(old) = age > 35")
+    assert out["name"].to_list() == ["Bob", "Charlie"]
+    assert out["old"].to_list() == [False, True]
+
+
+def test_filter_fasta():
+    path = os.path.join(TEST_DATA, "fasta", "1.fa")
+    chunks = list(pyfilterx.filter_fasta(path, "len(seq) > 3", chunk_size=1))
+    assert len(chunks) > 0
+    assert all(chunk["seq"].str.len_chars().min() > 3 for chunk in chunks if chunk.height)
+
+
+def test_error():
+    df = pl.DataFrame({"a": [1, 2]})
+    try:
+        pyfilterx.filter_df(df, "b > 1")
+    except pyfilterx.FilterxError as e:
+        assert "b" in str(e)
+    else:
+        raise AssertionError("expected FilterxError")
+
+
+if __name__ == "__main__":
+    test_filter_df()
+    test_filter_fasta()
+    test_error()
+    print("ok")
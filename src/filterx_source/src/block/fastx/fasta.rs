@@ -5,7 +5,12 @@ use filterx_core::{
 };
 use memchr::memchr;
 use polars::prelude::*;
-use std::{collections::HashSet, io::BufRead};
+use std::{
+    collections::{HashSet, VecDeque},
+    io::BufRead,
+};
+
+use super::with_source_column;
 
 #[derive(Debug, Clone, Copy)]
 pub struct FastaParserOptions {
@@ -24,6 +29,11 @@ pub struct FastaSource {
     pub fasta: Fasta,
     pub records: Vec<FastaRecord>,
     pub dataframe: DataframeSource,
+    /// files to read after the current one
+    pub pending: VecDeque<String>,
+    /// add a column with the file name of every record
+    pub source_column: Option<String>,
+    n_detect: usize,
 }
 
 impl Drop for FastaSource {
@@ -50,9 +60,26 @@ impl FastaSource {
             fasta,
             records,
             dataframe,
+            pending: VecDeque::new(),
+            source_column: None,
+            n_detect,
         })
     }
 
+    /// Read `paths` in order after the first file.
+    pub fn set_next_paths(&mut self, paths: Vec<String>) {
+        self.pending = paths.into();
+    }
+
+    pub fn set_source_column(&mut self, name: Option<String>) {
+        self.source_column = name;
+    }
+
+    /// Switch to the next file, return false if all files are read.
+    pub fn next_file(&mut self) -> FilterxResult<bool> {
+        open_next_file(&mut self.fasta, &mut self.pending, self.n_detect)
+    }
+
     pub fn into_dataframe(&mut self, n: usize) -> FilterxResult<usize> {
         let records = &mut self.records;
         if records.capacity() < n {
@@ -67,15 +94,23 @@ impl FastaSource {
             records.set_len(n);
         }
         let mut count = 0;
-        while let Some(record) = self.fasta.parse_next()? {
-            let r = unsafe { records.get_unchecked_mut(count) };
-            r.clear();
-            r.buffer.extend_from_slice(&record.buffer);
-            r._name = record._name;
-            r._sequence = record._sequence;
-            r._comment = record._comment;
-            count += 1;
-            if count >= n {
+        // (index of the first record, path) of every file in this chunk
+        let mut files = vec![];
+        loop {
+            files.push((count, self.fasta.path.clone()));
+            while let Some(record) = self.fasta.parse_next()? {
+                let r = unsafe { records.get_unchecked_mut(count) };
+                r.clear();
+                r.buffer.extend_from_slice(&record.buffer);
+                r._name = record._name;
+                r._sequence = record._sequence;
+                r._comment = record._comment;
+                count += 1;
+                if count >= n {
+                    break;
+                }
+            }
+            if count >= n || !open_next_file(&mut self.fasta, &mut self.pending, self.n_detect)? {
                 break;
             }
         }
@@ -85,7 +120,10 @@ impl FastaSource {
         if records.is_empty() {
             return Ok(0);
         }
-        let df = Fasta::as_dataframe(&records, &self.fasta.parser_options)?;
+        let mut df = Fasta::as_dataframe(records, &self.fasta.parser_options)?;
+        if let Some(name) = &self.source_column {
+            with_source_column(&mut df, name, &files)?;
+        }
         self.dataframe.update(df.lazy());
         Ok(count)
     }
@@ -95,6 +133,21 @@ impl FastaSource {
     }
 }
 
+fn open_next_file(
+    fasta: &mut Fasta,
+    pending: &mut VecDeque<String>,
+    n_detect: usize,
+) -> FilterxResult<bool> {
+    let path = match pending.pop_front() {
+        Some(path) => path,
+        None => return Ok(false),
+    };
+    let parser_options = fasta.parser_options;
+    *fasta =
+        Fasta::from_path(&path, fasta.record_type, n_detect)?.set_parser_options(parser_options);
+    Ok(true)
+}
+
 pub struct Fasta {
     reader: FilterxReader,
     read_end: bool,
@@ -275,31 +328,35 @@ impl Fasta {
         }
         if hashset.len() < 4 {
             let mut h = Hint::new();
-            h.white("Too less sequences are used to detect alphabet. Try increase the number of sequences to detect alphabet.")
-                .print_and_exit();
+            return h.white("Too less sequences are used to detect alphabet. Try increase the number of sequences to detect alphabet.")
+                .bail();
         }
         if hashset.len() > 4 {
             self.record_type = FastaRecordType::Protein;
+            self.reset()?;
+            return Ok(());
         }
         let contain_t = hashset.contains(&b'T') || hashset.contains(&b't');
         let contain_u = hashset.contains(&b'u') || hashset.contains(&b'U');
         if contain_t && contain_u {
             let mut h = Hint::new();
-            h.white("The fasta file contains both ")
+            return h
+                .white("The fasta file contains both ")
                 .cyan("'T'")
                 .white(" and ")
                 .cyan("'U'")
                 .white(" nucleotides. Can not determine the record type.")
-                .print_and_exit();
+                .bail();
         }
         if !contain_t && !contain_u {
             let mut h = Hint::new();
-            h.white("The fasta file contains none of ")
+            return h
+                .white("The fasta file contains none of ")
                 .cyan("'T'")
                 .white(" and ")
                 .cyan("'U'")
                 .white(" nucleotides. Can not determine the record type.")
-                .print_and_exit();
+                .bail();
         }
         if contain_t {
             self.record_type = FastaRecordType::Dna;
@@ -358,7 +415,7 @@ impl Fasta {
                     .bold()
                     .white(" command instead.");
             }
-            h.print_and_exit();
+            return h.bail();
         }
 
         let break_line_len = self.break_line_len.unwrap();
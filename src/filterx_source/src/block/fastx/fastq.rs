@@ -1,5 +1,7 @@
 use polars::prelude::*;
-use std::{fmt::Display, io::BufRead};
+use std::{collections::VecDeque, fmt::Display, io::BufRead};
+
+use super::with_source_column;
 
 use crate::dataframe::DataframeSource;
 
@@ -12,8 +14,18 @@ pub struct FastqSource {
     pub fastq: Fastq,
     pub records: Vec<FastqRecord>,
     pub dataframe: DataframeSource,
+    /// files to read after the current one
+    pub pending: VecDeque<String>,
+    /// add a column with the file name of every record
+    pub source_column: Option<String>,
+    /// R2 of paired reads
+    pub mate: Option<Box<FastqSource>>,
+    detect_size: usize,
 }
 
+/// Suffix of the columns of R2 in paired mode, e.g. `seq2`.
+pub const MATE_SUFFIX: &str = "2";
+
 impl Drop for FastqSource {
     fn drop(&mut self) {
         unsafe {
@@ -43,9 +55,53 @@ impl FastqSource {
             fastq,
             records,
             dataframe,
+            pending: VecDeque::new(),
+            source_column: None,
+            mate: None,
+            detect_size,
         })
     }
 
+    /// Read R2 of paired reads from `mate` along with R1, one row per pair.
+    pub fn set_mate(&mut self, mate: FastqSource) {
+        self.mate = Some(Box::new(mate));
+    }
+
+    /// The next `n` records of R2, the columns are suffixed with `2`.
+    fn mate_dataframe(&mut self, n: usize) -> FilterxResult<Option<DataFrame>> {
+        // R2 should have no records left once R1 is read
+        let count = self.into_dataframe(n.max(1))?;
+        if count != n {
+            let more = if count > n { "more" } else { "fewer" };
+            return Err(FilterxError::FastqError(format!(
+                "paired reads: R2 file {} has {} reads than R1",
+                self.fastq.path, more
+            )));
+        }
+        if n == 0 {
+            return Ok(None);
+        }
+        let mut df = self.dataframe.into_df()?;
+        for name in df.get_column_names_owned() {
+            df.rename(&name, format!("{}{}", name, MATE_SUFFIX).into())?;
+        }
+        Ok(Some(df))
+    }
+
+    /// Read `paths` in order after the first file.
+    pub fn set_next_paths(&mut self, paths: Vec<String>) {
+        self.pending = paths.into();
+    }
+
+    pub fn set_source_column(&mut self, name: Option<String>) {
+        self.source_column = name;
+    }
+
+    /// Switch to the next file, return false if all files are read.
+    pub fn next_file(&mut self) -> FilterxResult<bool> {
+        open_next_file(&mut self.fastq, &mut self.pending, self.detect_size)
+    }
+
     pub fn into_dataframe(&mut self, n: usize) -> FilterxResult<usize> {
         let records = &mut self.records;
 
@@ -61,26 +117,46 @@ impl FastqSource {
             records.set_len(n);
         }
         let mut count = 0;
-        while let Some(record) = self.fastq.parse_next()? {
-            let r = unsafe { records.get_unchecked_mut(count) };
-            r.clear();
-            r.buffer.extend_from_slice(&record.buffer);
-            r._name = record._name;
-            r._comment = record._comment;
-            r._sequence = record._sequence;
-            r._qual = record._qual;
-            count += 1;
-            if count >= n {
+        // (index of the first record, path) of every file in this chunk
+        let mut files = vec![];
+        loop {
+            files.push((count, self.fastq.path.clone()));
+            while let Some(record) = self.fastq.parse_next()? {
+                let r = unsafe { records.get_unchecked_mut(count) };
+                r.clear();
+                r.buffer.extend_from_slice(&record.buffer);
+                r._name = record._name;
+                r._comment = record._comment;
+                r._sequence = record._sequence;
+                r._qual = record._qual;
+                count += 1;
+                if count >= n {
+                    break;
+                }
+            }
+            if count >= n || !open_next_file(&mut self.fastq, &mut self.pending, self.detect_size)?
+            {
                 break;
             }
         }
         unsafe {
             records.set_len(count);
         }
+        let mate = match self.mate.as_mut() {
+            Some(mate) => mate.mate_dataframe(count)?,
+            None => None,
+        };
+        let records = &self.records;
         if records.is_empty() {
             Ok(0)
         } else {
-            let df = Fastq::as_dataframe(&records, &self.fastq.parser_option)?;
+            let mut df = Fastq::as_dataframe(records, &self.fastq.parser_option)?;
+            if let Some(name) = &self.source_column {
+                with_source_column(&mut df, name, &files)?;
+            }
+            if let Some(mate) = mate {
+                df.hstack_mut(mate.get_columns())?;
+            }
             self.dataframe.update(df.lazy());
             Ok(count)
         }
@@ -131,6 +207,21 @@ impl Default for QualityType {
     }
 }
 
+fn open_next_file(
+    fastq: &mut Fastq,
+    pending: &mut VecDeque<String>,
+    detect_size: usize,
+) -> FilterxResult<bool> {
+    let path = match pending.pop_front() {
+        Some(path) => path,
+        None => return Ok(false),
+    };
+    let parser_option = fastq.parser_option;
+    *fastq =
+        Fastq::from_path(&path, fastq.quality_type, detect_size)?.set_parser_options(parser_option);
+    Ok(true)
+}
+
 pub struct Fastq {
     reader: FilterxReader,
     read_end: bool,
@@ -393,7 +484,7 @@ impl Fastq {
                     .bold()
                     .white(" command instead.");
             }
-            h.print_and_exit();
+            return h.bail();
         }
 
         let break_line_len = self.break_line_len.unwrap();
@@ -564,3 +655,49 @@ impl Fastq {
         Ok(())
     }
 }
+
+#[test]
+fn test_mate() {
+    let dir = std::env::temp_dir().join("filterx_test_mate");
+    std::fs::create_dir_all(&dir).unwrap();
+    let write = |name: &str, n: usize| {
+        let reads: String = (0..n)
+            .map(|i| format!("@r{}\nACGT\n+\nIIII\n", i))
+            .collect();
+        let path = dir.join(name).to_str().unwrap().to_string();
+        std::fs::write(&path, reads).unwrap();
+        path
+    };
+    let r1 = write("r1.fq", 3);
+    let source = |r2: &str| {
+        let mut source = FastqSource::new(&r1, true, true, QualityType::Phred33, 0).unwrap();
+        let mate = FastqSource::new(r2, true, true, QualityType::Phred33, 0).unwrap();
+        source.set_mate(mate);
+        source
+    };
+    let column = |source: &FastqSource, name: &str| -> Vec<String> {
+        let df = source.dataframe.into_df().unwrap();
+        let ca = df.column(name).unwrap().str().unwrap().clone();
+        ca.into_iter().map(|v| v.unwrap().to_string()).collect()
+    };
+
+    let mut paired = source(&write("r2.fq", 3));
+    assert_eq!(paired.into_dataframe(2).unwrap(), 2);
+    assert_eq!(column(&paired, "name"), vec!["r0", "r1"]);
+    assert_eq!(column(&paired, "name2"), vec!["r0", "r1"]);
+    assert_eq!(paired.into_dataframe(2).unwrap(), 1);
+    assert_eq!(column(&paired, "seq2"), vec!["ACGT"]);
+    assert_eq!(paired.into_dataframe(2).unwrap(), 0);
+
+    // R2 has fewer reads
+    let mut paired = source(&write("r2_short.fq", 2));
+    assert_eq!(paired.into_dataframe(2).unwrap(), 2);
+    assert!(paired.into_dataframe(2).is_err());
+
+    // R2 has more reads, found once R1 is read
+    let mut paired = source(&write("r2_long.fq", 4));
+    assert_eq!(paired.into_dataframe(2).unwrap(), 2);
+    assert_eq!(paired.into_dataframe(2).unwrap(), 1);
+    assert!(paired.into_dataframe(2).is_err());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -1,2 +1,22 @@
 pub mod fasta;
 pub mod fastq;
+
+use filterx_core::FilterxResult;
+use polars::prelude::*;
+
+/// Add a column with the file name of every record, `files` is the index of
+/// the first record and the path of every file in the chunk.
+pub(crate) fn with_source_column(
+    df: &mut DataFrame,
+    name: &str,
+    files: &[(usize, String)],
+) -> FilterxResult<()> {
+    let rows = df.height();
+    let mut values = Vec::with_capacity(rows);
+    for (i, (start, path)) in files.iter().enumerate() {
+        let end = files.get(i + 1).map(|f| f.0).unwrap_or(rows);
+        values.extend(std::iter::repeat_n(path.as_str(), end - start));
+    }
+    df.with_column(Column::new(name.into(), values))?;
+    Ok(())
+}
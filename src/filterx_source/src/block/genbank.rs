@@ -0,0 +1,565 @@
+use std::collections::VecDeque;
+use std::io::BufRead;
+
+use polars::prelude::*;
+
+use crate::dataframe::DataframeSource;
+use filterx_core::{
+    reader::FilterxReader,
+    seq::{reverse_complement, Complement},
+    FilterxResult, Hint,
+};
+
+/// Column of qualifiers starts at 21 in both GenBank and EMBL feature tables.
+const QUALIFIER_COLUMN: usize = 21;
+
+pub struct GenBankSource {
+    pub genbank: GenBank,
+    pub records: Vec<GenBankRecord>,
+    pub dataframe: DataframeSource,
+    /// output one row per feature instead of one row per record
+    pub features: bool,
+    /// files to read after the current one
+    pub pending: VecDeque<String>,
+    /// add a column with the file name of every row
+    pub source_column: Option<String>,
+}
+
+impl GenBankSource {
+    pub fn new(path: &str, features: bool) -> FilterxResult<Self> {
+        Ok(GenBankSource {
+            genbank: GenBank::from_path(path)?,
+            records: Vec::new(),
+            dataframe: DataframeSource::new(DataFrame::empty().lazy()),
+            features,
+            pending: VecDeque::new(),
+            source_column: None,
+        })
+    }
+
+    /// Read `paths` in order after the first file.
+    pub fn set_next_paths(&mut self, paths: Vec<String>) {
+        self.pending = paths.into();
+    }
+
+    pub fn set_source_column(&mut self, name: Option<String>) {
+        self.source_column = name;
+    }
+
+    /// Switch to the next file, return false if all files are read.
+    pub fn next_file(&mut self) -> FilterxResult<bool> {
+        match self.pending.pop_front() {
+            Some(path) => {
+                self.genbank = GenBank::from_path(&path)?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    pub fn column_names(features: bool) -> Vec<&'static str> {
+        if features {
+            vec![
+                "name",
+                "type",
+                "start",
+                "end",
+                "strand",
+                "location",
+                "gene",
+                "product",
+                "locus_tag",
+                "qualifiers",
+                "seq",
+            ]
+        } else {
+            vec!["name", "desc", "seq", "length", "organism", "taxid"]
+        }
+    }
+
+    pub fn into_dataframe(&mut self, n: usize) -> FilterxResult<usize> {
+        self.records.clear();
+        // (index of the first record, path) of every file in this chunk
+        let mut files = vec![];
+        loop {
+            files.push((self.records.len(), self.genbank.path.clone()));
+            while let Some(record) = self.genbank.parse_next()? {
+                self.records.push(record);
+                if self.records.len() >= n {
+                    break;
+                }
+            }
+            if self.records.len() >= n || !self.next_file()? {
+                break;
+            }
+        }
+        let count = self.records.len();
+        if count == 0 {
+            return Ok(0);
+        }
+        let mut df = if self.features {
+            GenBank::features_as_dataframe(&self.records, &files, &self.source_column)?
+        } else {
+            GenBank::as_dataframe(&self.records)?
+        };
+        if let (Some(name), false) = (&self.source_column, self.features) {
+            crate::block::fastx::with_source_column(&mut df, name, &files)?;
+        }
+        self.dataframe.update(df.lazy());
+        Ok(count)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FlatFileFormat {
+    GenBank,
+    Embl,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Feature {
+    pub kind: String,
+    pub location: String,
+    pub qualifiers: Vec<(String, String)>,
+}
+
+impl Feature {
+    pub fn qualifier(&self, key: &str) -> Option<&str> {
+        self.qualifiers
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// 1-based (start, end, reverse) parts of the location, e.g.
+    /// `join(1..10,complement(20..30))`, a part is reverse if it is inside
+    /// `complement(...)`.
+    fn parts(&self) -> Vec<(usize, usize, bool)> {
+        let outer = self.location.starts_with("complement(");
+        let mut parts = vec![];
+        for part in self.location.split(',') {
+            let numbers: Vec<usize> = part
+                .split(|c: char| !c.is_ascii_digit())
+                .filter(|s| !s.is_empty())
+                .filter_map(|s| s.parse().ok())
+                .collect();
+            // remote references like `J00194.1:100..202` are skipped
+            if part.contains(':') || numbers.is_empty() {
+                continue;
+            }
+            let start = numbers[0];
+            let end = if part.contains("..") && numbers.len() > 1 {
+                numbers[1]
+            } else {
+                start
+            };
+            let reverse = outer || part.contains("complement(");
+            parts.push((start.min(end), start.max(end), reverse));
+        }
+        parts
+    }
+
+    /// 1-based (start, end) ranges of the location, e.g. `join(1..10,20..30)`
+    pub fn ranges(&self) -> Vec<(usize, usize)> {
+        self.parts()
+            .into_iter()
+            .map(|(start, end, _)| (start, end))
+            .collect()
+    }
+
+    /// On the minus strand, e.g. `complement(1..10)` or
+    /// `join(complement(20..30),complement(1..10))`.
+    pub fn is_reverse(&self) -> bool {
+        if self.location.starts_with("complement(") {
+            return true;
+        }
+        let parts = self.parts();
+        !parts.is_empty() && parts.iter().all(|&(_, _, reverse)| reverse)
+    }
+
+    /// Sequence of the feature, parts on the minus strand are reverse
+    /// complemented.
+    pub fn extract(&self, seq: &str) -> String {
+        let seq = seq.as_bytes();
+        // `complement(join(...))` is the reverse complement of all parts
+        let outer = self.location.starts_with("complement(");
+        let mut s = Vec::new();
+        for (start, end, reverse) in self.parts() {
+            if start == 0 || start > seq.len() {
+                continue;
+            }
+            let part = &seq[start - 1..end.min(seq.len())];
+            match reverse && !outer {
+                true => s.extend(reverse_complement(part, Complement::Dna)),
+                false => s.extend_from_slice(part),
+            }
+        }
+        if outer {
+            s = reverse_complement(&s, Complement::Dna);
+        }
+        String::from_utf8(s).unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct GenBankRecord {
+    pub name: String,
+    pub desc: String,
+    pub organism: String,
+    pub seq: String,
+    pub features: Vec<Feature>,
+}
+
+impl GenBankRecord {
+    pub fn taxid(&self) -> Option<&str> {
+        self.features
+            .iter()
+            .filter(|f| f.kind == "source")
+            .flat_map(|f| f.qualifiers.iter())
+            .find(|(k, v)| k == "db_xref" && v.starts_with("taxon:"))
+            .map(|(_, v)| &v["taxon:".len()..])
+    }
+}
+
+pub struct GenBank {
+    reader: FilterxReader,
+    pub path: String,
+    line: String,
+    line_no: usize,
+}
+
+fn append_text(s: &mut String, text: &str) {
+    let text = text.trim();
+    if text.is_empty() {
+        return;
+    }
+    if !s.is_empty() {
+        s.push(' ');
+    }
+    s.push_str(text);
+}
+
+impl GenBank {
+    pub fn from_path(path: &str) -> FilterxResult<GenBank> {
+        Ok(GenBank {
+            reader: FilterxReader::new(path)?,
+            path: path.to_string(),
+            line: String::new(),
+            line_no: 0,
+        })
+    }
+
+    fn read_line(&mut self) -> FilterxResult<bool> {
+        self.line.clear();
+        let bytes = self.reader.read_line(&mut self.line)?;
+        if bytes == 0 {
+            return Ok(false);
+        }
+        self.line_no += 1;
+        let len = self.line.trim_end_matches(['\r', '\n']).len();
+        self.line.truncate(len);
+        Ok(true)
+    }
+
+    fn invalid<T>(&self, expect: &str) -> FilterxResult<T> {
+        let mut h = Hint::new();
+        h.white("Invalid GenBank/EMBL format in ")
+            .cyan(&self.path)
+            .white(&format!(" at line {}. Expecting ", self.line_no))
+            .cyan(expect)
+            .bold()
+            .white(", but got: ")
+            .cyan(&self.line)
+            .bail()
+    }
+
+    pub fn parse_next(&mut self) -> FilterxResult<Option<GenBankRecord>> {
+        // skip blank lines between records
+        loop {
+            if !self.read_line()? {
+                return Ok(None);
+            }
+            if !self.line.trim().is_empty() {
+                break;
+            }
+        }
+        let format = if self.line.starts_with("LOCUS") {
+            FlatFileFormat::GenBank
+        } else if self.line.starts_with("ID ") {
+            FlatFileFormat::Embl
+        } else {
+            return self.invalid("LOCUS or ID");
+        };
+
+        let name = match self.line.get(5..) {
+            Some(name) => name,
+            None => return self.invalid("ID   <name>;"),
+        };
+        let name = name
+            .split_whitespace()
+            .next()
+            .unwrap_or("")
+            .trim_end_matches(';');
+        let mut record = GenBankRecord {
+            name: name.to_string(),
+            ..Default::default()
+        };
+
+        // the current GenBank keyword, continuation lines start with spaces
+        let mut keyword = String::new();
+        let mut in_sequence = false;
+        loop {
+            if !self.read_line()? {
+                return self.invalid("//");
+            }
+            let line = self.line.as_str();
+            if line.starts_with("//") {
+                break;
+            }
+            if in_sequence {
+                record
+                    .seq
+                    .extend(line.chars().filter(|c| c.is_ascii_alphabetic()));
+                continue;
+            }
+            let (key, value) = match format {
+                FlatFileFormat::GenBank => {
+                    // keywords start at column 0, sub keywords e.g. `  ORGANISM` at column 2
+                    let bytes = line.as_bytes();
+                    if !line.starts_with(' ') || (bytes.len() > 2 && bytes[2] != b' ') {
+                        keyword = line.split_whitespace().next().unwrap_or("").to_string();
+                    }
+                    (keyword.as_str(), line.get(12..).unwrap_or(""))
+                }
+                FlatFileFormat::Embl => (line.get(..2).unwrap_or(""), line.get(5..).unwrap_or("")),
+            };
+            match key {
+                "DEFINITION" | "DE" => append_text(&mut record.desc, value),
+                // the lineage follows the organism name in GenBank
+                "ORGANISM" if line.starts_with("  ORGANISM") => {
+                    append_text(&mut record.organism, value)
+                }
+                "OS" => append_text(&mut record.organism, value),
+                "FEATURES" if line.starts_with(' ') => {
+                    Self::parse_feature_line(&mut record.features, line)
+                }
+                "FT" => Self::parse_feature_line(&mut record.features, line),
+                "ORIGIN" | "SQ" => in_sequence = true,
+                _ => {}
+            }
+        }
+        record.seq.make_ascii_uppercase();
+        Ok(Some(record))
+    }
+
+    fn parse_feature_line(features: &mut Vec<Feature>, line: &str) {
+        let key = line.get(5..QUALIFIER_COLUMN).unwrap_or("").trim();
+        let value = line.get(QUALIFIER_COLUMN..).unwrap_or("").trim();
+        if !key.is_empty() {
+            features.push(Feature {
+                kind: key.to_string(),
+                location: value.to_string(),
+                qualifiers: vec![],
+            });
+            return;
+        }
+        let feature = match features.last_mut() {
+            Some(feature) => feature,
+            None => return,
+        };
+        if let Some(qualifier) = value.strip_prefix('/') {
+            let (k, v) = match qualifier.split_once('=') {
+                Some((k, v)) => (k, v),
+                None => (qualifier, ""),
+            };
+            feature
+                .qualifiers
+                .push((k.to_string(), v.trim_matches('"').to_string()));
+        } else if let Some((k, v)) = feature.qualifiers.last_mut() {
+            // protein sequences are wrapped without spaces
+            if k.as_str() != "translation" && !v.is_empty() {
+                v.push(' ');
+            }
+            v.push_str(value.trim_matches('"'));
+        } else {
+            // location continues on the next line
+            feature.location.push_str(value);
+        }
+    }
+
+    pub fn as_dataframe(records: &[GenBankRecord]) -> FilterxResult<DataFrame> {
+        let names: Vec<&str> = records.iter().map(|r| r.name.as_str()).collect();
+        let desc: Vec<&str> = records.iter().map(|r| r.desc.as_str()).collect();
+        let seq: Vec<&str> = records.iter().map(|r| r.seq.as_str()).collect();
+        let length: Vec<u32> = records.iter().map(|r| r.seq.len() as u32).collect();
+        let organism: Vec<&str> = records.iter().map(|r| r.organism.as_str()).collect();
+        let taxid: Vec<Option<&str>> = records.iter().map(|r| r.taxid()).collect();
+        let df = DataFrame::new(vec![
+            Column::new("name".into(), names),
+            Column::new("desc".into(), desc),
+            Column::new("seq".into(), seq),
+            Column::new("length".into(), length),
+            Column::new("organism".into(), organism),
+            Column::new("taxid".into(), taxid),
+        ])?;
+        Ok(df)
+    }
+
+    pub fn features_as_dataframe(
+        records: &[GenBankRecord],
+        files: &[(usize, String)],
+        source_column: &Option<String>,
+    ) -> FilterxResult<DataFrame> {
+        let rows: usize = records.iter().map(|r| r.features.len()).sum();
+        let mut names = Vec::with_capacity(rows);
+        let mut kinds = Vec::with_capacity(rows);
+        let mut starts: Vec<Option<u32>> = Vec::with_capacity(rows);
+        let mut ends: Vec<Option<u32>> = Vec::with_capacity(rows);
+        let mut strands = Vec::with_capacity(rows);
+        let mut locations = Vec::with_capacity(rows);
+        let mut genes = Vec::with_capacity(rows);
+        let mut products = Vec::with_capacity(rows);
+        let mut locus_tags = Vec::with_capacity(rows);
+        let mut qualifiers = Vec::with_capacity(rows);
+        let mut seqs = Vec::with_capacity(rows);
+        let mut sources = Vec::with_capacity(rows);
+        for (i, record) in records.iter().enumerate() {
+            let file = files
+                .iter()
+                .rev()
+                .find(|(start, _)| *start <= i)
+                .map(|(_, path)| path.as_str())
+                .unwrap_or("");
+            for feature in &record.features {
+                let ranges = feature.ranges();
+                names.push(record.name.as_str());
+                kinds.push(feature.kind.as_str());
+                starts.push(ranges.iter().map(|r| r.0 as u32).min());
+                ends.push(ranges.iter().map(|r| r.1 as u32).max());
+                strands.push(if feature.is_reverse() { "-" } else { "+" });
+                locations.push(feature.location.as_str());
+                genes.push(feature.qualifier("gene"));
+                products.push(feature.qualifier("product"));
+                locus_tags.push(feature.qualifier("locus_tag"));
+                qualifiers.push(
+                    feature
+                        .qualifiers
+                        .iter()
+                        .map(|(k, v)| format!("{}={}", k, v))
+                        .collect::<Vec<String>>()
+                        .join(";"),
+                );
+                seqs.push(feature.extract(&record.seq));
+                sources.push(file);
+            }
+        }
+        let mut cols = vec![
+            Column::new("name".into(), names),
+            Column::new("type".into(), kinds),
+            Column::new("start".into(), starts),
+            Column::new("end".into(), ends),
+            Column::new("strand".into(), strands),
+            Column::new("location".into(), locations),
+            Column::new("gene".into(), genes),
+            Column::new("product".into(), products),
+            Column::new("locus_tag".into(), locus_tags),
+            Column::new("qualifiers".into(), qualifiers),
+            Column::new("seq".into(), seqs),
+        ];
+        if let Some(name) = source_column {
+            cols.push(Column::new(name.into(), sources));
+        }
+        Ok(DataFrame::new(cols)?)
+    }
+}
+
+#[cfg(test)]
+fn test_data(name: &str) -> String {
+    format!(
+        "{}/../../test_data/genbank/{}",
+        env!("CARGO_MANIFEST_DIR"),
+        name
+    )
+}
+
+#[test]
+fn test_genbank() {
+    let mut gb = GenBank::from_path(&test_data("test.gb")).unwrap();
+    let record = gb.parse_next().unwrap().unwrap();
+    assert_eq!(record.name, "SEQ00001");
+    assert_eq!(
+        record.desc,
+        "Escherichia coli test sequence one, complete sequence."
+    );
+    assert_eq!(record.organism, "Escherichia coli");
+    assert_eq!(record.seq.len(), 60);
+    assert!(record.seq.starts_with("ATGAAAAAAC"));
+    assert_eq!(record.taxid(), Some("562"));
+    let kinds: Vec<&str> = record.features.iter().map(|f| f.kind.as_str()).collect();
+    assert_eq!(kinds, vec!["source", "gene", "CDS", "gene"]);
+    let cds = &record.features[2];
+    assert_eq!(
+        cds.qualifier("product"),
+        Some("hypothetical protein with a long name")
+    );
+    assert_eq!(cds.qualifier("translation"), Some("MKKLLPTAAAGLLL"));
+    assert_eq!(cds.ranges(), vec![(1, 30)]);
+    let gene = &record.features[3];
+    assert!(gene.is_reverse());
+    assert_eq!(gene.extract(&record.seq), "CGCGCGCGCGGATCCTTACA");
+
+    let record = gb.parse_next().unwrap().unwrap();
+    assert_eq!(record.name, "SEQ00002");
+    assert_eq!(record.taxid(), Some("1423"));
+    let feature = &record.features[1];
+    assert_eq!(feature.ranges(), vec![(1, 5), (11, 15)]);
+    assert!(!feature.is_reverse());
+    assert_eq!(feature.extract(&record.seq), "AAAAACCCCG");
+    assert!(gb.parse_next().unwrap().is_none());
+}
+
+#[test]
+fn test_embl() {
+    let mut embl = GenBank::from_path(&test_data("test.embl")).unwrap();
+    let record = embl.parse_next().unwrap().unwrap();
+    assert_eq!(record.name, "X56734");
+    assert_eq!(
+        record.desc,
+        "Trifolium repens mRNA for non-cyanogenic beta-glucosidase"
+    );
+    assert_eq!(record.organism, "Trifolium repens (white clover)");
+    assert_eq!(record.seq, "AAAATGGCGCTTACCGTAAGGTAA");
+    assert_eq!(record.taxid(), Some("3899"));
+    assert_eq!(record.features.len(), 2);
+    assert_eq!(record.features[1].ranges(), vec![(4, 21)]);
+    assert!(embl.parse_next().unwrap().is_none());
+}
+
+#[test]
+fn test_feature_strand() {
+    let seq = "AAAAATTTTTCCCCGGGGGG";
+    let feature = |location: &str| Feature {
+        location: location.to_string(),
+        ..Default::default()
+    };
+    let f = feature("join(complement(11..15),complement(1..5))");
+    assert!(f.is_reverse());
+    assert_eq!(f.extract(seq), "CGGGGTTTTT");
+    let f = feature("complement(join(1..5,11..15))");
+    assert!(f.is_reverse());
+    assert_eq!(f.extract(seq), "CGGGGTTTTT");
+    let f = feature("join(1..5,complement(11..15))");
+    assert!(!f.is_reverse());
+    assert_eq!(f.extract(seq), "AAAAACGGGG");
+    let f = feature("J00194.1:100..202");
+    assert!(f.ranges().is_empty() && !f.is_reverse());
+}
+
+#[test]
+fn test_invalid_header() {
+    let path = std::env::temp_dir().join("filterx_test_short_id.embl");
+    std::fs::write(&path, "ID\n//\n").unwrap();
+    let mut embl = GenBank::from_path(path.to_str().unwrap()).unwrap();
+    assert!(embl.parse_next().is_err());
+    std::fs::remove_file(&path).unwrap();
+}
@@ -1,4 +1,5 @@
 pub mod fastx;
+pub mod genbank;
 
 pub use fastx::fasta;
 pub use fastx::fastq;
@@ -11,7 +11,6 @@ pub struct DataframeSource {
     pub has_header: bool,
     pub init_column_names: Vec<String>,
     pub ret_column_names: Vec<String>,
-    pub is_in_and_ctx: bool,
 }
 
 pub fn detect_columns(df: LazyFrame) -> FilterxResult<Vec<String>> {
@@ -26,20 +25,11 @@ impl DataframeSource {
             has_header: true,
             init_column_names: vec![],
             ret_column_names: vec![],
-            is_in_and_ctx: false,
         }
     }
 }
 
 impl DataframeSource {
-    pub fn enter_and_ctx(&mut self) {
-        self.is_in_and_ctx = true;
-    }
-
-    pub fn exit_and_ctx(&mut self) {
-        self.is_in_and_ctx = false;
-    }
-
     pub fn reset(&mut self) {
         self.has_header = true;
         self.init_column_names.clear();
@@ -55,28 +45,30 @@ impl DataframeSource {
         self.has_header = has_header;
     }
 
-    pub fn index2column(&self, index: usize) -> String {
+    pub fn index2column(&self, index: usize) -> FilterxResult<String> {
         if self.has_header {
             if index < self.ret_column_names.len() {
-                return self.ret_column_names[index].clone();
+                return Ok(self.ret_column_names[index].clone());
             }
             let mut h = Hint::new();
-            h.white("Have ")
+            return h
+                .white("Have ")
                 .cyan(&format!("{}", self.ret_column_names.len()))
                 .white(" columns, but got index ")
                 .cyan(&format!("{}", index))
-                .print_and_exit();
+                .bail();
         }
-        format!("column_{}", index + 1)
+        Ok(format!("column_{}", index + 1))
     }
 
-    pub fn set_index_with_name(&mut self, index: usize, name: &str) {
+    pub fn set_index_with_name(&mut self, index: usize, name: &str) -> FilterxResult<()> {
         let lazy = self.lazy.clone();
-        let lazy = lazy.with_column(col(self.index2column(index)).alias(name));
+        let lazy = lazy.with_column(col(self.index2column(index)?).alias(name));
         self.update(lazy);
 
         assert!(self.init_column_names.len() > index);
         self.ret_column_names[index] = name.to_string();
+        Ok(())
     }
 
     pub fn into_df(&self) -> FilterxResult<DataFrame> {
@@ -120,9 +112,6 @@ impl DataframeSource {
     }
 
     pub fn filter(&mut self, expr: Expr) {
-        if self.is_in_and_ctx {
-            return;
-        }
         let lazy = self.lazy.clone();
         let lazy = lazy.filter(expr);
         self.update(lazy);
@@ -159,20 +148,22 @@ impl DataframeSource {
         self.update(lazy);
     }
 
-    pub fn rename(&mut self, old: &str, new: &str) {
+    pub fn rename(&mut self, old: &str, new: &str) -> FilterxResult<()> {
         if !self.check_column(old) {
             let mut h = Hint::new();
-            h.white("Column ")
+            return h
+                .white("Column ")
                 .cyan(old)
                 .white(" not found in the DataFrame")
-                .print_and_exit();
+                .bail();
         }
         if self.check_column(&new) {
             let mut h = Hint::new();
-            h.white("Column ")
+            return h
+                .white("Column ")
                 .cyan(new)
                 .white(" already exists in the DataFrame")
-                .print_and_exit();
+                .bail();
         }
         let idx = self.ret_column_names.iter().position(|x| x == old).unwrap();
         self.ret_column_names[idx] = new.to_string();
@@ -180,25 +171,46 @@ impl DataframeSource {
         let lazy = self.lazy.clone();
         let lazy = lazy.rename([old], [new], false);
         self.update(lazy);
+        Ok(())
     }
 
-    pub fn has_column(&self, name: &str) -> () {
+    pub fn has_column(&self, name: &str) -> FilterxResult<()> {
         let ret = self.check_column(name);
         if !ret {
-            let re = Regex::new(name).unwrap();
-            for c in &self.ret_column_names {
-                if re.is_match(c) {
-                    return;
+            // a name which is not a valid pattern, like `x[`, is just not found
+            if let Ok(re) = Regex::new(name) {
+                if self.ret_column_names.iter().any(|c| re.is_match(c)) {
+                    return Ok(());
                 }
             }
             let mut h = Hint::new();
             h.white("Column ")
                 .cyan(name)
                 .white(" not found. Valid columns: ")
-                .green(&self.ret_column_names.join(", "))
-                .print_and_exit();
+                .green(&self.ret_column_names.join(", "));
+            if let Some(similar) = self.similar_column(name) {
+                h.suggestion(similar);
+            }
+            return h.bail();
+        }
+        Ok(())
+    }
+
+    /// the most similar column name, used to suggest a fix for a typo
+    pub fn similar_column(&self, name: &str) -> Option<&str> {
+        let mut best_score = 0.0;
+        let mut best_name = None;
+        for c in &self.ret_column_names {
+            let score = strsim::jaro_winkler(name, c);
+            if score > best_score {
+                best_score = score;
+                best_name = Some(c.as_str());
+            }
         }
-        ()
+        if best_score >= 0.8 {
+            return best_name;
+        }
+        None
     }
 
     pub fn check_column(&self, name: &str) -> bool {
@@ -206,3 +218,14 @@ impl DataframeSource {
         i.is_some()
     }
 }
+
+#[test]
+fn test_has_column() {
+    let df = df!("abc" => [1], "x" => [2]).unwrap();
+    let mut source = DataframeSource::new(df.lazy());
+    source.set_init_column_names(&vec!["abc".to_string(), "x".to_string()]);
+    assert!(source.has_column("x").is_ok());
+    assert!(source.has_column("^a").is_ok());
+    assert!(source.has_column("y").is_err());
+    assert!(source.has_column("x[").is_err());
+}
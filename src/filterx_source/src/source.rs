@@ -1,5 +1,6 @@
 use crate::block::fasta::{Fasta, FastaSource};
 use crate::block::fastq::{Fastq, FastqSource};
+use crate::block::genbank::GenBankSource;
 use crate::DataframeSource;
 
 use filterx_core::{FilterxError, FilterxResult};
@@ -14,6 +15,7 @@ pub enum SourceType {
     Sam,
     Gff,
     Gtf,
+    GenBank,
 }
 
 impl SourceType {
@@ -53,6 +55,9 @@ impl SourceType {
             _ => false,
         }
     }
+    pub fn is_genbank(&self) -> bool {
+        matches!(self, SourceType::GenBank)
+    }
 }
 
 impl Into<&str> for SourceType {
@@ -65,6 +70,7 @@ impl Into<&str> for SourceType {
             SourceType::Sam => "sam",
             SourceType::Gff => "gff",
             SourceType::Gtf => "gtf",
+            SourceType::GenBank => "genbank",
         }
     }
 }
@@ -79,6 +85,7 @@ impl From<&str> for SourceType {
             "sam" => SourceType::Sam,
             "gff" => SourceType::Gff,
             "gtf" => SourceType::Gtf,
+            "genbank" => SourceType::GenBank,
             _ => panic!("Invalid source type"),
         }
     }
@@ -88,6 +95,7 @@ pub enum SourceInner {
     DataFrame(DataframeSource),
     Fasta(FastaSource),
     Fastq(FastqSource),
+    GenBank(GenBankSource),
 }
 
 impl From<DataframeSource> for SourceInner {
@@ -108,6 +116,12 @@ impl From<FastqSource> for SourceInner {
     }
 }
 
+impl From<GenBankSource> for SourceInner {
+    fn from(genbank: GenBankSource) -> Self {
+        SourceInner::GenBank(genbank)
+    }
+}
+
 pub struct Source {
     pub source_type: SourceType,
     pub inner: SourceInner,
@@ -125,6 +139,7 @@ impl Source {
             SourceInner::DataFrame(df) => df,
             SourceInner::Fasta(fasta) => &mut fasta.dataframe,
             SourceInner::Fastq(fastq) => &mut fastq.dataframe,
+            SourceInner::GenBank(genbank) => &mut genbank.dataframe,
         }
     }
 
@@ -133,6 +148,7 @@ impl Source {
             SourceInner::DataFrame(df) => df,
             SourceInner::Fasta(fasta) => &fasta.dataframe,
             SourceInner::Fastq(fastq) => &fastq.dataframe,
+            SourceInner::GenBank(genbank) => &genbank.dataframe,
         }
     }
 